@@ -0,0 +1,50 @@
+use std::f32::consts::TAU;
+
+/// Ticks in one full day and night.
+pub const DAY_LENGTH: u64 = 7200;
+/// The world starts at seven in the morning rather than at midnight.
+const START_OF_DAY: u64 = DAY_LENGTH * 7 / 24;
+/// Ticks in one minute of play at the usual sixty frames a second.
+pub const TICKS_PER_MINUTE: u64 = 3600;
+/// Daylight never drops below this, so moonless nights are dark but not pitch black.
+pub const NIGHT_LIGHT: f32 = 0.15;
+
+/// Counts simulation ticks; every frame advances the world by one tick.
+#[derive(Default)]
+pub struct Clock {
+    pub tick: u64,
+}
+
+impl Clock {
+    pub fn advance(&mut self) {
+        self.tick += 1;
+    }
+
+    /// Whether something that acts every `interval` ticks should act on this tick.
+    pub fn every(&self, interval: u64) -> bool {
+        self.tick.is_multiple_of(interval.max(1))
+    }
+
+    /// The current day, starting at 1.
+    pub fn day(&self) -> u64 {
+        (self.tick + START_OF_DAY) / DAY_LENGTH + 1
+    }
+
+    /// How far the current day has gone, from 0 at midnight to just under 1.
+    pub fn time_of_day(&self) -> f32 {
+        ((self.tick + START_OF_DAY) % DAY_LENGTH) as f32 / DAY_LENGTH as f32
+    }
+
+    pub fn hour_and_minute(&self) -> (u32, u32) {
+        let minutes = (self.time_of_day() * 24. * 60.) as u32;
+
+        (minutes / 60, minutes % 60)
+    }
+
+    /// How bright the sun is, from `NIGHT_LIGHT` at night to 1 for most of the day.
+    pub fn daylight(&self) -> f32 {
+        let sun = -(self.time_of_day() * TAU).cos();
+
+        ((sun + 0.3) * 1.5).clamp(NIGHT_LIGHT, 1.)
+    }
+}
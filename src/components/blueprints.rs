@@ -0,0 +1,439 @@
+use std::env;
+use std::fs::{read_dir, read_to_string};
+use std::path::PathBuf;
+
+use knuffel::Decode;
+
+use crate::components::structures::{Direction, Side};
+use crate::spawner::is_structure_name;
+use crate::{Component, DenseVecStorage};
+
+/// Starts every blueprint text string, so other text is not mistaken for one.
+const TEXT_PREFIX: &str = "bp1";
+
+/// A structure to be built where it stands, with the rotation and configuration it will get.
+/// The player builds ghosts within reach from their backpack.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Ghost {
+    pub structure: String,
+    pub direction: Direction,
+    pub recipe: Option<String>,
+    pub filter: Option<String>,
+    /// The side a splitter or merger favours.
+    pub priority: Option<Side>,
+}
+
+/// One structure of a blueprint, placed relative to the top left corner of the blueprint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlueprintEntry {
+    pub x: i32,
+    pub y: i32,
+    pub ghost: Ghost,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blueprint {
+    pub name: String,
+    pub width: i32,
+    pub height: i32,
+    pub entries: Vec<BlueprintEntry>,
+}
+
+/// Blueprints the player copied or imported, in the order they were added.
+#[derive(Default)]
+pub struct BlueprintLibrary {
+    pub blueprints: Vec<Blueprint>,
+}
+
+impl BlueprintLibrary {
+    /// Adds `blueprint` unless one with the same name is already in the library.
+    pub fn add(&mut self, blueprint: Blueprint) -> bool {
+        if self.find(&blueprint.name).is_some() {
+            return false;
+        }
+
+        self.blueprints.push(blueprint);
+        true
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Blueprint> {
+        self.blueprints
+            .iter()
+            .find(|blueprint| blueprint.name == name)
+    }
+
+    /// The first free name of the form `blueprint N`.
+    pub fn next_name(&self) -> String {
+        (1..)
+            .map(|number| format!("blueprint {}", number))
+            .find(|name| self.find(name).is_none())
+            .unwrap()
+    }
+}
+
+/// Where blueprints are exported to and imported from, next to the game.
+pub fn blueprint_directory() -> PathBuf {
+    let mut path =
+        env::current_exe().expect("could not load current directory for blueprint loading");
+    path.pop(); // removes the binary
+    path.push("blueprints");
+
+    path
+}
+
+/// Reads every blueprint in the `.kdl` files and the lines of the `.txt` files of `directory`.
+/// Files that do not parse are reported and skipped.
+pub fn load_blueprints(directory: &PathBuf) -> (Vec<Blueprint>, Vec<String>) {
+    let mut blueprints = vec![];
+    let mut errors = vec![];
+
+    let mut paths: Vec<PathBuf> = match read_dir(directory) {
+        Err(_) => return (blueprints, errors),
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect(),
+    };
+    paths.sort();
+
+    for path in paths {
+        let text = match read_to_string(&path) {
+            Err(_) => continue,
+            Ok(text) => text,
+        };
+        let name = path.display().to_string();
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("kdl") => match Blueprint::from_kdl(&name, &text) {
+                Ok(mut parsed) => blueprints.append(&mut parsed),
+                Err(error) => errors.push(format!("{}: {}", name, error)),
+            },
+            Some("txt") => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .for_each(|line| match Blueprint::from_text(line.trim()) {
+                    Ok(blueprint) => blueprints.push(blueprint),
+                    Err(error) => errors.push(format!("{}: {}", name, error)),
+                }),
+            _ => {}
+        }
+    }
+
+    (blueprints, errors)
+}
+
+#[derive(Decode)]
+enum BlueprintFile {
+    Blueprint(BlueprintNode),
+}
+
+#[derive(Decode)]
+struct BlueprintNode {
+    #[knuffel(argument)]
+    name: String,
+    #[knuffel(property)]
+    width: i32,
+    #[knuffel(property)]
+    height: i32,
+    #[knuffel(children(name = "structure"))]
+    structures: Vec<StructureNode>,
+}
+
+#[derive(Decode)]
+struct StructureNode {
+    #[knuffel(argument)]
+    name: String,
+    #[knuffel(property)]
+    x: i32,
+    #[knuffel(property)]
+    y: i32,
+    #[knuffel(property)]
+    direction: Option<String>,
+    #[knuffel(property)]
+    recipe: Option<String>,
+    #[knuffel(property)]
+    filter: Option<String>,
+    #[knuffel(property)]
+    priority: Option<String>,
+}
+
+fn kdl_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Characters that separate the parts of a blueprint text string, and `%`, which escapes them.
+const TEXT_SEPARATORS: &[char] = &['%', ':', ',', ';'];
+
+/// Writes every separator in `text` as `%` and its two digit hex code.
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match TEXT_SEPARATORS.contains(&c) {
+            true => format!("%{:02X}", c as u32),
+            false => c.to_string(),
+        })
+        .collect()
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => {
+                let code: String = chars.by_ref().take(2).collect();
+                let code = u8::from_str_radix(&code, 16).ok()?;
+                unescaped.push(code as char);
+            }
+            c => unescaped.push(c),
+        }
+    }
+
+    Some(unescaped)
+}
+
+impl Blueprint {
+    /// One `blueprint` node with a `structure` child for every entry.
+    pub fn to_kdl(&self) -> String {
+        let mut kdl = format!(
+            "blueprint {} width={} height={} {{\n",
+            kdl_string(&self.name),
+            self.width,
+            self.height
+        );
+
+        self.entries.iter().for_each(|entry| {
+            let ghost = &entry.ghost;
+            kdl.push_str(&format!(
+                "    structure {} x={} y={} direction=\"{}\"",
+                kdl_string(&ghost.structure),
+                entry.x,
+                entry.y,
+                ghost.direction.name()
+            ));
+            if let Some(recipe) = &ghost.recipe {
+                kdl.push_str(&format!(" recipe={}", kdl_string(recipe)));
+            }
+            if let Some(filter) = &ghost.filter {
+                kdl.push_str(&format!(" filter={}", kdl_string(filter)));
+            }
+            if let Some(priority) = ghost.priority {
+                kdl.push_str(&format!(" priority=\"{}\"", priority.name()));
+            }
+            kdl.push('\n');
+        });
+
+        kdl.push_str("}\n");
+        kdl
+    }
+
+    pub fn from_kdl(file_name: &str, text: &str) -> Result<Vec<Blueprint>, String> {
+        let nodes = knuffel::parse::<Vec<BlueprintFile>>(file_name, text)
+            .map_err(|error| error.to_string())?;
+
+        nodes
+            .into_iter()
+            .map(|BlueprintFile::Blueprint(node)| {
+                let entries = node
+                    .structures
+                    .into_iter()
+                    .map(|structure| {
+                        if !is_structure_name(&structure.name) {
+                            return Err(format!("unknown structure {}", structure.name));
+                        }
+                        let direction = match structure.direction {
+                            None => Direction::default(),
+                            Some(name) => Direction::from_name(&name)
+                                .ok_or(format!("unknown direction {}", name))?,
+                        };
+                        let priority = match structure.priority {
+                            None => None,
+                            Some(name) => Some(
+                                Side::from_name(&name).ok_or(format!("unknown side {}", name))?,
+                            ),
+                        };
+
+                        Ok(BlueprintEntry {
+                            x: structure.x,
+                            y: structure.y,
+                            ghost: Ghost {
+                                structure: structure.name,
+                                direction,
+                                recipe: structure.recipe,
+                                filter: structure.filter,
+                                priority,
+                            },
+                        })
+                    })
+                    .collect::<Result<_, String>>()?;
+
+                Ok(Blueprint {
+                    name: node.name,
+                    width: node.width,
+                    height: node.height,
+                    entries,
+                })
+            })
+            .collect()
+    }
+
+    /// A single line like `bp1:name:3x1:Belt,0,0,e,,,;Splitter,1,0,e,,,l`, with the name, the size
+    /// and every entry's structure, position, direction, recipe, filter and priority side.
+    /// Separators within names are escaped.
+    pub fn to_text(&self) -> String {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let ghost = &entry.ghost;
+                format!(
+                    "{},{},{},{},{},{},{}",
+                    escape(&ghost.structure),
+                    entry.x,
+                    entry.y,
+                    &ghost.direction.name()[..1],
+                    escape(ghost.recipe.as_deref().unwrap_or("")),
+                    escape(ghost.filter.as_deref().unwrap_or("")),
+                    ghost.priority.map_or("", |side| &side.name()[..1])
+                )
+            })
+            .collect();
+
+        format!(
+            "{}:{}:{}x{}:{}",
+            TEXT_PREFIX,
+            escape(&self.name),
+            self.width,
+            self.height,
+            entries.join(";")
+        )
+    }
+
+    pub fn from_text(text: &str) -> Result<Blueprint, String> {
+        let invalid = || format!("not a blueprint: {}", text);
+
+        let parts: Vec<&str> = text.splitn(4, ':').collect();
+        let (name, size, entries) = match parts[..] {
+            [TEXT_PREFIX, name, size, entries] => (name, size, entries),
+            _ => return Err(invalid()),
+        };
+        let (width, height) = size
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or_else(invalid)?;
+
+        let optional = |field: &str| match field {
+            "" => Some(None),
+            field => unescape(field).map(Some),
+        };
+        let entries = entries
+            .split(';')
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let fields: Vec<&str> = entry.split(',').collect();
+                match fields[..] {
+                    [structure, x, y, direction, recipe, filter, priority] => {
+                        Some(BlueprintEntry {
+                            x: x.parse().ok()?,
+                            y: y.parse().ok()?,
+                            ghost: Ghost {
+                                structure: unescape(structure)?,
+                                direction: Direction::from_name(direction)?,
+                                recipe: optional(recipe)?,
+                                filter: optional(filter)?,
+                                priority: match priority {
+                                    "" => None,
+                                    side => Some(Side::from_name(side)?),
+                                },
+                            },
+                        })
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<BlueprintEntry>>>()
+            .ok_or_else(invalid)?;
+        if let Some(entry) = entries
+            .iter()
+            .find(|entry| !is_structure_name(&entry.ghost.structure))
+        {
+            return Err(format!("unknown structure {}", entry.ghost.structure));
+        }
+
+        Ok(Blueprint {
+            name: unescape(name).ok_or_else(invalid)?,
+            width,
+            height,
+            entries,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blueprint(name: &str) -> Blueprint {
+        let entry =
+            |structure: &str, x, recipe: Option<&str>, filter: Option<&str>| BlueprintEntry {
+                x,
+                y: 1,
+                ghost: Ghost {
+                    structure: structure.to_string(),
+                    direction: Direction::East,
+                    recipe: recipe.map(str::to_string),
+                    filter: filter.map(str::to_string),
+                    priority: None,
+                },
+            };
+        let mut splitter = entry("Splitter", 3, None, None);
+        splitter.ghost.priority = Some(Side::Right);
+
+        Blueprint {
+            name: name.to_string(),
+            width: 4,
+            height: 2,
+            entries: vec![
+                entry("Belt", 0, None, None),
+                entry("Inserter", 1, None, Some("Flint")),
+                entry("Assembler", 2, Some("Wooden Stick"), None),
+                splitter,
+            ],
+        }
+    }
+
+    #[test]
+    fn text_round_trip() {
+        for name in ["smelter", "iron: 2, copper; 1", "100% \"belts\""] {
+            let original = blueprint(name);
+
+            assert_eq!(Blueprint::from_text(&original.to_text()), Ok(original));
+        }
+    }
+
+    #[test]
+    fn kdl_round_trip() {
+        for name in ["smelter", "iron: 2, copper; 1", "100% \"belts\" \\ {}"] {
+            let original = blueprint(name);
+
+            let parsed = Blueprint::from_kdl("test.kdl", &original.to_kdl());
+            assert_eq!(parsed, Ok(vec![original]));
+        }
+    }
+
+    #[test]
+    fn text_rejects_unknown_structures() {
+        let text = "bp1:axes:1x1:Stone Axe,0,0,n,,,";
+
+        assert!(Blueprint::from_text(text).is_err());
+    }
+
+    #[test]
+    fn kdl_rejects_unknown_structures() {
+        let kdl = "blueprint \"axes\" width=1 height=1 {\n    structure \"Stone Axe\" x=0 y=0\n}\n";
+
+        assert_eq!(
+            Blueprint::from_kdl("test.kdl", kdl),
+            Err("unknown structure Stone Axe".to_string())
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use specs::Entity;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Status {
+    Working,
+    /// A machine without a recipe to work on.
+    Idle,
+    InputStarved,
+    OutputBlocked,
+    Unpowered,
+    /// A chest, belt or inserter at the head of a line that has nothing to pass on.
+    Unsupplied,
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Working => "working",
+            Status::Idle => "idle",
+            Status::InputStarved => "input-starved",
+            Status::OutputBlocked => "output-blocked",
+            Status::Unpowered => "unpowered",
+            Status::Unsupplied => "unsupplied",
+        }
+    }
+}
+
+/// A machine, chest, belt or inserter and what it is doing. Starved ones carry the `cause` their
+/// supply line was traced back to, which may be themselves when nothing feeds them; jammed belts
+/// carry the head of their jam and blocked inserters what refuses their item.
+pub struct Finding {
+    pub entity: Entity,
+    pub status: Status,
+    pub cause: Option<Entity>,
+}
+
+/// Something holding up production, and how many machines, chests, belts and inserters stall
+/// because of it.
+pub struct Cause {
+    pub entity: Entity,
+    pub status: Status,
+    pub impact: usize,
+}
+
+/// The state of every machine, chest, belt and inserter as of the last tick, with the causes of stalls, most harmful first.
+#[derive(Default)]
+pub struct Bottlenecks {
+    pub findings: Vec<Finding>,
+    pub causes: Vec<Cause>,
+}
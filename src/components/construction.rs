@@ -0,0 +1,13 @@
+use crate::{Component, DenseVecStorage};
+
+/// Marks a ghost as a construction site. What was delivered to it is in its `InBackpack`, and
+/// once everything is there it is built for as many ticks as its structure takes to craft.
+#[derive(Component, Debug, Default)]
+pub struct ConstructionSite {
+    pub progress: u64,
+    /// Why the site can not be finished right now, such as something standing in the way.
+    pub problem: Option<String>,
+    /// Set for sites of structures the player placed by hand, which go into the undo history once
+    /// built. Blueprint sites are left out.
+    pub is_placed_by_player: bool,
+}
@@ -0,0 +1,133 @@
+use specs::Entity;
+
+use crate::components::items::ResourceNode;
+use crate::{Component, DenseVecStorage};
+
+/// Chance that harvesting a plant also gives one of its seeds.
+pub const SEED_CHANCE: f32 = 0.5;
+
+/// One look of a growing crop, held for `ticks` of growth before the next one.
+pub struct Stage {
+    pub name: &'static str,
+    pub glyph: char,
+    pub color: (u8, u8, u8),
+    pub ticks: u64,
+}
+
+/// Grows from `seed` through its `stages` into a harvestable plant. The last stage is the grown
+/// plant itself. Plants that `needs_light` grow only as fast as their tile is lit.
+pub struct Plant {
+    pub name: &'static str,
+    pub seed: &'static str,
+    pub yields: &'static str,
+    pub max_yield: u32,
+    pub regrow_interval: u64,
+    pub needs_light: bool,
+    pub stages: &'static [Stage],
+}
+
+impl Plant {
+    pub fn node(&self) -> ResourceNode {
+        ResourceNode {
+            yields: self.yields,
+            remaining: self.max_yield,
+            max_yield: self.max_yield,
+            tool: None,
+            tier: 0,
+            regrow_interval: Some(self.regrow_interval),
+            seed: Some(self.seed),
+        }
+    }
+}
+
+pub const PLANTS: &[Plant] = &[
+    Plant {
+        name: "Bush",
+        seed: "Bush Seed",
+        yields: "Wooden Stick",
+        max_yield: 3,
+        regrow_interval: 1200,
+        needs_light: false,
+        stages: &[
+            Stage {
+                name: "Seedling",
+                glyph: '.',
+                color: (144, 238, 144),
+                ticks: 400,
+            },
+            Stage {
+                name: "Sprout",
+                glyph: ',',
+                color: (50, 205, 50),
+                ticks: 800,
+            },
+            Stage {
+                name: "Shrub",
+                glyph: '"',
+                color: (34, 139, 34),
+                ticks: 1200,
+            },
+            Stage {
+                name: "Bush",
+                glyph: '%',
+                color: (0, 191, 0),
+                ticks: 0,
+            },
+        ],
+    },
+    Plant {
+        name: "Rose",
+        seed: "Rose Seed",
+        yields: "Rose Petal",
+        max_yield: 2,
+        regrow_interval: 1800,
+        needs_light: true,
+        stages: &[
+            Stage {
+                name: "Seedling",
+                glyph: '.',
+                color: (144, 238, 144),
+                ticks: 600,
+            },
+            Stage {
+                name: "Stem",
+                glyph: '|',
+                color: (34, 139, 34),
+                ticks: 1200,
+            },
+            Stage {
+                name: "Bud",
+                glyph: '¡',
+                color: (255, 182, 193),
+                ticks: 1200,
+            },
+            Stage {
+                name: "Rose",
+                glyph: '±',
+                color: (255, 0, 0),
+                ticks: 0,
+            },
+        ],
+    },
+];
+
+pub fn find_plant(name: &str) -> Option<&'static Plant> {
+    PLANTS.iter().find(|plant| plant.name == name)
+}
+
+pub fn plant_by_seed(seed: &str) -> Option<&'static Plant> {
+    PLANTS.iter().find(|plant| plant.seed == seed)
+}
+
+/// A planted seed working its way through the stages of `plant`.
+#[derive(Component, Debug)]
+pub struct Crop {
+    pub plant: &'static str,
+    pub stage: usize,
+    pub progress: f32,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct PlantQueue {
+    pub seed: Entity,
+}
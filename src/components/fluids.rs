@@ -0,0 +1,110 @@
+use specs::Entity;
+
+use crate::{Component, DenseVecStorage};
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Fluid {
+    Water,
+    Sap,
+}
+
+impl Fluid {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fluid::Water => "water",
+            Fluid::Sap => "sap",
+        }
+    }
+}
+
+pub struct FluidAmount {
+    pub fluid: Fluid,
+    pub amount: f32,
+}
+
+/// Fluid held by a pipe, pump, tank or machine. Adjacent boxes form a network.
+#[derive(Component, Debug)]
+pub struct FluidBox {
+    pub capacity: f32,
+    pub volume: f32,
+    pub fluid: Option<Fluid>,
+}
+
+impl FluidBox {
+    pub fn new(capacity: f32) -> Self {
+        Self {
+            capacity,
+            volume: 0.,
+            fluid: None,
+        }
+    }
+
+    pub fn can_hold(&self, fluid: Fluid) -> bool {
+        self.fluid.is_none() || self.fluid == Some(fluid) || self.volume <= 0.
+    }
+
+    /// Adds as much of `amount` as fits and returns how much was added.
+    pub fn fill(&mut self, fluid: Fluid, amount: f32) -> f32 {
+        if !self.can_hold(fluid) {
+            return 0.;
+        }
+
+        let added = amount.min(self.capacity - self.volume).max(0.);
+        self.volume += added;
+        self.fluid = Some(fluid);
+
+        added
+    }
+
+    pub fn drain(&mut self, amount: f32) {
+        self.volume = (self.volume - amount).max(0.);
+
+        if self.volume <= 0. {
+            self.fluid = None;
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Pipe {}
+
+/// Pulls water into its fluid box every tick while it is next to a water tile.
+#[derive(Component, Debug)]
+pub struct Pump {
+    pub rate: f32,
+}
+
+#[derive(Component, Debug)]
+pub struct Tank {}
+
+pub struct FluidNetwork {
+    pub members: Vec<Entity>,
+    pub fluid: Option<Fluid>,
+    pub volume: f32,
+    pub capacity: f32,
+}
+
+impl FluidNetwork {
+    /// How full the network is, from 0 to 1.
+    pub fn pressure(&self) -> f32 {
+        if self.capacity <= 0. {
+            return 0.;
+        }
+
+        self.volume / self.capacity
+    }
+}
+
+/// Every fluid network found during the last tick.
+#[derive(Default)]
+pub struct FluidNetworks {
+    pub networks: Vec<FluidNetwork>,
+}
+
+impl FluidNetworks {
+    pub fn of(&self, entity: Entity) -> Option<&FluidNetwork> {
+        self.networks
+            .iter()
+            .find(|network| network.members.contains(&entity))
+    }
+}
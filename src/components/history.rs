@@ -0,0 +1,201 @@
+use specs::Entity;
+
+use crate::components::structures::{Direction, Side};
+
+/// How many actions can be undone before the oldest ones are forgotten.
+const HISTORY_LENGTH: usize = 100;
+
+/// What a configurable structure was set to: a machine's recipe, the filter of an inserter or
+/// filter splitter, or the side a splitter or merger prefers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Setting {
+    Recipe(Option<&'static str>),
+    Filter(Option<String>),
+    Priority(Option<Side>),
+}
+
+/// A world edit the player made in build mode. Crafting, harvesting and the like change what
+/// the player has for good and are never recorded.
+#[derive(Clone, Debug)]
+pub enum Action {
+    Place {
+        structure: Entity,
+        name: String,
+        x: i32,
+        y: i32,
+        direction: Direction,
+    },
+    Deconstruct {
+        structure: Entity,
+        name: String,
+        x: i32,
+        y: i32,
+        direction: Direction,
+    },
+    Rotate {
+        structure: Entity,
+        from: Direction,
+        to: Direction,
+    },
+    Configure {
+        structure: Entity,
+        from: Setting,
+        to: Setting,
+    },
+}
+
+impl Action {
+    /// The action that takes this one back.
+    pub fn inverse(&self) -> Action {
+        match self.clone() {
+            Action::Place {
+                structure,
+                name,
+                x,
+                y,
+                direction,
+            } => Action::Deconstruct {
+                structure,
+                name,
+                x,
+                y,
+                direction,
+            },
+            Action::Deconstruct {
+                structure,
+                name,
+                x,
+                y,
+                direction,
+            } => Action::Place {
+                structure,
+                name,
+                x,
+                y,
+                direction,
+            },
+            Action::Rotate {
+                structure,
+                from,
+                to,
+            } => Action::Rotate {
+                structure,
+                from: to,
+                to: from,
+            },
+            Action::Configure {
+                structure,
+                from,
+                to,
+            } => Action::Configure {
+                structure,
+                from: to,
+                to: from,
+            },
+        }
+    }
+
+    /// Whether `done` is what carrying out this action amounts to. A structure placed back may be
+    /// a different one of the same kind.
+    pub fn is_done_by(&self, done: &Action) -> bool {
+        match (self, done) {
+            (
+                Action::Place { name, x, y, .. },
+                Action::Place {
+                    name: done_name,
+                    x: done_x,
+                    y: done_y,
+                    ..
+                },
+            ) => name == done_name && (x, y) == (done_x, done_y),
+            (Action::Deconstruct { structure, .. }, Action::Deconstruct { .. }) => {
+                *structure == done.structure()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn structure(&self) -> Entity {
+        match self {
+            Action::Place { structure, .. }
+            | Action::Deconstruct { structure, .. }
+            | Action::Rotate { structure, .. }
+            | Action::Configure { structure, .. } => *structure,
+        }
+    }
+
+    fn replace(&mut self, old: Entity, new: Entity) {
+        match self {
+            Action::Place { structure, .. }
+            | Action::Deconstruct { structure, .. }
+            | Action::Rotate { structure, .. }
+            | Action::Configure { structure, .. } => {
+                if *structure == old {
+                    *structure = new
+                }
+            }
+        }
+    }
+}
+
+/// An undo or redo waiting for the build or deconstruct system to carry it out.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    /// The action as it was recorded, not the inverse an undo carries out.
+    pub action: Action,
+    pub undoing: bool,
+}
+
+impl Replay {
+    /// What the replay does to the world.
+    pub fn applied(&self) -> Action {
+        match self.undoing {
+            true => self.action.inverse(),
+            false => self.action.clone(),
+        }
+    }
+}
+
+/// Actions that can be undone, most recent last, and the undone actions that can be redone.
+#[derive(Default)]
+pub struct History {
+    pub undo: Vec<Action>,
+    pub redo: Vec<Action>,
+    pub replaying: Option<Replay>,
+}
+
+impl History {
+    /// Remembers a new action, which makes the undone actions impossible to redo.
+    pub fn record(&mut self, action: Action) {
+        self.undo.push(action);
+        if self.undo.len() > HISTORY_LENGTH {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Moves a replayed action over, so an undone action can be redone and the other way around.
+    pub fn replayed(&mut self, replay: Replay) {
+        match replay.undoing {
+            true => self.redo.push(replay.action),
+            false => self.undo.push(replay.action),
+        }
+    }
+
+    /// Puts back an action that could not be replayed, so it can be tried again.
+    pub fn failed(&mut self, replay: Replay) {
+        match replay.undoing {
+            true => self.undo.push(replay.action),
+            false => self.redo.push(replay.action),
+        }
+    }
+
+    /// Points the remembered actions of one structure at another, for when an undo places a
+    /// different structure of the same kind than the one that was taken down.
+    pub fn replace(&mut self, old: Entity, new: Entity) {
+        self.undo
+            .iter_mut()
+            .chain(self.redo.iter_mut())
+            .for_each(|action| action.replace(old, new));
+    }
+}
@@ -1,12 +1,19 @@
-use specs::{Entity, Join, WorldExt};
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
 
-use crate::{Component, Player, Position, World};
+use specs::storage::MaskedStorage;
+use specs::world::EntitiesRes;
+use specs::{Entity, Join, LazyUpdate, Storage, WorldExt};
+
+use crate::{Component, Name, Player, Position, World};
 use crate::{DenseVecStorage, Log};
 
+/// Fraction of the maximum carry weight above which the owner becomes encumbered.
+pub const ENCUMBRANCE_THRESHOLD: f32 = 0.75;
+
 #[derive(Component, Debug, Default)]
 pub struct Item {
     pub can_be_picked: bool,
-    pub can_be_crafted: bool,
 }
 
 pub fn get_item(world: &mut World) {
@@ -56,12 +63,182 @@ pub struct InBackpack {
     pub owner: Entity,
 }
 
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Weight {
+    pub weight: f32,
+    pub volume: f32,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Capacity {
+    pub max_weight: f32,
+    pub max_volume: f32,
+}
+
+/// Every other step of an encumbered entity is spent catching its breath.
+#[derive(Component, Debug, Default)]
+pub struct Encumbered {
+    pub out_of_breath: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Load {
+    pub weight: f32,
+    pub volume: f32,
+}
+
+impl Load {
+    pub fn fits(&self, item: &Weight, capacity: &Capacity) -> bool {
+        self.weight + item.weight <= capacity.max_weight
+            && self.volume + item.volume <= capacity.max_volume
+    }
+
+    pub fn is_encumbering(&self, capacity: &Capacity) -> bool {
+        self.weight > capacity.max_weight * ENCUMBRANCE_THRESHOLD
+    }
+}
+
+/// Sums the weight and volume of everything `owner` carries. Items without a `Weight` are free.
+pub fn backpack_load<B, W>(
+    owner: Entity,
+    backpack: &Storage<InBackpack, B>,
+    weights: &Storage<Weight, W>,
+) -> Load
+where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    W: Deref<Target = MaskedStorage<Weight>>,
+{
+    (backpack, weights)
+        .join()
+        .filter(|(pack, _)| pack.owner == owner)
+        .fold(Load::default(), |load, (_, weight)| Load {
+            weight: load.weight + weight.weight,
+            volume: load.volume + weight.volume,
+        })
+}
+
+/// Whether `item` still fits into what `owner` carries. Owners without a `Capacity` and items
+/// without a `Weight` always fit. Every insertion into a backpack is checked through here.
+pub fn fits_into<B, C, W>(
+    owner: Entity,
+    item: Entity,
+    backpack: &Storage<InBackpack, B>,
+    capacities: &Storage<Capacity, C>,
+    weights: &Storage<Weight, W>,
+) -> bool
+where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    C: Deref<Target = MaskedStorage<Capacity>>,
+    W: Deref<Target = MaskedStorage<Weight>>,
+{
+    match (capacities.get(owner), weights.get(item)) {
+        (Some(capacity), Some(weight)) => {
+            backpack_load(owner, backpack, weights).fits(weight, capacity)
+        }
+        _ => true,
+    }
+}
+
+/// Gives `item` to `owner` if it fits, or puts it down on the owner's tile otherwise.
+/// Returns whether the item ended up in the backpack.
+pub fn stow<B, P, C, W>(
+    item: Entity,
+    owner: Entity,
+    backpack: &mut Storage<InBackpack, B>,
+    positions: &mut Storage<Position, P>,
+    capacities: &Storage<Capacity, C>,
+    weights: &Storage<Weight, W>,
+) -> bool
+where
+    B: DerefMut<Target = MaskedStorage<InBackpack>>,
+    P: DerefMut<Target = MaskedStorage<Position>>,
+    C: Deref<Target = MaskedStorage<Capacity>>,
+    W: Deref<Target = MaskedStorage<Weight>>,
+{
+    backpack.remove(item);
+    let tile = positions
+        .get(owner)
+        .map(|position| (position.x, position.y));
+
+    match tile {
+        Some((x, y)) if !fits_into(owner, item, backpack, capacities, weights) => {
+            positions
+                .insert(item, Position { x, y })
+                .expect("unable to drop item");
+            false
+        }
+        _ => {
+            positions.remove(item);
+            backpack
+                .insert(item, InBackpack { owner })
+                .expect("unable to add to backpack");
+            true
+        }
+    }
+}
+
+/// Stows an item that is still being built through `LazyUpdate`, once it exists.
+pub fn stow_later(lazy: &LazyUpdate, item: Entity, owner: Entity) {
+    lazy.exec_mut(move |world| {
+        let stowed = stow(
+            item,
+            owner,
+            &mut world.write_storage::<InBackpack>(),
+            &mut world.write_storage::<Position>(),
+            &world.read_storage::<Capacity>(),
+            &world.read_storage::<Weight>(),
+        );
+
+        if !stowed && owner == *world.fetch::<Entity>() {
+            let name = world
+                .read_storage::<Name>()
+                .get(item)
+                .map(|name| name.name.clone())
+                .unwrap_or_default();
+            world.fetch_mut::<Log>().log(format!(
+                "the {} does not fit in your backpack, you put it down",
+                name
+            ));
+        }
+    });
+}
+
+/// Groups everything `owner` carries into stacks of equally named items, sorted by name.
+pub fn stacks_of<B, N>(
+    owner: Entity,
+    entities: &EntitiesRes,
+    backpack: &Storage<InBackpack, B>,
+    names: &Storage<Name, N>,
+) -> BTreeMap<String, Vec<Entity>>
+where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    N: Deref<Target = MaskedStorage<Name>>,
+{
+    let mut stacks: BTreeMap<String, Vec<Entity>> = BTreeMap::new();
+
+    for (entity, _pack, name) in (entities, backpack, names)
+        .join()
+        .filter(|(_, pack, _)| pack.owner == owner)
+    {
+        stacks.entry(name.name.clone()).or_default().push(entity);
+    }
+
+    stacks
+}
+
 #[derive(Component, Debug, Clone)]
 pub struct PickupQueue {
     pub collected_by: Entity,
     pub item: Entity,
 }
 
+/// Moves the item it is attached to into the inventory of `to`.
+#[derive(Component, Debug, Clone)]
+pub struct TransferQueue {
+    pub to: Entity,
+}
+
+/// Crafts `item_name` by hand for the entity it is attached to, from that entity's inventory.
 #[derive(Component, Debug, Clone)]
 pub struct CraftQueue {
     pub item_name: String,
@@ -70,12 +247,13 @@ pub struct CraftQueue {
 #[derive(Component)]
 pub struct Tier {
     pub level: u8,
-    pub alternative_name: Option<String>,
 }
 
 pub fn name_by_tier(level: u8) -> &'static str {
     match level {
         0 => "Flint",
+        1 => "Copper",
+        2 => "Iron",
         _ => todo!(),
     }
 }
@@ -101,5 +279,56 @@ pub struct Rose {}
 #[derive(Component)]
 pub struct Axe {}
 
+#[derive(Component)]
+pub struct Pickaxe {}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tool {
+    Axe,
+    Pickaxe,
+}
+
+impl Tool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Axe => "axe",
+            Tool::Pickaxe => "pickaxe",
+        }
+    }
+}
+
+/// Something in the world that gives one `yields` per harvest until `remaining` runs out.
+/// Harvesting needs a `tool` of at least `tier`, or bare hands when there is none. Nodes with a
+/// `regrow_interval` win back one yield every that many ticks up to `max_yield`, all others
+/// disappear once depleted. Plants may also drop a `seed` when harvested.
+#[derive(Component, Debug)]
+pub struct ResourceNode {
+    pub yields: &'static str,
+    pub remaining: u32,
+    pub max_yield: u32,
+    pub tool: Option<Tool>,
+    pub tier: u8,
+    pub regrow_interval: Option<u64>,
+    pub seed: Option<&'static str>,
+}
+
+impl ResourceNode {
+    pub fn is_depleted(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// Put on a regrowing node when it is first harvested below `max_yield`, so every node regrows on
+/// its own schedule. `since` is the tick its next yield started growing.
+#[derive(Component, Debug)]
+pub struct Regrowing {
+    pub since: u64,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct HarvestQueue {
+    pub node: Entity,
+}
+
 #[derive(Component)]
 pub struct FirePit {}
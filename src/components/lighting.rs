@@ -0,0 +1,44 @@
+use crate::map::{xy_to_idx, MAP_COUNT};
+use crate::{Component, DenseVecStorage, RGB};
+
+/// Tiles lit less than this hide what stands on them.
+pub const VISIBLE_LIGHT: f32 = 0.25;
+
+/// Lights up everything it can see within `radius` while its burner, if any, is burning and its
+/// power consumer, if any, gets power.
+#[derive(Component, Debug)]
+pub struct Light {
+    pub radius: i32,
+}
+
+/// How lit every tile is, from daylight and every light source, updated each tick.
+pub struct LightMap {
+    pub levels: Vec<f32>,
+}
+
+impl Default for LightMap {
+    fn default() -> Self {
+        LightMap {
+            levels: vec![1.; MAP_COUNT],
+        }
+    }
+}
+
+impl LightMap {
+    pub fn at(&self, x: i32, y: i32) -> f32 {
+        self.levels[xy_to_idx(x, y)]
+    }
+
+    pub fn is_visible(&self, x: i32, y: i32) -> bool {
+        self.at(x, y) >= VISIBLE_LIGHT
+    }
+}
+
+/// Darkens `color` by `light`, keeping a little blue so night looks like night.
+pub fn shade(color: RGB, light: f32) -> RGB {
+    RGB::from_f32(
+        color.r * light,
+        color.g * light,
+        color.b * (light + (1. - light) * 0.35),
+    )
+}
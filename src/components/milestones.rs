@@ -0,0 +1,20 @@
+use std::collections::{HashMap, VecDeque};
+
+/// How far along every goal is, which ones are reached, and what was made within the last
+/// minute for goals about production rates.
+#[derive(Default)]
+pub struct Milestones {
+    pub progress: HashMap<&'static str, u32>,
+    pub completed: Vec<&'static str>,
+    pub recent: VecDeque<(u64, String)>,
+}
+
+impl Milestones {
+    pub fn is_completed(&self, goal: &str) -> bool {
+        self.completed.contains(&goal)
+    }
+
+    pub fn progress_of(&self, goal: &str) -> u32 {
+        self.progress.get(goal).copied().unwrap_or(0)
+    }
+}
@@ -1 +1,17 @@
+pub mod blueprints;
+pub mod bottlenecks;
+pub mod construction;
+pub mod farming;
+pub mod fluids;
+pub mod history;
 pub mod items;
+pub mod lighting;
+pub mod milestones;
+pub mod power;
+pub mod rails;
+pub mod research;
+pub mod signals;
+pub mod statistics;
+pub mod stockpiles;
+pub mod structures;
+pub mod workers;
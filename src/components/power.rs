@@ -0,0 +1,85 @@
+use specs::Entity;
+
+use crate::{Component, DenseVecStorage};
+
+/// Items that can be burned, and for how many ticks they burn.
+#[derive(Component, Debug)]
+pub struct Fuel {
+    pub burn_ticks: u64,
+}
+
+/// Burns fuel items from its own inventory, one after another, while it is `ignited`.
+#[derive(Component, Debug)]
+pub struct Burner {
+    pub remaining: u64,
+    pub ignited: bool,
+    pub leaves_ash: bool,
+}
+
+impl Burner {
+    pub fn is_burning(&self) -> bool {
+        self.ignited && self.remaining > 0
+    }
+}
+
+/// Heat and light given off while its burner is burning.
+#[derive(Component, Debug)]
+pub struct Heat {
+    pub output: f32,
+    pub light_radius: i32,
+}
+
+/// Feeds `output` kW into its grid while its burner is burning, or scaled by daylight when it is
+/// `Solar`. A burning generator only burns while the grid has something to power.
+#[derive(Component, Debug)]
+pub struct Generator {
+    pub output: f32,
+}
+
+#[derive(Component, Debug)]
+pub struct Solar {}
+
+/// Connects everything within `radius` tiles, including other poles, into one grid.
+#[derive(Component, Debug)]
+pub struct PowerPole {
+    pub radius: i32,
+}
+
+/// Draws `draw` kW while working. `satisfaction` is the share of that demand its grid met during
+/// the last tick, from 0 to 1.
+#[derive(Component, Debug)]
+pub struct PowerConsumer {
+    pub draw: f32,
+    pub satisfaction: f32,
+}
+
+pub struct PowerGrid {
+    pub poles: Vec<Entity>,
+    pub generators: Vec<Entity>,
+    pub production: f32,
+    pub consumption: f32,
+}
+
+impl PowerGrid {
+    pub fn has_demand(&self) -> bool {
+        self.consumption > 0.
+    }
+
+    pub fn satisfaction(&self) -> f32 {
+        if self.production <= 0. {
+            return 0.;
+        }
+
+        if self.consumption <= 0. {
+            return 1.;
+        }
+
+        (self.production / self.consumption).min(1.)
+    }
+}
+
+/// Every power grid found during the last tick.
+#[derive(Default)]
+pub struct PowerGrids {
+    pub grids: Vec<PowerGrid>,
+}
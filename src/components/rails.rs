@@ -0,0 +1,98 @@
+use crate::{Component, DenseVecStorage};
+
+/// Ticks a cart waits at a station without anything being loaded or unloaded before it leaves.
+pub const MAX_IDLE_WAIT: u64 = 600;
+
+/// A rail tile. Rails join the rails next to them into lines carts run along.
+#[derive(Component, Debug)]
+pub struct Rail {}
+
+/// A rail tile carts stop at. Stations are named with the next free letter once placed.
+#[derive(Component, Debug, Default)]
+pub struct Station {
+    pub name: Option<String>,
+}
+
+/// A rail tile that splits a line into blocks. A cart only passes it while the block ahead has no
+/// other cart in it or heading into it, unless all of them leave the block by the same signal as
+/// the cart will.
+#[derive(Component, Debug, Default)]
+pub struct RailSignal {
+    pub is_clear: bool,
+}
+
+/// When a cart waiting at a station moves on to its next stop. Either way it also moves on once
+/// its cargo has not changed for `MAX_IDLE_WAIT` ticks, so a station with nothing to load or
+/// unload does not hold it forever.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Until {
+    Full,
+    Empty,
+}
+
+impl Until {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Until::Full => "full",
+            Until::Empty => "empty",
+        }
+    }
+
+    pub fn other(&self) -> Until {
+        match self {
+            Until::Full => Until::Empty,
+            Until::Empty => Until::Full,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Stop {
+    pub station: String,
+    pub until: Until,
+}
+
+/// A cart that runs along rails from stop to stop of its schedule, carrying its cargo as a
+/// container.
+#[derive(Component, Debug)]
+pub struct Cart {
+    pub schedule: Vec<Stop>,
+    /// Index into `schedule` of the stop the cart is heading for or waiting at.
+    pub stop: usize,
+    /// The rail tiles left to travel to the station of the current stop, nearest first.
+    pub route: Vec<(i32, i32)>,
+    /// Ticks it takes to move one tile.
+    pub interval: u64,
+    pub is_waiting: bool,
+    /// While waiting, how much cargo the cart held and the tick it arrived or that last changed.
+    pub held: (usize, u64),
+    /// Set while no rails lead to the station of the current stop.
+    pub is_lost: bool,
+}
+
+impl Cart {
+    pub fn current(&self) -> Option<&Stop> {
+        self.schedule.get(self.stop)
+    }
+
+    /// Forgets the route and the wait at the current station, for when the schedule changed.
+    pub fn reschedule(&mut self) {
+        if self.stop >= self.schedule.len() {
+            self.stop = 0;
+        }
+        self.route.clear();
+        self.is_waiting = false;
+        self.is_lost = false;
+    }
+
+    pub fn status(&self) -> String {
+        match self.current() {
+            None => "no stops".to_string(),
+            Some(stop) if self.is_lost => format!("no way to {}", stop.station),
+            Some(stop) if self.is_waiting => {
+                format!("at {} until {}", stop.station, stop.until.name())
+            }
+            Some(stop) => format!("heading to {}", stop.station),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{Component, DenseVecStorage};
+
+/// What has been researched so far and what is being researched now.
+#[derive(Default)]
+pub struct Research {
+    pub completed: Vec<&'static str>,
+    pub current: Option<&'static str>,
+    pub progress: f32,
+}
+
+impl Research {
+    pub fn is_completed(&self, technology: &str) -> bool {
+        self.completed.contains(&technology)
+    }
+}
+
+/// A structure that research materials can be taken from, and that speeds up research.
+#[derive(Component, Debug)]
+pub struct Lab {}
+
+#[derive(Component, Debug, Clone)]
+pub struct ResearchQueue {
+    pub technology: &'static str,
+}
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use specs::Entity;
+
+use crate::{Component, DenseVecStorage};
+
+/// Signal values by name: item counts, whole units of fluid and combinator outputs.
+pub type Signals = BTreeMap<String, i32>;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Comparison {
+    Less,
+    Greater,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::Greater => ">",
+            Comparison::Equal => "=",
+            Comparison::NotEqual => "!=",
+        }
+    }
+
+    pub fn next(&self) -> Comparison {
+        match self {
+            Comparison::Less => Comparison::Greater,
+            Comparison::Greater => Comparison::Equal,
+            Comparison::Equal => Comparison::NotEqual,
+            Comparison::NotEqual => Comparison::Less,
+        }
+    }
+
+    pub fn compare(&self, left: i32, right: i32) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::Greater => left > right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+/// A signal compared to a constant, like `Flint < 50`. Signals missing from a network count as 0.
+#[derive(Clone, Debug)]
+pub struct Condition {
+    pub signal: String,
+    pub comparison: Comparison,
+    pub value: i32,
+}
+
+impl Condition {
+    pub fn holds(&self, signals: &Signals) -> bool {
+        let left = signals.get(&self.signal).copied().unwrap_or(0);
+        self.comparison.compare(left, self.value)
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.signal,
+            self.comparison.symbol(),
+            self.value
+        )
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::Multiply => "*",
+            Operation::Divide => "/",
+        }
+    }
+
+    pub fn next(&self) -> Operation {
+        match self {
+            Operation::Add => Operation::Subtract,
+            Operation::Subtract => Operation::Multiply,
+            Operation::Multiply => Operation::Divide,
+            Operation::Divide => Operation::Add,
+        }
+    }
+
+    /// Saturates instead of overflowing, and dividing by zero gives zero.
+    pub fn apply(&self, left: i32, right: i32) -> i32 {
+        match self {
+            Operation::Add => left.saturating_add(right),
+            Operation::Subtract => left.saturating_sub(right),
+            Operation::Multiply => left.saturating_mul(right),
+            Operation::Divide => left.checked_div(right).unwrap_or(0),
+        }
+    }
+}
+
+/// Joins the wires next to it into one network, and connects the containers, tanks, machines,
+/// inserters and belts next to it to that network.
+#[derive(Component, Debug)]
+pub struct Wire {}
+
+/// A machine, inserter or belt that only runs while `condition` holds on the signals of the
+/// networks it is connected to, added up.
+#[derive(Component, Debug)]
+pub struct Controlled {
+    pub condition: Condition,
+}
+
+/// Set on controlled entities whose condition did not hold this tick.
+#[derive(Component, Debug)]
+pub struct Disabled {}
+
+/// Reads `signal` from the wire behind it and puts `signal` `operation` `value` on the wire in
+/// front of it as `output`, a tick later.
+#[derive(Component, Debug)]
+pub struct ArithmeticCombinator {
+    pub signal: String,
+    pub operation: Operation,
+    pub value: i32,
+    pub output: String,
+    pub result: i32,
+}
+
+/// Puts 1 of `output` on the wire in front of it while `condition` holds on the wire behind it,
+/// a tick later.
+#[derive(Component, Debug)]
+pub struct DeciderCombinator {
+    pub condition: Condition,
+    pub output: String,
+    pub result: i32,
+}
+
+/// Wires that touch, the entities connected to them and the signals on them this tick.
+pub struct SignalNetwork {
+    pub members: Vec<Entity>,
+    pub signals: Signals,
+}
+
+/// Every signal network found during the last tick.
+#[derive(Default)]
+pub struct SignalNetworks {
+    pub networks: Vec<SignalNetwork>,
+}
+
+impl SignalNetworks {
+    /// Every network `entity` is connected to. Something between two separate wires joins both.
+    pub fn of(&self, entity: Entity) -> Vec<&SignalNetwork> {
+        self.networks
+            .iter()
+            .filter(|network| network.members.contains(&entity))
+            .collect()
+    }
+
+    /// The signals on every network `entity` is connected to, added up.
+    pub fn signals_of(&self, entity: Entity) -> Signals {
+        let mut signals = Signals::new();
+        self.of(entity)
+            .iter()
+            .flat_map(|network| network.signals.iter())
+            .for_each(|(name, value)| {
+                let total = signals.entry(name.clone()).or_default();
+                *total = total.saturating_add(*value);
+            });
+        signals.retain(|_, value| *value != 0);
+
+        signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(comparison: Comparison, value: i32) -> Condition {
+        Condition {
+            signal: "Flint".to_string(),
+            comparison,
+            value,
+        }
+    }
+
+    #[test]
+    fn condition_compares_the_signal_to_its_value() {
+        let signals = Signals::from([("Flint".to_string(), 50)]);
+
+        assert!(condition(Comparison::Less, 51).holds(&signals));
+        assert!(!condition(Comparison::Less, 50).holds(&signals));
+        assert!(condition(Comparison::Greater, 49).holds(&signals));
+        assert!(condition(Comparison::Equal, 50).holds(&signals));
+        assert!(condition(Comparison::NotEqual, 49).holds(&signals));
+    }
+
+    #[test]
+    fn condition_counts_missing_signals_as_zero() {
+        let signals = Signals::from([("Log".to_string(), 7)]);
+
+        assert!(condition(Comparison::Equal, 0).holds(&signals));
+        assert!(condition(Comparison::Less, 1).holds(&Signals::new()));
+    }
+
+    #[test]
+    fn operation_applies_to_both_sides() {
+        assert_eq!(Operation::Add.apply(7, 3), 10);
+        assert_eq!(Operation::Subtract.apply(7, 3), 4);
+        assert_eq!(Operation::Multiply.apply(7, 3), 21);
+        assert_eq!(Operation::Divide.apply(7, 3), 2);
+    }
+
+    #[test]
+    fn operation_saturates_and_divides_by_zero_to_zero() {
+        assert_eq!(Operation::Add.apply(i32::MAX, 1), i32::MAX);
+        assert_eq!(Operation::Subtract.apply(i32::MIN, 1), i32::MIN);
+        assert_eq!(Operation::Multiply.apply(i32::MAX, 2), i32::MAX);
+        assert_eq!(Operation::Divide.apply(7, 0), 0);
+    }
+}
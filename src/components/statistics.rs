@@ -0,0 +1,289 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write;
+
+use crate::clock::TICKS_PER_MINUTE;
+
+/// A stretch of recent play the statistics are summed over.
+pub struct Window {
+    pub name: &'static str,
+    pub ticks: u64,
+}
+
+pub const WINDOWS: &[Window] = &[
+    Window {
+        name: "1 min",
+        ticks: TICKS_PER_MINUTE,
+    },
+    Window {
+        name: "10 min",
+        ticks: TICKS_PER_MINUTE * 10,
+    },
+    Window {
+        name: "1 h",
+        ticks: TICKS_PER_MINUTE * 60,
+    },
+];
+
+/// Ticks whose records are summed up together.
+const BUCKET_TICKS: u64 = TICKS_PER_MINUTE / 60;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Flow {
+    Produced,
+    Consumed,
+}
+
+/// `amount` of `item` made or used up by `source` on `tick`.
+pub struct Record {
+    pub tick: u64,
+    pub source: String,
+    pub item: String,
+    pub flow: Flow,
+    pub amount: u32,
+}
+
+/// How much of one item was made and used up within a window.
+pub struct ItemTotals {
+    pub item: String,
+    pub produced: u32,
+    pub consumed: u32,
+}
+
+/// Who made or used up which item.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Key {
+    source: String,
+    item: String,
+    flow: Flow,
+}
+
+/// The amounts recorded under each key id during the `BUCKET_TICKS` ticks of bucket `number`.
+struct Bucket {
+    number: u64,
+    amounts: Vec<(usize, u32)>,
+}
+
+/// Everything made and used up within the longest window, summed per bucket, oldest first.
+#[derive(Default)]
+pub struct Statistics {
+    /// Every key seen, at the id buckets count it under.
+    keys: Vec<Key>,
+    ids: HashMap<Key, usize>,
+    buckets: VecDeque<Bucket>,
+}
+
+impl Statistics {
+    pub fn record(&mut self, record: Record) {
+        let key = Key {
+            source: record.source,
+            item: record.item,
+            flow: record.flow,
+        };
+        let id = match self.ids.get(&key) {
+            Some(id) => *id,
+            None => {
+                self.keys.push(key.clone());
+                self.ids.insert(key, self.keys.len() - 1);
+                self.keys.len() - 1
+            }
+        };
+
+        let number = record.tick / BUCKET_TICKS;
+        if self
+            .buckets
+            .back()
+            .is_none_or(|bucket| bucket.number != number)
+        {
+            self.buckets.push_back(Bucket {
+                number,
+                amounts: vec![],
+            });
+        }
+        let amounts = &mut self.buckets.back_mut().unwrap().amounts;
+        match amounts.iter_mut().find(|(other, _)| *other == id) {
+            Some((_, amount)) => *amount += record.amount,
+            None => amounts.push((id, record.amount)),
+        }
+    }
+
+    /// Drops buckets that fell out of every window.
+    pub fn forget_before(&mut self, tick: u64) {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|bucket| (bucket.number + 1) * BUCKET_TICKS <= tick)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// The amounts of every bucket that started within `window`, with the tick it started on.
+    fn within<'a>(
+        &'a self,
+        now: u64,
+        window: &Window,
+    ) -> impl Iterator<Item = (u64, &'a Key, u32)> {
+        let start = now.saturating_sub(window.ticks);
+
+        self.buckets
+            .iter()
+            .map(|bucket| (bucket.number * BUCKET_TICKS, bucket))
+            .filter(move |(tick, _)| *tick > start)
+            .flat_map(move |(tick, bucket)| {
+                bucket
+                    .amounts
+                    .iter()
+                    .map(move |(id, amount)| (tick, &self.keys[*id], *amount))
+            })
+    }
+
+    /// Every item seen within `window`, the most produced first.
+    pub fn items(&self, now: u64, window: &Window) -> Vec<ItemTotals> {
+        let mut totals: HashMap<&str, (u32, u32)> = HashMap::new();
+
+        self.within(now, window).for_each(|(_, key, amount)| {
+            let total = totals.entry(&key.item).or_insert((0, 0));
+            match key.flow {
+                Flow::Produced => total.0 += amount,
+                Flow::Consumed => total.1 += amount,
+            }
+        });
+
+        let mut items: Vec<ItemTotals> = totals
+            .into_iter()
+            .map(|(item, (produced, consumed))| ItemTotals {
+                item: item.to_string(),
+                produced,
+                consumed,
+            })
+            .collect();
+        items.sort_by(|a, b| {
+            b.produced
+                .cmp(&a.produced)
+                .then(b.consumed.cmp(&a.consumed))
+                .then(a.item.cmp(&b.item))
+        });
+
+        items
+    }
+
+    /// Who produced or consumed `item` within `window`, the busiest first.
+    pub fn sources(&self, now: u64, window: &Window, item: &str, flow: Flow) -> Vec<(String, u32)> {
+        let mut totals: HashMap<&str, u32> = HashMap::new();
+
+        self.within(now, window)
+            .filter(|(_, key, _)| key.item == item && key.flow == flow)
+            .for_each(|(_, key, amount)| *totals.entry(&key.source).or_insert(0) += amount);
+
+        let mut sources: Vec<(String, u32)> = totals
+            .into_iter()
+            .map(|(source, amount)| (source.to_string(), amount))
+            .collect();
+        sources.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        sources
+    }
+
+    /// Splits `window` into `bins` equal slices, oldest first, and sums `item` in each.
+    pub fn history(
+        &self,
+        now: u64,
+        window: &Window,
+        item: &str,
+        flow: Flow,
+        bins: usize,
+    ) -> Vec<u32> {
+        let mut history = vec![0; bins];
+        let start = now.saturating_sub(window.ticks);
+
+        self.within(now, window)
+            .filter(|(_, key, _)| key.item == item && key.flow == flow)
+            .for_each(|(tick, _, amount)| {
+                let bin = ((tick - start - 1) * bins as u64 / window.ticks.max(1)) as usize;
+                history[bin.min(bins - 1)] += amount;
+            });
+
+        history
+    }
+
+    /// Produced and consumed amounts per source and item within `window`.
+    fn table(&self, now: u64, window: &Window) -> BTreeMap<(&str, &str), (u32, u32)> {
+        let mut table = BTreeMap::new();
+
+        self.within(now, window).for_each(|(_, key, amount)| {
+            let row = table
+                .entry((key.source.as_str(), key.item.as_str()))
+                .or_insert((0, 0));
+            match key.flow {
+                Flow::Produced => row.0 += amount,
+                Flow::Consumed => row.1 += amount,
+            }
+        });
+
+        table
+    }
+
+    pub fn to_csv(&self, now: u64) -> String {
+        let mut csv = String::from("window,source,item,produced,consumed\n");
+
+        WINDOWS.iter().for_each(|window| {
+            self.table(now, window)
+                .iter()
+                .for_each(|((source, item), (produced, consumed))| {
+                    writeln!(
+                        csv,
+                        "{},{},{},{},{}",
+                        window.name,
+                        csv_field(source),
+                        csv_field(item),
+                        produced,
+                        consumed
+                    )
+                    .unwrap();
+                });
+        });
+
+        csv
+    }
+
+    pub fn to_json(&self, now: u64) -> String {
+        let windows: Vec<String> = WINDOWS
+            .iter()
+            .map(|window| {
+                let rows: Vec<String> = self
+                    .table(now, window)
+                    .iter()
+                    .map(|((source, item), (produced, consumed))| {
+                        format!(
+                            "{{\"source\":{},\"item\":{},\"produced\":{},\"consumed\":{}}}",
+                            json_string(source),
+                            json_string(item),
+                            produced,
+                            consumed
+                        )
+                    })
+                    .collect();
+
+                format!(
+                    "{{\"window\":{},\"ticks\":{},\"rows\":[{}]}}",
+                    json_string(window.name),
+                    window.ticks,
+                    rows.join(",")
+                )
+            })
+            .collect();
+
+        format!("{{\"tick\":{},\"windows\":[{}]}}\n", now, windows.join(","))
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    match text.contains([',', '"']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
+
+fn json_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
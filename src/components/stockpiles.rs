@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+
+use crate::components::workers::HIGHEST_PRIORITY;
+
+type Tile = (i32, i32);
+
+/// How many items of one kind a stockpile tile holds.
+pub const TILE_STACK: usize = 10;
+
+/// Tiles painted onto the map that workers carry loose items to.
+#[derive(Debug, Clone)]
+pub struct Stockpile {
+    pub name: String,
+    pub tiles: BTreeSet<Tile>,
+    /// Names of the items it takes. An empty filter takes everything.
+    pub filter: Vec<String>,
+    /// Items are moved to zones of a higher priority that take them.
+    pub priority: u8,
+}
+
+impl Stockpile {
+    pub fn accepts(&self, item: &str) -> bool {
+        self.filter.is_empty() || self.filter.iter().any(|kind| kind == item)
+    }
+}
+
+/// Every stockpile zone, in the order they were added. A tile belongs to one zone at most.
+#[derive(Default)]
+pub struct Stockpiles {
+    pub zones: Vec<Stockpile>,
+}
+
+impl Stockpiles {
+    /// Adds an empty zone that takes everything and returns its index.
+    pub fn add(&mut self) -> usize {
+        let name = (1..)
+            .map(|number| format!("stockpile {}", number))
+            .find(|name| self.zones.iter().all(|zone| zone.name != *name))
+            .unwrap();
+
+        self.zones.push(Stockpile {
+            name,
+            tiles: BTreeSet::new(),
+            filter: vec![],
+            priority: HIGHEST_PRIORITY.div_ceil(2),
+        });
+        self.zones.len() - 1
+    }
+
+    pub fn zone_at(&self, tile: Tile) -> Option<&Stockpile> {
+        self.zones.iter().find(|zone| zone.tiles.contains(&tile))
+    }
+
+    /// Adds `tiles` to the zone at `index`, taking them away from any other zone.
+    pub fn paint(&mut self, index: usize, tiles: &[Tile]) {
+        for (other, zone) in self.zones.iter_mut().enumerate() {
+            match other == index {
+                true => zone.tiles.extend(tiles),
+                false => tiles.iter().for_each(|tile| {
+                    zone.tiles.remove(tile);
+                }),
+            }
+        }
+    }
+
+    pub fn erase(&mut self, index: usize, tiles: &[Tile]) {
+        if let Some(zone) = self.zones.get_mut(index) {
+            tiles.iter().for_each(|tile| {
+                zone.tiles.remove(tile);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn painting_moves_tiles_out_of_other_zones() {
+        let mut stockpiles = Stockpiles::default();
+        let first = stockpiles.add();
+        let second = stockpiles.add();
+
+        stockpiles.paint(first, &[(1, 1), (2, 1)]);
+        stockpiles.paint(second, &[(2, 1), (3, 1)]);
+
+        assert_eq!(stockpiles.zones[first].tiles, BTreeSet::from([(1, 1)]));
+        assert_eq!(
+            stockpiles.zones[second].tiles,
+            BTreeSet::from([(2, 1), (3, 1)])
+        );
+        assert_eq!(stockpiles.zone_at((2, 1)).unwrap().name, "stockpile 2");
+    }
+
+    #[test]
+    fn erasing_only_touches_its_own_zone() {
+        let mut stockpiles = Stockpiles::default();
+        let first = stockpiles.add();
+        let second = stockpiles.add();
+        stockpiles.paint(first, &[(1, 1), (2, 1)]);
+        stockpiles.paint(second, &[(3, 1)]);
+
+        stockpiles.erase(first, &[(2, 1), (3, 1)]);
+
+        assert_eq!(stockpiles.zones[first].tiles, BTreeSet::from([(1, 1)]));
+        assert_eq!(stockpiles.zones[second].tiles, BTreeSet::from([(3, 1)]));
+        assert!(stockpiles.zone_at((2, 1)).is_none());
+    }
+}
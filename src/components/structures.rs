@@ -0,0 +1,341 @@
+use specs::Entity;
+
+use crate::{Component, DenseVecStorage, FontCharType};
+
+/// Items that can be placed from the backpack onto the map.
+#[derive(Component, Debug)]
+pub struct Structure {}
+
+/// Entities other than the player whose `InBackpack` items can be moved in and out.
+#[derive(Component, Debug)]
+pub struct Container {}
+
+#[derive(Component, Debug)]
+pub struct Chest {}
+
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+pub enum Direction {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    pub fn clockwise(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn counterclockwise(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::North => "north",
+            Direction::East => "east",
+            Direction::South => "south",
+            Direction::West => "west",
+        }
+    }
+
+    /// The direction called `name`, or abbreviated to its first letter.
+    pub fn from_name(name: &str) -> Option<Direction> {
+        [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+        .into_iter()
+        .find(|direction| direction.name() == name || direction.name()[..1] == *name)
+    }
+}
+
+/// Rotatable structures, with one glyph per direction in north, east, south, west order.
+#[derive(Component, Debug)]
+pub struct Facing {
+    pub direction: Direction,
+    pub glyphs: [FontCharType; 4],
+}
+
+impl Facing {
+    pub fn glyph(&self) -> FontCharType {
+        self.glyph_towards(self.direction)
+    }
+
+    pub fn glyph_towards(&self, direction: Direction) -> FontCharType {
+        self.glyphs[direction as usize]
+    }
+
+    /// The tile in front of a structure at `x`, `y`.
+    pub fn front(&self, x: i32, y: i32) -> (i32, i32) {
+        let (delta_x, delta_y) = self.direction.delta();
+        (x + delta_x, y + delta_y)
+    }
+
+    /// The tile behind a structure at `x`, `y`.
+    pub fn back(&self, x: i32, y: i32) -> (i32, i32) {
+        let (delta_x, delta_y) = self.direction.delta();
+        (x - delta_x, y - delta_y)
+    }
+}
+
+/// Moves loose items lying on it one tile forward every `interval` ticks.
+#[derive(Component, Debug)]
+pub struct Belt {
+    pub interval: u64,
+}
+
+/// The left or right hand side of a structure, as seen looking the way it faces.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    pub fn other(&self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Side::Left => "left",
+            Side::Right => "right",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Side> {
+        [Side::Left, Side::Right]
+            .into_iter()
+            .find(|side| side.name() == name || side.name()[..1] == *name)
+    }
+
+    /// The tile on this side of `(x, y)` for something facing `direction`.
+    pub fn beside(&self, x: i32, y: i32, direction: Direction) -> (i32, i32) {
+        let (delta_x, delta_y) = match self {
+            Side::Left => direction.counterclockwise().delta(),
+            Side::Right => direction.clockwise().delta(),
+        };
+        (x + delta_x, y + delta_y)
+    }
+}
+
+/// Sends the items its belt brings in from behind out to the tiles on its left and right. It
+/// takes turns between the two unless it has a `priority` side, and uses the other side while
+/// the one it wants is blocked.
+#[derive(Component, Debug)]
+pub struct Splitter {
+    pub priority: Option<Side>,
+    pub next: Side,
+}
+
+impl Splitter {
+    /// The sides `item` may leave by, in the order they are tried. With a `filter`, only that
+    /// item goes left and everything else goes right, waiting for its side to clear.
+    pub fn outputs(&self, item: &str, filter: Option<&str>) -> Vec<Side> {
+        match filter {
+            Some(filter) if filter == item => vec![Side::Left],
+            Some(_) => vec![Side::Right],
+            None => {
+                let first = self.priority.unwrap_or(self.next);
+                vec![first, first.other()]
+            }
+        }
+    }
+}
+
+/// A splitter that sorts out one kind of item, once `filter` is set.
+#[derive(Component, Debug)]
+pub struct FilterSplitter {
+    pub filter: Option<String>,
+}
+
+/// Lets items in from the belts on its left and right and passes them on in front. When both
+/// sides have an item waiting, it takes turns unless it has a `priority` side.
+#[derive(Component, Debug)]
+pub struct Merger {
+    pub priority: Option<Side>,
+    pub next: Side,
+}
+
+impl Merger {
+    pub fn preferred(&self) -> Side {
+        self.priority.unwrap_or(self.next)
+    }
+}
+
+/// One end of a tunnel that carries items or fluid under up to `reach` tiles, whatever is built
+/// in between. Ends pair up by themselves: an unpaired end becomes the exit of the nearest unpaired
+/// end of the same kind facing the same way within reach behind it.
+#[derive(Component, Debug)]
+pub struct Tunnel {
+    pub reach: i32,
+    pub partner: Option<Entity>,
+    pub is_entrance: bool,
+}
+
+impl Tunnel {
+    /// The nearest end within `reach` tiles behind `(x, y)`, looking against `direction`, with
+    /// how far back it is. `end_at` tells which tiles hold an end that could pair.
+    pub fn search_behind(
+        x: i32,
+        y: i32,
+        direction: Direction,
+        reach: i32,
+        end_at: impl Fn((i32, i32)) -> Option<Entity>,
+    ) -> Option<(Entity, i32)> {
+        let (delta_x, delta_y) = direction.delta();
+
+        (1..=reach).find_map(|distance| {
+            end_at((x - delta_x * distance, y - delta_y * distance)).map(|end| (end, distance))
+        })
+    }
+}
+
+/// Every `interval` ticks, moves one item from behind it to the tile or container in front of it.
+#[derive(Component, Debug)]
+pub struct Inserter {
+    pub interval: u64,
+    pub filter: Option<String>,
+    pub stalled: bool,
+}
+
+/// Crafts `recipe` from the items and fluid it holds, spending the recipe's `craft_ticks` on each
+/// batch. `progress` counts the ticks spent on the current batch; an under-supplied machine only
+/// makes part of a tick's progress.
+#[derive(Component, Debug)]
+pub struct Machine {
+    pub recipe: Option<&'static str>,
+    pub progress: Option<f32>,
+}
+
+/// Machines that smelt, burning fuel instead of drawing power.
+#[derive(Component, Debug)]
+pub struct Furnace {}
+
+#[derive(Component, Debug, Clone)]
+pub struct BuildQueue {
+    pub structure: Entity,
+    pub x: i32,
+    pub y: i32,
+    pub direction: Direction,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct DeconstructQueue {
+    pub structure: Entity,
+}
+
+#[cfg(test)]
+mod tests {
+    use specs::{Builder, World, WorldExt};
+
+    use super::*;
+
+    #[test]
+    fn splitter_takes_turns_starting_with_next() {
+        let splitter = Splitter {
+            priority: None,
+            next: Side::Right,
+        };
+
+        assert_eq!(
+            splitter.outputs("Flint", None),
+            vec![Side::Right, Side::Left]
+        );
+    }
+
+    #[test]
+    fn splitter_prefers_its_priority_side() {
+        let splitter = Splitter {
+            priority: Some(Side::Left),
+            next: Side::Right,
+        };
+
+        assert_eq!(
+            splitter.outputs("Flint", None),
+            vec![Side::Left, Side::Right]
+        );
+    }
+
+    #[test]
+    fn filter_splitter_sorts_by_item() {
+        let splitter = Splitter {
+            priority: Some(Side::Right),
+            next: Side::Right,
+        };
+
+        assert_eq!(splitter.outputs("Flint", Some("Flint")), vec![Side::Left]);
+        assert_eq!(splitter.outputs("Log", Some("Flint")), vec![Side::Right]);
+    }
+
+    fn tunnel_ends(world: &mut World, count: usize) -> Vec<Entity> {
+        (0..count).map(|_| world.create_entity().build()).collect()
+    }
+
+    #[test]
+    fn search_behind_finds_the_nearest_end_against_the_direction() {
+        let mut world = World::new();
+        let ends = tunnel_ends(&mut world, 3);
+        let end_at = |tile| match tile {
+            (2, 5) => Some(ends[0]),
+            (4, 5) => Some(ends[1]),
+            (8, 5) => Some(ends[2]),
+            _ => None,
+        };
+
+        assert_eq!(
+            Tunnel::search_behind(6, 5, Direction::East, 4, end_at),
+            Some((ends[1], 2))
+        );
+        assert_eq!(
+            Tunnel::search_behind(6, 5, Direction::West, 4, end_at),
+            Some((ends[2], 2))
+        );
+    }
+
+    #[test]
+    fn search_behind_stops_at_reach() {
+        let mut world = World::new();
+        let ends = tunnel_ends(&mut world, 1);
+        let end_at = |tile| (tile == (5, 1)).then_some(ends[0]);
+
+        assert_eq!(
+            Tunnel::search_behind(5, 5, Direction::South, 4, end_at),
+            Some((ends[0], 4))
+        );
+        assert_eq!(
+            Tunnel::search_behind(5, 5, Direction::South, 3, end_at),
+            None
+        );
+        assert_eq!(
+            Tunnel::search_behind(5, 1, Direction::South, 4, end_at),
+            None
+        );
+    }
+}
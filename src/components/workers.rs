@@ -0,0 +1,69 @@
+use specs::Entity;
+
+use crate::{Component, DenseVecStorage};
+
+/// Jobs are worked on from the highest priority down.
+pub const HIGHEST_PRIORITY: u8 = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Job {
+    /// Harvests resource nodes that yield `item` until the worker is full, then takes what it
+    /// carries to the container `to`.
+    Gather { item: String, to: Option<Entity> },
+    /// Carries everything in the container `from` over to the container `to`.
+    Haul {
+        from: Option<Entity>,
+        to: Option<Entity>,
+    },
+    /// Takes the ingredients of `recipe` out of the container `at`, crafts it by hand next to it
+    /// and puts what it made back in.
+    Craft {
+        recipe: &'static str,
+        at: Option<Entity>,
+    },
+}
+
+impl Job {
+    /// The containers the job uses, in the order they are picked on the worker screen.
+    pub fn containers(&self) -> Vec<Option<Entity>> {
+        match self {
+            Job::Gather { to, .. } => vec![*to],
+            Job::Haul { from, to } => vec![*from, *to],
+            Job::Craft { at, .. } => vec![*at],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    pub job: Job,
+    pub priority: u8,
+}
+
+/// Works through its jobs on its own, carrying what it gathers, hauls and crafts in its own
+/// inventory.
+#[derive(Component, Debug)]
+pub struct Worker {
+    pub jobs: Vec<Assignment>,
+    /// The tiles left to walk to where the job at hand is done, nearest first.
+    pub path: Vec<(i32, i32)>,
+    /// Ticks it takes to take a step or do a bit of work.
+    pub interval: u64,
+    pub status: String,
+    /// Set while the worker has jobs but can not get on with any of them.
+    pub is_stuck: bool,
+    /// Loose items it picked up to take to a stockpile.
+    pub hauling: Vec<Entity>,
+    /// Materials it fetched for construction sites.
+    pub delivering: Vec<Entity>,
+}
+
+impl Worker {
+    /// Indices into `jobs`, highest priority first and in list order among equals.
+    pub fn by_priority(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.jobs.len()).collect();
+        order.sort_by_key(|index| HIGHEST_PRIORITY - self.jobs[*index].priority);
+
+        order
+    }
+}
@@ -1,5 +1,5 @@
 use std::env;
-use std::fmt::{Display, Formatter, Write};
+use std::fmt::{Display, Formatter};
 use std::fs::{read_to_string, File};
 
 use knuffel::Decode;
@@ -21,7 +21,7 @@ pub struct Performance {
 
 impl Display for Performance {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&*format!(
+        f.write_str(&format!(
             "Performance(show_fps={}, fps_cap={})",
             self.show_fps, self.fps_cap
         ))
@@ -46,17 +46,14 @@ pub fn load_config() -> Config {
     )
     .unwrap();
 
-    *config
-        .iter()
-        .nth(0)
-        .unwrap_or(&Config::Performance(Performance {
-            show_fps: true,
-            fps_cap: 144,
-        }))
+    *config.first().unwrap_or(&Config::Performance(Performance {
+        show_fps: true,
+        fps_cap: 144,
+    }))
 }
 
 fn parse_config(path: &String) -> Result<Vec<Config>, Error> {
     let text = read_to_string(path).unwrap();
 
-    knuffel::parse::<Vec<Config>>(&*path, &text)
+    knuffel::parse::<Vec<Config>>(path, &text)
 }
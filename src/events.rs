@@ -0,0 +1,71 @@
+use specs::Entity;
+
+use crate::components::structures::Direction;
+
+/// Something that happened in the world this tick, for systems that keep score.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// A recipe finished, by hand or in a machine, making one `item`.
+    Crafted {
+        item: String,
+        by: Entity,
+    },
+    Harvested {
+        item: String,
+        by: Entity,
+    },
+    /// A recipe, a technology or a burner used up `amount` of `item`.
+    Consumed {
+        item: String,
+        amount: u32,
+        by: Entity,
+    },
+    /// A construction site put down `entity`, a structure called `structure`. `by_player` is set
+    /// when the player placed it by hand rather than planning it in a blueprint.
+    Built {
+        structure: String,
+        entity: Entity,
+        x: i32,
+        y: i32,
+        direction: Direction,
+        by_player: bool,
+    },
+    /// The player took `entity` down into their backpack.
+    Deconstructed {
+        structure: String,
+        entity: Entity,
+        x: i32,
+        y: i32,
+        direction: Direction,
+    },
+    /// The largest fluid network grew to `members` pipes, pumps and tanks.
+    FluidNetworkGrown {
+        members: usize,
+    },
+}
+
+impl Event {
+    /// The item this event brought into the world, if any.
+    pub fn produced(&self) -> Option<&str> {
+        match self {
+            Event::Crafted { item, .. } | Event::Harvested { item, .. } => Some(item),
+            _ => None,
+        }
+    }
+}
+
+/// Events of the current tick. Cleared when the next tick starts.
+#[derive(Default)]
+pub struct Events {
+    pub this_tick: Vec<Event>,
+}
+
+impl Events {
+    pub fn emit(&mut self, event: Event) {
+        self.this_tick.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        self.this_tick.clear();
+    }
+}
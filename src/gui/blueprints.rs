@@ -0,0 +1,122 @@
+use bracket_lib::color::{DARKCYAN, GREY, WHITE};
+use specs::{Join, WorldExt};
+
+use crate::components::blueprints::{Blueprint, BlueprintLibrary};
+use crate::components::structures::{Direction, Facing};
+use crate::{to_cp437, BTerm, FontCharType, Name, Renderable, State, UserInterfaceState, World};
+use crate::{BLACK, RGB};
+
+/// The glyph a structure called `name` has when facing `direction`, borrowed from any such
+/// structure in the world, or its initial when there is none.
+pub fn structure_glyph(world: &World, name: &str, direction: Direction) -> FontCharType {
+    let names = world.read_storage::<Name>();
+    let renderables = world.read_storage::<Renderable>();
+    let facings = world.read_storage::<Facing>();
+    let entities = world.entities();
+
+    (&entities, &names, &renderables)
+        .join()
+        .find(|(_, known, _)| known.name == name)
+        .map(|(entity, _, render)| match facings.get(entity) {
+            Some(facing) => facing.glyph_towards(direction),
+            None => render.glyph,
+        })
+        .unwrap_or_else(|| to_cp437(name.chars().next().unwrap_or('?')))
+}
+
+pub fn show_blueprints(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let library = state.world.fetch::<BlueprintLibrary>();
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, "blueprints");
+
+    if library.blueprints.is_empty() {
+        ctx.print(4, 4, "copy an area in build mode");
+    } else {
+        ctx.set(
+            4,
+            4 + ui.selected_option as i32,
+            RGB::named(WHITE),
+            RGB::named(BLACK),
+            to_cp437('→'),
+        );
+    }
+
+    library
+        .blueprints
+        .iter()
+        .enumerate()
+        .for_each(|(index, blueprint)| {
+            let marker = match ui.blueprint == Some(index) {
+                true => "*",
+                false => "",
+            };
+            ctx.print(
+                6,
+                4 + index as i32,
+                format!("{}{} ({})", blueprint.name, marker, blueprint.entries.len()),
+            );
+        });
+
+    ctx.print_color(
+        4,
+        28,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "enter: use  d: delete",
+    );
+    ctx.print_color(
+        4,
+        29,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "x: export  i: import",
+    );
+    ctx.print_color(
+        4,
+        30,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "p: paste in build mode",
+    );
+
+    if let Some(blueprint) = library.blueprints.get(ui.selected_option) {
+        show_preview(&state.world, ctx, blueprint);
+    }
+}
+
+/// Draws the structures of a blueprint as they would be laid out, cut off at the pane's edge.
+fn show_preview(world: &World, ctx: &mut BTerm, blueprint: &Blueprint) {
+    ctx.draw_box(31, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, &blueprint.name);
+    ctx.print(
+        33,
+        4,
+        format!("{}x{} tiles", blueprint.width, blueprint.height),
+    );
+
+    blueprint
+        .entries
+        .iter()
+        .filter(|entry| entry.x < 26 && entry.y < 24)
+        .for_each(|entry| {
+            let ghost = &entry.ghost;
+            ctx.set(
+                33 + entry.x,
+                6 + entry.y,
+                RGB::named(DARKCYAN),
+                RGB::named(BLACK),
+                structure_glyph(world, &ghost.structure, ghost.direction),
+            );
+        });
+}
+
+/// Tints the area between where a selection started and the build cursor.
+pub fn draw_selection(ctx: &mut BTerm, start: (i32, i32), cursor: (i32, i32)) {
+    for x in start.0.min(cursor.0)..=start.0.max(cursor.0) {
+        for y in start.1.min(cursor.1)..=start.1.max(cursor.1) {
+            ctx.set_bg(x, y, RGB::named(DARKCYAN) * 0.5);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bracket_lib::color::{GREEN, GREY, MAGENTA, ORANGE, RED, YELLOW};
+use specs::WorldExt;
+
+use crate::components::bottlenecks::{Bottlenecks, Status};
+use crate::{BTerm, Name, Position, World, BLACK, RGB};
+
+fn status_color(status: Status) -> RGB {
+    match status {
+        Status::Working => RGB::named(GREEN),
+        Status::Idle => RGB::named(GREY),
+        Status::InputStarved => RGB::named(YELLOW),
+        Status::OutputBlocked => RGB::named(ORANGE),
+        Status::Unpowered => RGB::named(RED),
+        Status::Unsupplied => RGB::named(MAGENTA),
+    }
+}
+
+/// Tints every machine, chest, belt and inserter by its status and marks what starved lines were traced back to.
+pub fn draw_bottleneck_overlay(world: &World, ctx: &mut BTerm) {
+    let bottlenecks = world.fetch::<Bottlenecks>();
+    let positions = world.read_storage::<Position>();
+
+    for finding in bottlenecks.findings.iter() {
+        if let Some(position) = positions.get(finding.entity) {
+            ctx.set_bg(position.x, position.y, status_color(finding.status) * 0.5);
+        }
+    }
+
+    for cause in bottlenecks.causes.iter() {
+        if let Some(position) = positions.get(cause.entity) {
+            ctx.set_bg(position.x, position.y, RGB::named(MAGENTA));
+        }
+    }
+}
+
+/// Lists what holds up production, for the right side menu, with how much it stalls.
+pub fn show_bottlenecks(world: &World, ctx: &mut BTerm, x: i32, y: i32) {
+    let bottlenecks = world.fetch::<Bottlenecks>();
+    let names = world.read_storage::<Name>();
+
+    ctx.print(x, y, "bottlenecks");
+    if bottlenecks.findings.is_empty() {
+        ctx.print(x, y + 2, "nothing to watch");
+        return;
+    }
+
+    let working = bottlenecks
+        .findings
+        .iter()
+        .filter(|finding| finding.status == Status::Working)
+        .count();
+    ctx.print_color(
+        x,
+        y + 2,
+        status_color(Status::Working),
+        RGB::named(BLACK),
+        format!("{}/{} working", working, bottlenecks.findings.len()),
+    );
+
+    let mut line = y + 4;
+    for cause in bottlenecks.causes.iter().take(8) {
+        let name = names
+            .get(cause.entity)
+            .map_or("Unknown".to_string(), |name| name.name.clone());
+
+        ctx.print(x, line, format!("{:.12} #{}", name, cause.entity.id()));
+        ctx.print_color(
+            x + 1,
+            line + 1,
+            status_color(cause.status),
+            RGB::named(BLACK),
+            format!("{}, {}", cause.status.name(), cause.impact),
+        );
+
+        line += 3;
+    }
+}
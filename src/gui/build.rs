@@ -0,0 +1,336 @@
+use bracket_lib::color::{CYAN, GREY, WHITE};
+use specs::{Entity, Join, WorldExt};
+
+use crate::components::blueprints::{BlueprintLibrary, Ghost};
+use crate::components::construction::ConstructionSite;
+use crate::components::fluids::FluidNetworks;
+use crate::components::items::stacks_of;
+use crate::components::rails::{Cart, RailSignal, Station};
+use crate::components::structures::{
+    Direction, Facing, FilterSplitter, Merger, Splitter, Structure, Tunnel,
+};
+use crate::components::workers::Worker;
+use crate::gui::blueprints::draw_selection;
+use crate::gui::signals::draw_signal_overlay;
+use crate::systems::construction::{build_ticks, missing_materials};
+use crate::{
+    to_cp437, BTerm, InBackpack, Name, Position, Renderable, State, UserInterfaceState, World,
+    BLACK, RGB,
+};
+
+/// Every stack of placeable structures in the player's backpack, sorted by name.
+pub fn structure_stacks(world: &World) -> Vec<(String, Vec<Entity>)> {
+    let player = world.fetch::<Entity>();
+    let entities = world.entities();
+    let backpack = world.read_storage::<InBackpack>();
+    let names = world.read_storage::<Name>();
+    let structures = world.read_storage::<Structure>();
+
+    stacks_of(*player, &entities, &backpack, &names)
+        .into_iter()
+        .filter(|(_, stack)| structures.contains(stack[0]))
+        .collect()
+}
+
+pub fn show_build(state: &mut State, ctx: &mut BTerm) {
+    let stacks = structure_stacks(&state.world);
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let renderables = state.world.read_storage::<Renderable>();
+    let facings = state.world.read_storage::<Facing>();
+    let library = state.world.fetch::<BlueprintLibrary>();
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, "build");
+
+    if stacks.is_empty() {
+        ctx.print(4, 4, "you have nothing to build");
+    } else {
+        ctx.set(
+            4,
+            4 + ui.selected_option,
+            RGB::named(WHITE),
+            RGB::named(BLACK),
+            to_cp437('→'),
+        );
+    }
+
+    let mut y = 4;
+    stacks.iter().for_each(|(name, stack)| {
+        ctx.print(6, y, format!("{} x{}", name, stack.len()));
+        y += 1;
+    });
+
+    let copy = match ui.selection {
+        None => "c: select area",
+        Some(_) => "c: copy selection",
+    };
+    ctx.print_color(4, 23, RGB::named(GREY), RGB::named(BLACK), copy);
+    let paste = ui
+        .blueprint
+        .and_then(|index| library.blueprints.get(index))
+        .map_or("none".to_string(), |blueprint| blueprint.name.clone());
+    ctx.print_color(
+        4,
+        24,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        format!("p: paste {}", paste),
+    );
+    ctx.print_color(4, 25, RGB::named(GREY), RGB::named(BLACK), "x: cancel site");
+    ctx.print_color(
+        4,
+        26,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "d: take down  z/Z: undo/redo",
+    );
+    ctx.print_color(4, 27, RGB::named(GREY), RGB::named(BLACK), "tab: next");
+    ctx.print_color(4, 28, RGB::named(GREY), RGB::named(BLACK), "enter: place");
+    ctx.print_color(
+        4,
+        29,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        format!("r: rotate ({})", ui.facing.name()),
+    );
+    ctx.print_color(
+        4,
+        30,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "f: configure  g: signals",
+    );
+
+    show_hovered(&state.world, ctx, ui.cursor);
+    draw_signal_overlay(&state.world, ctx, ui.cursor);
+
+    let (x, y) = ui.cursor;
+    let selected = stacks.get(ui.selected_option).map(|(_, stack)| stack[0]);
+    if let Some(structure) = selected {
+        show_tunnel_preview(&state.world, ctx, structure, (x, y), ui.facing);
+    }
+
+    if let Some(start) = ui.selection {
+        draw_selection(ctx, start, ui.cursor);
+    }
+
+    let glyph = match selected.and_then(|structure| facings.get(structure)) {
+        Some(facing) => facing.glyph_towards(ui.facing),
+        None => selected
+            .and_then(|structure| renderables.get(structure))
+            .map_or(to_cp437('X'), |render| render.glyph),
+    };
+    ctx.set(x, y, RGB::named(BLACK), RGB::named(CYAN), glyph);
+}
+
+/// Shows what a tunnel end placed at the cursor would pair with, or how far ahead its exit can
+/// go when it would be an entrance.
+fn show_tunnel_preview(
+    world: &World,
+    ctx: &mut BTerm,
+    structure: Entity,
+    (x, y): (i32, i32),
+    direction: Direction,
+) {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let names = world.read_storage::<Name>();
+    let facings = world.read_storage::<Facing>();
+    let tunnels = world.read_storage::<Tunnel>();
+    let structures = world.read_storage::<Structure>();
+
+    let (reach, name) = match (tunnels.get(structure), names.get(structure)) {
+        (Some(tunnel), Some(name)) => (tunnel.reach, name),
+        _ => return,
+    };
+    let is_free = !(&positions, &structures)
+        .join()
+        .any(|(position, _)| position.x == x && position.y == y);
+
+    let entrance = Tunnel::search_behind(x, y, direction, reach, |(end_x, end_y)| {
+        (&entities, &tunnels, &positions, &facings, &names)
+            .join()
+            .find(|(_, tunnel, position, facing, known)| {
+                (position.x, position.y) == (end_x, end_y)
+                    && tunnel.partner.is_none()
+                    && facing.direction == direction
+                    && known.name == name.name
+            })
+            .map(|(end, _, _, _, _)| end)
+    });
+
+    let (delta_x, delta_y) = direction.delta();
+    match entrance {
+        Some((_, distance)) => {
+            (1..=distance).for_each(|step| {
+                ctx.set_bg(
+                    x - delta_x * step,
+                    y - delta_y * step,
+                    RGB::named(CYAN) * 0.5,
+                )
+            });
+            if is_free {
+                ctx.print(4, 22, format!("exit, {} from entrance", distance));
+            }
+        }
+        None => {
+            (1..=reach).for_each(|step| {
+                ctx.set_bg(
+                    x + delta_x * step,
+                    y + delta_y * step,
+                    RGB::named(GREY) * 0.4,
+                )
+            });
+            if is_free {
+                ctx.print(4, 22, format!("entrance, reaches {}", reach));
+            }
+        }
+    }
+}
+
+/// Describes the construction site under the build cursor: what it still needs delivered, or how
+/// far along it is.
+fn show_site(world: &World, ctx: &mut BTerm, (x, y): (i32, i32)) {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let ghosts = world.read_storage::<Ghost>();
+    let sites = world.read_storage::<ConstructionSite>();
+    let backpack = world.read_storage::<InBackpack>();
+    let names = world.read_storage::<Name>();
+
+    let hovered = (&entities, &positions, &ghosts, &sites)
+        .join()
+        .find(|(_, position, _, _)| position.x == x && position.y == y);
+    let (entity, ghost, site) = match hovered {
+        None => return,
+        Some((entity, _, ghost, site)) => (entity, ghost, site),
+    };
+
+    ctx.print(4, 20, format!("here: {} site", ghost.structure));
+
+    let held: Vec<String> = (&backpack, &names)
+        .join()
+        .filter(|(pack, _)| pack.owner == entity)
+        .map(|(_, name)| name.name.clone())
+        .collect();
+    let missing = missing_materials(&ghost.structure, &held);
+    match (missing.is_empty(), &site.problem) {
+        (false, _) => {
+            let needs: Vec<String> = missing
+                .iter()
+                .map(|(name, amount)| format!("{} {}", amount, name))
+                .collect();
+            ctx.print(
+                4,
+                21,
+                format!("{:.27}", format!("needs {}", needs.join(", "))),
+            );
+        }
+        (true, Some(problem)) => ctx.print(4, 21, format!("{:.27}", problem)),
+        (true, None) => {
+            let ticks = build_ticks(&ghost.structure).max(1);
+            let percent = (site.progress * 100 / ticks).min(100);
+            ctx.print(4, 21, format!("building {}%", percent));
+        }
+    }
+}
+
+/// Describes the structure under the build cursor, how a splitter or merger is configured, what a
+/// cart or worker is up to, the tunnel end it is linked to and the fluid network it belongs to.
+fn show_hovered(world: &World, ctx: &mut BTerm, (x, y): (i32, i32)) {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let structures = world.read_storage::<Structure>();
+    let names = world.read_storage::<Name>();
+    let networks = world.fetch::<FluidNetworks>();
+    let splitters = world.read_storage::<Splitter>();
+    let filter_splitters = world.read_storage::<FilterSplitter>();
+    let mergers = world.read_storage::<Merger>();
+    let tunnels = world.read_storage::<Tunnel>();
+    let carts = world.read_storage::<Cart>();
+    let stations = world.read_storage::<Station>();
+    let rail_signals = world.read_storage::<RailSignal>();
+    let workers = world.read_storage::<Worker>();
+
+    let hovered = (&entities, &positions, &structures, &names)
+        .join()
+        .filter(|(_, position, _, _)| position.x == x && position.y == y)
+        .max_by_key(|(entity, _, _, _)| carts.contains(*entity) || workers.contains(*entity));
+    let (entity, name) = match hovered {
+        None => return show_site(world, ctx, (x, y)),
+        Some((entity, _, _, name)) => (entity, name),
+    };
+
+    ctx.print(4, 20, format!("here: {}", name));
+
+    let filter = filter_splitters
+        .get(entity)
+        .and_then(|splitter| splitter.filter.as_ref());
+    let priority = splitters
+        .get(entity)
+        .map(|splitter| splitter.priority)
+        .or(mergers.get(entity).map(|merger| merger.priority));
+    match (filter, priority) {
+        (Some(filter), _) => ctx.print(4, 21, format!("{} left, rest right", filter)),
+        (None, Some(Some(side))) => ctx.print(4, 21, format!("prefers {}", side.name())),
+        (None, Some(None)) => ctx.print(4, 21, "takes turns"),
+        (None, None) => {}
+    }
+
+    if let Some(cart) = carts.get(entity) {
+        ctx.print(4, 21, cart.status());
+
+        if let Some((x, y)) = cart.route.last() {
+            ctx.set_bg(*x, *y, RGB::named(CYAN) * 0.5);
+        }
+    }
+    if let Some(worker) = workers.get(entity) {
+        ctx.print(4, 21, format!("{:.27}", worker.status));
+
+        if let Some((x, y)) = worker.path.last() {
+            ctx.set_bg(*x, *y, RGB::named(CYAN) * 0.5);
+        }
+    }
+    if let Some(name) = stations
+        .get(entity)
+        .and_then(|station| station.name.as_ref())
+    {
+        ctx.print(4, 21, format!("station {}", name));
+    }
+    if let Some(signal) = rail_signals.get(entity) {
+        match signal.is_clear {
+            true => ctx.print(4, 21, "blocks around are clear"),
+            false => ctx.print(4, 21, "a cart holds a block"),
+        }
+    }
+
+    if let Some(tunnel) = tunnels.get(entity) {
+        let partner = tunnel.partner.and_then(|partner| positions.get(partner));
+        match partner {
+            None => ctx.print(4, 22, "not linked"),
+            Some(partner) => {
+                let role = match tunnel.is_entrance {
+                    true => "exit",
+                    false => "entrance",
+                };
+                let distance = (partner.x - x).abs() + (partner.y - y).abs();
+                ctx.print(4, 22, format!("linked to {} {} away", role, distance));
+                ctx.set_bg(partner.x, partner.y, RGB::named(CYAN) * 0.5);
+            }
+        }
+    }
+
+    if let Some(network) = networks.of(entity) {
+        ctx.print(
+            4,
+            21,
+            format!(
+                "{} {:.0}/{:.0} ({:.0}%)",
+                network.fluid.map_or("empty", |fluid| fluid.name()),
+                network.volume,
+                network.capacity,
+                network.pressure() * 100.
+            ),
+        );
+    }
+}
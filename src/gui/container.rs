@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use bracket_lib::color::{GREY, WHITE, YELLOW};
+use specs::{Entity, WorldExt};
+
+use crate::components::fluids::FluidBox;
+use crate::components::items::stacks_of;
+use crate::components::power::{Burner, Heat};
+use crate::components::structures::Machine;
+use crate::systems::craft::find_recipe;
+use crate::{to_cp437, BTerm, InBackpack, Name, State, UserInterfaceState, World, BLACK, RGB};
+
+#[derive(PartialEq, Copy, Clone, Default)]
+pub enum Pane {
+    #[default]
+    Backpack,
+    Container,
+}
+
+/// The stacks in the focused pane, and who receives them when they are moved.
+pub fn focused_stacks(world: &World) -> (BTreeMap<String, Vec<Entity>>, Option<Entity>) {
+    let ui = world.fetch::<UserInterfaceState>();
+    let player = world.fetch::<Entity>();
+    let entities = world.entities();
+    let backpack = world.read_storage::<InBackpack>();
+    let names = world.read_storage::<Name>();
+
+    match (ui.container, ui.pane) {
+        (None, _) => (BTreeMap::new(), None),
+        (Some(container), Pane::Backpack) => (
+            stacks_of(*player, &entities, &backpack, &names),
+            Some(container),
+        ),
+        (Some(container), Pane::Container) => (
+            stacks_of(container, &entities, &backpack, &names),
+            Some(*player),
+        ),
+    }
+}
+
+pub fn show_container(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let player = state.world.fetch::<Entity>();
+    let entities = state.world.entities();
+    let backpack = state.world.read_storage::<InBackpack>();
+    let names = state.world.read_storage::<Name>();
+
+    let container = match ui.container {
+        None => return,
+        Some(container) => container,
+    };
+
+    let panes = [
+        (
+            Pane::Backpack,
+            2,
+            "backpack".to_string(),
+            stacks_of(*player, &entities, &backpack, &names),
+        ),
+        (
+            Pane::Container,
+            31,
+            names
+                .get(container)
+                .map_or("container".to_string(), |name| {
+                    name.to_string().to_lowercase()
+                }),
+            stacks_of(container, &entities, &backpack, &names),
+        ),
+    ];
+
+    for (pane, x, title, stacks) in panes.iter() {
+        let border = match *pane == ui.pane {
+            true => RGB::named(YELLOW),
+            false => RGB::named(WHITE),
+        };
+
+        ctx.draw_box(*x, 2, 28, 30, border, RGB::named(BLACK));
+        ctx.print_centered_at(x + 14, 2, title);
+
+        if *pane == ui.pane && !stacks.is_empty() {
+            ctx.set(
+                x + 2,
+                4 + ui.selected_option,
+                RGB::named(WHITE),
+                RGB::named(BLACK),
+                to_cp437('→'),
+            );
+        }
+
+        let mut y = 4;
+        stacks.iter().for_each(|(name, stack)| {
+            ctx.print(x + 4, y, format!("{} x{}", name, stack.len()));
+            y += 1;
+        });
+    }
+
+    show_machine(&state.world, ctx, container);
+    show_burner(&state.world, ctx, container);
+
+    ctx.print_color(
+        4,
+        31,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "enter: one  s: stack  a: all  h/l: pane",
+    );
+}
+
+/// Whether a burner is lit, how long its current fuel lasts and what it gives off.
+fn show_burner(world: &World, ctx: &mut BTerm, burner: Entity) {
+    let burners = world.read_storage::<Burner>();
+    let heats = world.read_storage::<Heat>();
+
+    let burner_state = match burners.get(burner) {
+        None => return,
+        Some(burner_state) => burner_state,
+    };
+
+    let status = match (burner_state.ignited, burner_state.is_burning()) {
+        (false, _) => "unlit (f: light)".to_string(),
+        (true, false) => "lit, out of fuel".to_string(),
+        (true, true) => format!("burning, {} ticks left", burner_state.remaining),
+    };
+    ctx.print(35, 26, status);
+
+    if let (Some(heat), true) = (heats.get(burner), burner_state.is_burning()) {
+        ctx.print(
+            35,
+            27,
+            format!("heat {:.0}, light {}", heat.output, heat.light_radius),
+        );
+    }
+}
+
+/// The recipe, progress and fluid of a machine, below its pane.
+fn show_machine(world: &World, ctx: &mut BTerm, machine: Entity) {
+    let machines = world.read_storage::<Machine>();
+    let boxes = world.read_storage::<FluidBox>();
+
+    let machine_state = match machines.get(machine) {
+        None => return,
+        Some(machine_state) => machine_state,
+    };
+
+    let status = match (
+        machine_state.recipe.and_then(find_recipe),
+        machine_state.progress,
+    ) {
+        (None, _) => "no recipe".to_string(),
+        (Some(recipe), None) => format!("{}: waiting", recipe.result_item_name),
+        (Some(recipe), Some(progress)) => format!(
+            "{}: {}%",
+            recipe.result_item_name,
+            (progress * 100. / recipe.craft_ticks.max(1) as f32).min(100.) as u32
+        ),
+    };
+    ctx.print(35, 28, status);
+
+    if let Some(fluid_box) = boxes.get(machine) {
+        ctx.print(
+            35,
+            29,
+            format!(
+                "{} {:.0}/{:.0}",
+                fluid_box.fluid.map_or("no fluid", |fluid| fluid.name()),
+                fluid_box.volume,
+                fluid_box.capacity
+            ),
+        );
+    }
+}
@@ -1,18 +1,25 @@
 use std::collections::{BTreeMap, HashMap};
-use std::os::macos::raw::stat;
 
-use bracket_lib::color::{GREEN, RED, WHITE};
-use specs::hibitset::BitSetLike;
+use bracket_lib::color::{DARKGRAY, GREEN, GREY, RED, WHITE};
 use specs::shred::Fetch;
-use specs::{Entity, Join, ReadStorage, WorldExt};
+use specs::{Entity, Join, WorldExt};
 
 use MenuMode::*;
 
+use crate::clock::Clock;
+use crate::components::items::{backpack_load, Capacity, Encumbered, ResourceNode, Weight};
+use crate::components::milestones::Milestones;
+use crate::components::research::Research;
+use crate::gui::bottlenecks::show_bottlenecks;
+use crate::gui::power::show_power;
 use crate::map::{xy_to_idx, TileType};
-use crate::systems::craft::RECIPES;
+use crate::player::adjacent_container;
+use crate::systems::craft::{Recipe, RECIPES};
+use crate::systems::milestones::{Condition, GOALS};
+use crate::systems::research::is_unlocked;
 use crate::{
-    new_map, to_cp437, BTerm, CraftQueue, InBackpack, Item, Log, MenuMode, Name, Player, Position,
-    Renderable, State, UserInterfaceState, World, BLACK, RGB,
+    to_cp437, BTerm, CraftQueue, InBackpack, MenuMode, Name, Player, Position, State,
+    UserInterfaceState, World, BLACK, RGB,
 };
 
 #[derive(Clone)]
@@ -41,28 +48,33 @@ pub fn draw_menu(world: &World, ctx: &mut BTerm) {
         return;
     }
 
-    let height = match ui.log {
-        true => 42,
-        false => 49,
-    };
-    ctx.draw_box(60, 0, 19, height, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.draw_box(60, 0, 19, 49, RGB::named(WHITE), RGB::named(BLACK));
+
+    let clock = world.fetch::<Clock>();
+    let (hour, minute) = clock.hour_and_minute();
+    ctx.print(
+        62,
+        0,
+        format!(" day {} {:02}:{:02} ", clock.day(), hour, minute),
+    );
 
     match ui.menu_mode {
-        Default | Inventory | Craft => show_options(ctx, 62, 2),
+        Default | Inventory | Craft | Build | Container | Research | Statistics | Planner
+        | Blueprints | Signals | Schedule | Worker | Stockpiles => {
+            show_options(ctx, 62, 2);
+            show_objectives(world, ctx, 62, 31);
+        }
         Interact => show_interact(world, ctx, 62, 2),
+        Power => show_power(world, ctx, 62, 2),
+        Bottlenecks => show_bottlenecks(world, ctx, 62, 2),
     }
 }
 
 fn show_interact(world: &World, ctx: &mut BTerm, x: i32, y: i32) {
-    struct Option {
-        glyph: String,
-        name: String,
-    };
-
     {
         let map = world.fetch::<Vec<TileType>>();
-        let mut players = world.write_storage::<Player>();
-        let mut positions = world.write_storage::<Position>();
+        let players = world.read_storage::<Player>();
+        let positions = world.read_storage::<Position>();
 
         let (mut player_x, mut player_y) = (0, 0);
         for (_player, position) in (&players, &positions).join() {
@@ -70,12 +82,12 @@ fn show_interact(world: &World, ctx: &mut BTerm, x: i32, y: i32) {
             player_y = position.y;
         }
 
-        (0..3).into_iter().for_each(|raw_offset_x| {
-            let offset_x = raw_offset_x - 1 + &player_x;
-            (0..3).into_iter().for_each(|raw_offset_y| {
-                let offset_y = raw_offset_y - 1 + &player_y;
+        (0..3).for_each(|raw_offset_x| {
+            let offset_x = raw_offset_x - 1 + player_x;
+            (0..3).for_each(|raw_offset_y| {
+                let offset_y = raw_offset_y - 1 + player_y;
 
-                if &offset_x == &player_x && &offset_y == &player_y {
+                if offset_x == player_x && offset_y == player_y {
                     return;
                 }
 
@@ -83,14 +95,36 @@ fn show_interact(world: &World, ctx: &mut BTerm, x: i32, y: i32) {
                 let black = RGB::named(BLACK);
                 let tile = map[xy_to_idx(offset_x, offset_y)];
 
-                match tile {
-                    t => t.render_custom(ctx, offset_x, offset_y, red, black),
-                }
+                tile.render_custom(ctx, offset_x, offset_y, red, black);
+            })
+        });
+
+        let entities = world.entities();
+        let nodes = world.read_storage::<ResourceNode>();
+        let names = world.read_storage::<Name>();
+        let nearby: Vec<String> = (&entities, &nodes, &positions, &names)
+            .join()
+            .filter(|(_, _, position, _)| {
+                (position.x - player_x).abs() <= 1 && (position.y - player_y).abs() <= 1
             })
+            .map(|(_, node, _, name)| format!("{} {}/{}", name, node.remaining, node.max_yield))
+            .collect();
+        let container = adjacent_container(world);
+
+        if nearby.is_empty() && container.is_none() {
+            ctx.print(x, y, "no objects to");
+            ctx.print(x, y + 1, "interact here")
+        }
+
+        nearby.iter().enumerate().for_each(|(index, line)| {
+            ctx.print(x, y + index as i32, line);
         });
 
-        ctx.print(x, y, "no objects to");
-        ctx.print(x, y + 1, "interact here")
+        if let Some(container) = container {
+            let name = names.get(container).unwrap().name.to_lowercase();
+            let line = y + nearby.len() as i32 + 1;
+            option("f", format!("open {}", name).as_ref()).print(ctx, x, line);
+        }
     }
 }
 
@@ -101,16 +135,63 @@ fn show_options(ctx: &mut BTerm, x: i32, y: i32) {
             option("i", "interact"),
             option("e", "backpack"),
             option("c", "craft"),
+            option("m", "harvest"),
+            option("s", "sow"),
+            option("p", "power"),
+            option("a", "bottlenecks"),
+            option("t", "research"),
+            option("v", "statistics"),
+            option("r", "planner"),
+            option("w", "blueprints"),
+            option("z", "stockpiles"),
             option("o", "options"),
         ],
     };
 
-    (0..menu.options.len()).into_iter().for_each(|i| {
+    (0..menu.options.len()).for_each(|i| {
         let option = menu.options.get(i).expect("out of bounds").clone();
         option.print(ctx, x, y + (i as i32 * 2) + 1);
     });
 }
 
+/// Lists the goals that are not reached yet, with how far along each one is.
+fn show_objectives(world: &World, ctx: &mut BTerm, x: i32, y: i32) {
+    let milestones = world.fetch::<Milestones>();
+
+    ctx.print(x, y, "objectives");
+
+    let open: Vec<_> = GOALS
+        .iter()
+        .filter(|goal| !milestones.is_completed(goal.name))
+        .collect();
+    if open.is_empty() {
+        ctx.print_color(x, y + 2, RGB::named(GREEN), RGB::named(BLACK), "all done");
+        return;
+    }
+
+    open.iter().take(6).enumerate().for_each(|(index, goal)| {
+        let line = y + 2 + index as i32 * 2;
+        let unit = match goal.condition {
+            Condition::ProductionRate { .. } => " /min",
+            _ => "",
+        };
+
+        ctx.print(x, line, goal.name);
+        ctx.print_color(
+            x + 1,
+            line + 1,
+            RGB::named(GREY),
+            RGB::named(BLACK),
+            format!(
+                "{}/{}{}",
+                milestones.progress_of(goal.name),
+                goal.condition.target(),
+                unit
+            ),
+        );
+    });
+}
+
 fn option(key: &str, name: &str) -> MenuOption {
     MenuOption {
         key: key.to_string(),
@@ -122,9 +203,33 @@ pub fn show_inventory(state: &mut State, ctx: &mut BTerm) {
     let player = state.world.fetch::<Entity>();
     let names = state.world.read_storage::<Name>();
     let backpack = state.world.read_storage::<InBackpack>();
+    let weights = state.world.read_storage::<Weight>();
+    let capacities = state.world.read_storage::<Capacity>();
+    let encumbered = state.world.read_storage::<Encumbered>();
 
     ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
-    ctx.print_centered_at(17, 2, "backpack");
+
+    match capacities.get(*player) {
+        None => ctx.print_centered_at(17, 2, "backpack"),
+        Some(capacity) => {
+            let load = backpack_load(*player, &backpack, &weights);
+            let color = match encumbered.contains(*player) {
+                true => RGB::named(RED),
+                false => RGB::named(WHITE),
+            };
+
+            ctx.print_color_centered_at(
+                17,
+                2,
+                color,
+                RGB::named(BLACK),
+                format!(
+                    "backpack {:.1}/{:.0}kg {:.0}/{:.0}L",
+                    load.weight, capacity.max_weight, load.volume, capacity.max_volume
+                ),
+            );
+        }
+    }
 
     let mut inventory: BTreeMap<&String, i32> = BTreeMap::new();
 
@@ -152,19 +257,23 @@ fn item_owner_is_player(item: &(&InBackpack, &Name), player: &Fetch<Entity>) ->
     item.0.owner == **player
 }
 
+/// Rows of recipes the craft window shows at once.
+const VISIBLE_RECIPES: usize = 27;
+
 pub fn show_craft(state: &mut State, ctx: &mut BTerm) {
     let ui = state.world.fetch::<UserInterfaceState>();
     let player = state.world.fetch::<Entity>();
     let backpack = state.world.read_storage::<InBackpack>();
     let names = state.world.read_storage::<Name>();
+    let research = state.world.fetch::<Research>();
+    let offset = ui.selected_option.saturating_sub(VISIBLE_RECIPES - 1);
 
     ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
     ctx.print_centered_at(17, 2, "craft");
 
-    let selected_y = 4 + ui.selected_option;
     ctx.set(
         4,
-        selected_y,
+        4 + (ui.selected_option - offset) as i32,
         RGB::named(WHITE),
         RGB::named(BLACK),
         to_cp437('→'),
@@ -189,35 +298,71 @@ pub fn show_craft(state: &mut State, ctx: &mut BTerm) {
     };
 
     let mut y = 4;
-    RECIPES.iter().for_each(|recipe| {
-        let can_craft_item = {
-            let mut requirements: HashMap<&str, i32> = HashMap::new();
-
-            recipe.requirements.iter().for_each(|requirement| {
-                if requirements.contains_key(requirement.item_name) {
-                    let old_amount = requirements.get(requirement.item_name).unwrap();
-                    requirements.insert(requirement.item_name, old_amount + requirement.amount);
-                } else {
-                    requirements.insert(requirement.item_name, requirement.amount);
-                }
-            });
+    RECIPES
+        .iter()
+        .skip(offset)
+        .take(VISIBLE_RECIPES)
+        .for_each(|recipe| {
+            let can_craft_item = {
+                let mut requirements: HashMap<&str, i32> = HashMap::new();
+
+                recipe.requirements.iter().for_each(|requirement| {
+                    if requirements.contains_key(requirement.item_name) {
+                        let old_amount = requirements.get(requirement.item_name).unwrap();
+                        requirements.insert(requirement.item_name, old_amount + requirement.amount);
+                    } else {
+                        requirements.insert(requirement.item_name, requirement.amount);
+                    }
+                });
+
+                requirements.iter().fold(true, |can_craft, requirement| {
+                    let inventory_amount = inventory.get(&requirement.0.to_string()).unwrap_or(&0);
+                    can_craft && requirement.1 <= inventory_amount
+                })
+            };
+
+            let color = match is_unlocked(recipe.result_item_name, &research) {
+                true => can_craft(can_craft_item),
+                false => RGB::named(DARKGRAY),
+            };
+            ctx.print_color(6, y, color, RGB::named(BLACK), recipe.result_item_name);
+
+            y += 1;
+        });
 
-            requirements.iter().fold(true, |can_craft, requirement| {
-                let inventory_amount = inventory.get(&requirement.0.to_string()).unwrap_or(&0);
-                can_craft && requirement.1 <= inventory_amount
-            })
-        };
+    if let Some(recipe) = RECIPES.get(ui.selected_option) {
+        show_outputs(ctx, recipe);
+    }
+}
 
-        ctx.print_color(
-            6,
-            y,
-            can_craft(can_craft_item),
-            RGB::named(BLACK),
-            &recipe.result_item_name,
-        );
+/// Lists everything a recipe can make, with the chance of each byproduct.
+fn show_outputs(ctx: &mut BTerm, recipe: &Recipe) {
+    ctx.draw_box(33, 2, 26, 12, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, "makes");
+
+    let mut lines = vec![format!(
+        "{} {}",
+        recipe.result_amount, recipe.result_item_name
+    )];
+    recipe.byproducts.iter().for_each(|byproduct| {
+        lines.push(match byproduct.chance {
+            None => format!("{} {}", byproduct.amount, byproduct.item_name),
+            Some(chance) => format!(
+                "{} {} ({:.0}%)",
+                byproduct.amount,
+                byproduct.item_name,
+                chance * 100.
+            ),
+        })
+    });
+    recipe
+        .fluid_results
+        .iter()
+        .for_each(|result| lines.push(format!("{:.0} {}", result.amount, result.fluid.name())));
 
-        y += 1;
-    })
+    lines.iter().enumerate().for_each(|(index, line)| {
+        ctx.print(35, 4 + index as i32, line);
+    });
 }
 
 fn can_craft(check: bool) -> RGB {
@@ -228,26 +373,23 @@ fn can_craft(check: bool) -> RGB {
     }
 }
 
-pub fn craft(state: &mut State, ctx: &mut BTerm) {
+pub fn craft(state: &mut State) {
     let mut wants_to_craft = state.world.write_storage::<CraftQueue>();
-    let entities = state.world.entities();
-    let items = state.world.read_storage::<Item>();
+    let player = state.world.fetch::<Entity>();
 
     let selected_option = {
-        let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+        let ui = state.world.fetch::<UserInterfaceState>();
         ui.selected_option
     };
 
     let to_craft = RECIPES.get(selected_option).unwrap();
 
-    for (entity, _item) in (&entities, &items).join() {
-        wants_to_craft
-            .insert(
-                entity,
-                CraftQueue {
-                    item_name: to_craft.result_item_name.to_string(),
-                },
-            )
-            .expect("can't craft item");
-    }
+    wants_to_craft
+        .insert(
+            *player,
+            CraftQueue {
+                item_name: to_craft.result_item_name.to_string(),
+            },
+        )
+        .expect("can't craft item");
 }
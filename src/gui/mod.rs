@@ -1,10 +1,25 @@
-use bracket_lib::color::{GREEN, RGB, WHITE};
+use bracket_lib::color::{RGB, WHITE};
+use specs::Entity;
 
+use crate::components::structures::Direction;
+use crate::gui::container::Pane;
 use crate::map::WIDTH;
 use crate::player::ControlMode;
 use crate::{BTerm, Log, World, BLACK};
 
+pub mod blueprints;
+pub mod bottlenecks;
+pub mod build;
+pub mod container;
 pub mod menu;
+pub mod planner;
+pub mod power;
+pub mod rails;
+pub mod research;
+pub mod signals;
+pub mod statistics;
+pub mod stockpiles;
+pub mod workers;
 
 #[derive(PartialEq, Copy, Clone, Default)]
 pub enum MenuMode {
@@ -13,6 +28,18 @@ pub enum MenuMode {
     Interact,
     Inventory,
     Craft,
+    Build,
+    Container,
+    Power,
+    Research,
+    Statistics,
+    Bottlenecks,
+    Planner,
+    Blueprints,
+    Signals,
+    Schedule,
+    Worker,
+    Stockpiles,
 }
 
 pub struct UserInterfaceState {
@@ -22,6 +49,30 @@ pub struct UserInterfaceState {
     pub control_mode: ControlMode,
     pub selected_option: usize,
     pub show_performance_info: bool,
+    pub cursor: (i32, i32),
+    pub facing: Direction,
+    pub container: Option<Entity>,
+    pub pane: Pane,
+    /// Index into `WINDOWS` of the window the statistics screen shows.
+    pub statistics_window: usize,
+    /// Items a minute the planner plans for, and which recipe makes its target.
+    pub planner_rate: u32,
+    pub planner_recipe: usize,
+    /// Where the area being copied into a blueprint started, while one is being selected.
+    pub selection: Option<(i32, i32)>,
+    /// Index into the blueprint library of the blueprint build mode pastes.
+    pub blueprint: Option<usize>,
+    /// The structure the signals screen edits, and which of its settings is selected.
+    pub signal_target: Option<Entity>,
+    pub signal_field: usize,
+    /// The cart whose schedule is being edited.
+    pub cart: Option<Entity>,
+    /// The worker whose jobs are being edited.
+    pub worker: Option<Entity>,
+    /// Whether the area being selected on the stockpiles screen is erased rather than painted.
+    pub is_erasing: bool,
+    /// Index into the item kinds of the kind the stockpiles screen adds to or takes from a filter.
+    pub stockpile_kind: usize,
 }
 
 impl UserInterfaceState {
@@ -42,6 +93,21 @@ impl Default for UserInterfaceState {
             control_mode: ControlMode::default(),
             selected_option: 0,
             show_performance_info: true,
+            cursor: (0, 0),
+            facing: Direction::default(),
+            container: None,
+            pane: Pane::default(),
+            statistics_window: 0,
+            planner_rate: 10,
+            planner_recipe: 0,
+            selection: None,
+            blueprint: None,
+            signal_target: None,
+            signal_field: 0,
+            cart: None,
+            worker: None,
+            is_erasing: false,
+            stockpile_kind: 0,
         }
     }
 }
@@ -53,7 +119,11 @@ pub fn draw_log(world: &World, ctx: &mut BTerm) {
         return;
     }
 
-    let width = WIDTH - 1;
+    // The menu runs the full height of the screen, so the log stops at its edge.
+    let width = match ui.menu {
+        true => 59,
+        false => WIDTH - 1,
+    };
     ctx.draw_box(0, 43, width, 6, RGB::named(WHITE), RGB::named(BLACK));
 
     let log = world.fetch::<Log>();
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use bracket_lib::color::{GREY, WHITE, YELLOW};
+
+use crate::planner::{craftable_items, plan, recipes_for, Plan};
+use crate::{to_cp437, BTerm, State, UserInterfaceState, BLACK, RGB};
+
+/// Rows of items the list shows at once.
+const VISIBLE: usize = 25;
+
+pub fn show_planner(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let items = craftable_items();
+    let offset = ui.selected_option.saturating_sub(VISIBLE - 1);
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, format!("planner, {}/min", ui.planner_rate));
+
+    ctx.set(
+        4,
+        4 + (ui.selected_option - offset) as i32,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+        to_cp437('→'),
+    );
+
+    items
+        .iter()
+        .skip(offset)
+        .take(VISIBLE)
+        .enumerate()
+        .for_each(|(index, item)| {
+            ctx.print(6, 4 + index as i32, item);
+        });
+
+    ctx.print_color(
+        4,
+        30,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "+/-: rate  tab: recipe",
+    );
+
+    let target = match items.get(ui.selected_option) {
+        None => return,
+        Some(target) => *target,
+    };
+    let choices = HashMap::from([(target, ui.planner_recipe)]);
+
+    if let Some(plan) = plan(target, ui.planner_rate as f32, &choices) {
+        show_plan(ctx, &plan, ui.planner_recipe);
+    }
+}
+
+/// Lists the machines each step needs, the raw input and what is left over.
+fn show_plan(ctx: &mut BTerm, plan: &Plan, recipe: usize) {
+    ctx.draw_box(31, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, plan.target);
+
+    let recipes = recipes_for(plan.target).len();
+    ctx.print_color(
+        33,
+        4,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        format!("recipe {}/{}", recipe.min(recipes - 1) + 1, recipes),
+    );
+
+    let mut y = 6;
+    ctx.print(33, y, "machines");
+    y += 1;
+    plan.steps.iter().for_each(|step| {
+        let color = match step.alternatives > 1 {
+            true => RGB::named(YELLOW),
+            false => RGB::named(WHITE),
+        };
+        ctx.print_color(
+            35,
+            y,
+            color,
+            RGB::named(BLACK),
+            format!("{:<14.14}", step.item),
+        );
+        ctx.print(
+            50,
+            y,
+            format!("{:>4.1} {:.3}", step.machines(), step.machine_name()),
+        );
+        y += 1;
+    });
+
+    let sections = [
+        ("raw input/min", &plan.raw),
+        ("leftovers/min", &plan.leftovers),
+    ];
+    sections.iter().for_each(|(title, rates)| {
+        y += 1;
+        ctx.print(33, y, title);
+        y += 1;
+
+        if rates.is_empty() {
+            ctx.print_color(35, y, RGB::named(GREY), RGB::named(BLACK), "none");
+            y += 1;
+        }
+        rates.iter().for_each(|(name, rate)| {
+            ctx.print(35, y, format!("{:<14.14}{:>7.1}", name, rate));
+            y += 1;
+        });
+    });
+}
@@ -0,0 +1,77 @@
+use bracket_lib::color::{GREEN, RED, YELLOW};
+use specs::{Join, WorldExt};
+
+use crate::components::power::{Generator, PowerConsumer, PowerGrids, PowerPole};
+use crate::map::{HEIGHT, WIDTH};
+use crate::{BTerm, Position, World, RGB};
+
+fn satisfaction_color(satisfaction: f32) -> RGB {
+    match satisfaction {
+        s if s >= 1. => RGB::named(GREEN),
+        s if s > 0. => RGB::named(YELLOW),
+        _ => RGB::named(RED),
+    }
+}
+
+/// Tints the area covered by each pole by how well its grid is supplied, and marks consumers and
+/// generators that are not connected to any grid.
+pub fn draw_power_overlay(world: &World, ctx: &mut BTerm) {
+    let grids = world.fetch::<PowerGrids>();
+    let positions = world.read_storage::<Position>();
+    let poles = world.read_storage::<PowerPole>();
+    let consumers = world.read_storage::<PowerConsumer>();
+    let generators = world.read_storage::<Generator>();
+    let entities = world.entities();
+
+    let mut covered = vec![];
+    for (entity, pole, position) in (&entities, &poles, &positions).join() {
+        let satisfaction = grids
+            .grids
+            .iter()
+            .find(|grid| grid.poles.contains(&entity))
+            .map_or(0., |grid| grid.satisfaction());
+        let tint = satisfaction_color(satisfaction) * 0.3;
+
+        for x in (position.x - pole.radius)..=(position.x + pole.radius) {
+            for y in (position.y - pole.radius)..=(position.y + pole.radius) {
+                let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+                if is_on_map {
+                    ctx.set_bg(x, y, tint);
+                    covered.push((x, y));
+                }
+            }
+        }
+    }
+
+    for (entity, position) in (&entities, &positions).join() {
+        let is_powered = consumers.contains(entity) || generators.contains(entity);
+        if is_powered && !covered.contains(&(position.x, position.y)) {
+            ctx.set_bg(position.x, position.y, RGB::named(RED));
+        }
+    }
+}
+
+/// Lists every grid with its production and consumption, for the right side menu.
+pub fn show_power(world: &World, ctx: &mut BTerm, x: i32, y: i32) {
+    let grids = world.fetch::<PowerGrids>();
+
+    ctx.print(x, y, "power grids");
+    if grids.grids.is_empty() {
+        ctx.print(x, y + 2, "no power poles");
+        return;
+    }
+
+    let mut line = y + 2;
+    for (index, grid) in grids.grids.iter().enumerate() {
+        ctx.print(x, line, format!("grid {}", index + 1));
+        ctx.print_color(
+            x,
+            line + 1,
+            satisfaction_color(grid.satisfaction()),
+            RGB::from_f32(0., 0., 0.),
+            format!("{:.0}/{:.0} kW", grid.production, grid.consumption),
+        );
+
+        line += 3;
+    }
+}
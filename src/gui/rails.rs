@@ -0,0 +1,131 @@
+use bracket_lib::color::{CYAN, GREY, WHITE};
+use specs::{Join, WorldExt};
+
+use crate::components::items::{backpack_load, stacks_of, Capacity, Weight};
+use crate::components::rails::{Cart, Station};
+use crate::{
+    to_cp437, BTerm, InBackpack, Name, Position, State, UserInterfaceState, World, BLACK, RGB,
+};
+
+/// The names of every placed station, sorted.
+pub fn station_names(world: &World) -> Vec<String> {
+    let positions = world.read_storage::<Position>();
+    let stations = world.read_storage::<Station>();
+
+    let mut names: Vec<String> = (&stations, &positions)
+        .join()
+        .filter_map(|(station, _)| station.name.clone())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    names
+}
+
+pub fn show_schedule(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let cart = match ui.cart {
+        None => return,
+        Some(cart) => cart,
+    };
+    let carts = state.world.read_storage::<Cart>();
+    let entities = state.world.entities();
+    let backpack = state.world.read_storage::<InBackpack>();
+    let names = state.world.read_storage::<Name>();
+    let weights = state.world.read_storage::<Weight>();
+    let capacities = state.world.read_storage::<Capacity>();
+    let positions = state.world.read_storage::<Position>();
+    let stations = state.world.read_storage::<Station>();
+
+    let data = match carts.get(cart) {
+        None => return,
+        Some(data) => data,
+    };
+
+    // Marks the stations on the schedule on the map.
+    (&positions, &stations)
+        .join()
+        .filter(|(_, station)| {
+            data.schedule
+                .iter()
+                .any(|stop| station.name.as_ref() == Some(&stop.station))
+        })
+        .for_each(|(position, _)| ctx.set_bg(position.x, position.y, RGB::named(CYAN) * 0.5));
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, "schedule");
+    ctx.print(4, 4, data.status());
+
+    if data.schedule.is_empty() {
+        ctx.print(4, 6, "add a stop to get going");
+    } else {
+        ctx.set(
+            4,
+            6 + ui.selected_option as i32,
+            RGB::named(WHITE),
+            RGB::named(BLACK),
+            to_cp437('→'),
+        );
+    }
+    data.schedule.iter().enumerate().for_each(|(index, stop)| {
+        let fg = match index == data.stop {
+            true => RGB::named(CYAN),
+            false => RGB::named(WHITE),
+        };
+        ctx.print_color(
+            6,
+            6 + index as i32,
+            fg,
+            RGB::named(BLACK),
+            format!(
+                "{}. {} until {}",
+                index + 1,
+                stop.station,
+                stop.until.name()
+            ),
+        );
+    });
+
+    ctx.print_color(
+        4,
+        28,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "a: add stop  d: delete",
+    );
+    ctx.print_color(
+        4,
+        29,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "h/l: station  f: full/empty",
+    );
+    ctx.print_color(4, 30, RGB::named(GREY), RGB::named(BLACK), "esc: back");
+
+    ctx.draw_box(31, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, "cargo");
+
+    if let Some(capacity) = capacities.get(cart) {
+        let load = backpack_load(cart, &backpack, &weights);
+        ctx.print(
+            33,
+            4,
+            format!(
+                "{:.0}/{:.0} kg  {:.0}/{:.0} l",
+                load.weight, capacity.max_weight, load.volume, capacity.max_volume
+            ),
+        );
+    }
+
+    let stacks = stacks_of(cart, &entities, &backpack, &names);
+    if stacks.is_empty() {
+        ctx.print(33, 6, "empty");
+    }
+    stacks
+        .iter()
+        .take(24)
+        .enumerate()
+        .for_each(|(index, (name, stack))| {
+            ctx.print(33, 6 + index as i32, format!("{} x{}", name, stack.len()));
+        });
+}
@@ -0,0 +1,108 @@
+use bracket_lib::color::{DARKGRAY, GREEN, GREY, WHITE, YELLOW};
+
+use crate::components::research::Research;
+use crate::systems::research::{Technology, TECHNOLOGIES};
+use crate::{to_cp437, BTerm, State, UserInterfaceState, BLACK, RGB};
+
+/// Completed technologies are green, the one being researched yellow, ones whose prerequisites
+/// are done white and the rest gray.
+fn technology_color(technology: &Technology, research: &Research) -> RGB {
+    match technology.name {
+        name if research.is_completed(name) => RGB::named(GREEN),
+        name if research.current == Some(name) => RGB::named(YELLOW),
+        _ if technology.is_available(research) => RGB::named(WHITE),
+        _ => RGB::named(DARKGRAY),
+    }
+}
+
+pub fn show_research(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let research = state.world.fetch::<Research>();
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, "research");
+
+    ctx.set(
+        4,
+        4 + ui.selected_option as i32,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+        to_cp437('→'),
+    );
+
+    TECHNOLOGIES
+        .iter()
+        .enumerate()
+        .for_each(|(index, technology)| {
+            let label = match research.current {
+                Some(current) if current == technology.name => format!(
+                    "{} {}%",
+                    technology.name,
+                    (research.progress * 100. / technology.research_ticks.max(1) as f32) as u32
+                ),
+                _ => technology.name.to_string(),
+            };
+
+            ctx.print_color(
+                6,
+                4 + index as i32,
+                technology_color(technology, &research),
+                RGB::named(BLACK),
+                label,
+            );
+        });
+
+    ctx.print_color(
+        4,
+        30,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "enter: research",
+    );
+
+    if let Some(technology) = TECHNOLOGIES.get(ui.selected_option) {
+        show_technology(ctx, technology, &research);
+    }
+}
+
+/// Lists the prerequisites, cost and unlocks of a technology.
+fn show_technology(ctx: &mut BTerm, technology: &Technology, research: &Research) {
+    ctx.draw_box(31, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, technology.name);
+
+    let mut y = 4;
+    ctx.print(33, y, "needs");
+    y += 1;
+    if technology.prerequisites.is_empty() {
+        ctx.print_color(35, y, RGB::named(GREY), RGB::named(BLACK), "nothing");
+        y += 1;
+    }
+    technology.prerequisites.iter().for_each(|prerequisite| {
+        let color = match research.is_completed(prerequisite) {
+            true => RGB::named(GREEN),
+            false => RGB::named(DARKGRAY),
+        };
+        ctx.print_color(35, y, color, RGB::named(BLACK), prerequisite);
+        y += 1;
+    });
+
+    y += 1;
+    ctx.print(33, y, format!("costs, {} ticks", technology.research_ticks));
+    y += 1;
+    technology.cost.iter().for_each(|requirement| {
+        ctx.print(
+            35,
+            y,
+            format!("{} {}", requirement.amount, requirement.item_name),
+        );
+        y += 1;
+    });
+
+    y += 1;
+    ctx.print(33, y, "unlocks");
+    y += 1;
+    technology.unlocks.iter().for_each(|unlock| {
+        ctx.print(35, y, unlock);
+        y += 1;
+    });
+}
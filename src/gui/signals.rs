@@ -0,0 +1,209 @@
+use bracket_lib::color::{GREEN, GREY, RED, WHITE};
+use specs::{Entity, Join, WorldExt};
+
+use crate::components::fluids::Fluid;
+use crate::components::signals::{
+    ArithmeticCombinator, Controlled, DeciderCombinator, Disabled, SignalNetworks, Signals, Wire,
+};
+use crate::components::structures::{Belt, Inserter, Machine, Structure};
+use crate::{to_cp437, BTerm, Item, Name, Position, State, UserInterfaceState, World, BLACK, RGB};
+
+/// Signals that stand for no item or fluid, for combinators to talk to each other.
+pub const VIRTUAL_SIGNALS: [&str; 3] = ["A", "B", "C"];
+
+/// Every signal a condition or combinator can use: the items that can be picked up, the fluids
+/// and the virtual signals.
+pub fn signal_names(world: &World) -> Vec<String> {
+    let names = world.read_storage::<Name>();
+    let items = world.read_storage::<Item>();
+
+    let mut known: Vec<String> = (&items, &names)
+        .join()
+        .filter(|(item, _)| item.can_be_picked)
+        .map(|(_, name)| name.name.clone())
+        .collect();
+    known.sort();
+    known.dedup();
+
+    known
+        .into_iter()
+        .chain([Fluid::Water, Fluid::Sap].map(|fluid| fluid.name().to_string()))
+        .chain(VIRTUAL_SIGNALS.map(str::to_string))
+        .collect()
+}
+
+/// Machines, inserters and belts can be switched by a condition.
+pub fn is_controllable(world: &World, entity: Entity) -> bool {
+    world.read_storage::<Machine>().contains(entity)
+        || world.read_storage::<Inserter>().contains(entity)
+        || world.read_storage::<Belt>().contains(entity)
+}
+
+/// The label and value of every setting the signals screen edits for `entity`.
+pub fn signal_fields(world: &World, entity: Entity) -> Vec<(&'static str, String)> {
+    if let Some(combinator) = world.read_storage::<ArithmeticCombinator>().get(entity) {
+        return vec![
+            ("signal", combinator.signal.clone()),
+            ("operation", combinator.operation.symbol().to_string()),
+            ("value", combinator.value.to_string()),
+            ("output", combinator.output.clone()),
+        ];
+    }
+    if let Some(combinator) = world.read_storage::<DeciderCombinator>().get(entity) {
+        let condition = &combinator.condition;
+        return vec![
+            ("signal", condition.signal.clone()),
+            ("compare", condition.comparison.symbol().to_string()),
+            ("value", condition.value.to_string()),
+            ("output", combinator.output.clone()),
+        ];
+    }
+
+    match world.read_storage::<Controlled>().get(entity) {
+        None => vec![("run when", "always".to_string())],
+        Some(controlled) => {
+            let condition = &controlled.condition;
+            vec![
+                ("run when", condition.signal.clone()),
+                ("compare", condition.comparison.symbol().to_string()),
+                ("value", condition.value.to_string()),
+            ]
+        }
+    }
+}
+
+pub fn show_signals(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let entity = match ui.signal_target {
+        None => return,
+        Some(entity) => entity,
+    };
+    let names = state.world.read_storage::<Name>();
+    let disabled = state.world.read_storage::<Disabled>();
+    let controlled = state.world.read_storage::<Controlled>();
+    let networks = state.world.fetch::<SignalNetworks>();
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, "signals");
+    if let Some(name) = names.get(entity) {
+        ctx.print(4, 4, name);
+    }
+
+    let fields = signal_fields(&state.world, entity);
+    ctx.set(
+        4,
+        6 + ui.signal_field as i32,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+        to_cp437('→'),
+    );
+    fields
+        .iter()
+        .enumerate()
+        .for_each(|(index, (label, value))| {
+            ctx.print(6, 6 + index as i32, format!("{:<10}{}", label, value));
+        });
+
+    if controlled.contains(entity) {
+        let (color, status) = match disabled.contains(entity) {
+            true => (RED, "stopped"),
+            false => (GREEN, "running"),
+        };
+        ctx.print_color(6, 11, RGB::named(color), RGB::named(BLACK), status);
+    }
+
+    ctx.print_color(
+        4,
+        29,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "j/k: setting  h/l: change",
+    );
+    ctx.print_color(
+        4,
+        30,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "shift: by 10  esc: back",
+    );
+
+    ctx.draw_box(31, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, "network");
+    match networks.of(entity).is_empty() {
+        true => ctx.print(33, 4, "not connected to a wire"),
+        false => show_signal_values(ctx, 33, 4, &networks.signals_of(entity), 26),
+    }
+}
+
+fn show_signal_values(ctx: &mut BTerm, x: i32, y: i32, signals: &Signals, rows: usize) {
+    if signals.is_empty() {
+        ctx.print_color(x, y, RGB::named(GREY), RGB::named(BLACK), "no signals");
+    }
+
+    signals
+        .iter()
+        .take(rows)
+        .enumerate()
+        .for_each(|(index, (name, value))| {
+            ctx.print(x, y + index as i32, format!("{:<16.16}{:>7}", name, value));
+        });
+}
+
+/// Lists the signals on the networks of the wire or connected structure under the cursor in a box
+/// next to it, with the condition a controlled structure runs on, and marks what else the network
+/// reaches.
+pub fn draw_signal_overlay(world: &World, ctx: &mut BTerm, (x, y): (i32, i32)) {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let structures = world.read_storage::<Structure>();
+    let wires = world.read_storage::<Wire>();
+    let controlled = world.read_storage::<Controlled>();
+    let networks = world.fetch::<SignalNetworks>();
+
+    let hovered = (&entities, &positions, &structures)
+        .join()
+        .find(|(_, position, _)| position.x == x && position.y == y)
+        .map(|(entity, _, _)| entity);
+    let condition = hovered
+        .and_then(|entity| controlled.get(entity))
+        .map(|control| format!("runs when {}", control.condition));
+    let connected = hovered.map_or(vec![], |entity| networks.of(entity));
+    if connected.is_empty() && condition.is_none() {
+        return;
+    }
+
+    connected
+        .iter()
+        .flat_map(|network| network.members.iter())
+        .filter(|member| !wires.contains(**member) && Some(**member) != hovered)
+        .filter_map(|member| positions.get(*member))
+        .for_each(|position| ctx.set_bg(position.x, position.y, RGB::named(RED) * 0.5));
+
+    let signals = hovered.map_or(Signals::new(), |entity| networks.signals_of(entity));
+    let rows = match connected.is_empty() {
+        true => 0,
+        false => signals.len().clamp(1, 10),
+    };
+    let heading = condition.is_some() as i32;
+    let (width, height) = (25, rows as i32 + heading + 1);
+    let left = match x + 2 + width < 80 {
+        true => x + 2,
+        false => x - 2 - width,
+    };
+    let top = y.min(49 - height);
+
+    ctx.draw_box(
+        left,
+        top,
+        width,
+        height,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+    );
+    if let Some(condition) = condition {
+        ctx.print(left + 1, top + 1, format!("{:.23}", condition));
+    }
+    if !connected.is_empty() {
+        show_signal_values(ctx, left + 1, top + 1 + heading, &signals, rows);
+    }
+}
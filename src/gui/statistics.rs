@@ -0,0 +1,157 @@
+use bracket_lib::color::{DARKGRAY, GREEN, GREY, ORANGE, WHITE};
+
+use crate::clock::Clock;
+use crate::components::statistics::{Flow, ItemTotals, Statistics, Window, WINDOWS};
+use crate::{to_cp437, BTerm, State, UserInterfaceState, BLACK, RGB};
+
+const CHART_WIDTH: usize = 26;
+const CHART_HEIGHT: i32 = 4;
+const RANKED: usize = 5;
+
+pub fn show_statistics(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let statistics = state.world.fetch::<Statistics>();
+    let now = state.world.fetch::<Clock>().tick;
+    let window = &WINDOWS[ui.statistics_window];
+    let items = statistics.items(now, window);
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, format!("statistics, {}", window.name));
+
+    if items.is_empty() {
+        ctx.print_color(
+            6,
+            4,
+            RGB::named(GREY),
+            RGB::named(BLACK),
+            "nothing made yet",
+        );
+    } else {
+        ctx.set(
+            4,
+            4 + ui.selected_option as i32,
+            RGB::named(WHITE),
+            RGB::named(BLACK),
+            to_cp437('→'),
+        );
+    }
+
+    items
+        .iter()
+        .take(25)
+        .enumerate()
+        .for_each(|(index, totals)| {
+            ctx.print(6, 4 + index as i32, format!("{:<12.12}", totals.item));
+            ctx.print_color(
+                19,
+                4 + index as i32,
+                RGB::named(GREEN),
+                RGB::named(BLACK),
+                format!("+{}", totals.produced),
+            );
+            ctx.print_color(
+                25,
+                4 + index as i32,
+                RGB::named(ORANGE),
+                RGB::named(BLACK),
+                format!("-{}", totals.consumed),
+            );
+        });
+
+    ctx.print_color(
+        4,
+        30,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "tab: window  w: export",
+    );
+
+    if let Some(totals) = items.get(ui.selected_option) {
+        show_item(ctx, &statistics, now, window, totals);
+    }
+}
+
+/// Charts how much of an item was made and used up over the window, and who did most of it.
+fn show_item(
+    ctx: &mut BTerm,
+    statistics: &Statistics,
+    now: u64,
+    window: &Window,
+    totals: &ItemTotals,
+) {
+    ctx.draw_box(31, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, &totals.item);
+
+    let flows = [
+        (
+            Flow::Produced,
+            "produced",
+            totals.produced,
+            RGB::named(GREEN),
+        ),
+        (
+            Flow::Consumed,
+            "consumed",
+            totals.consumed,
+            RGB::named(ORANGE),
+        ),
+    ];
+
+    let mut y = 4;
+    flows.iter().for_each(|(flow, label, total, color)| {
+        ctx.print(33, y, format!("{}, {}", label, total));
+        let history = statistics.history(now, window, &totals.item, *flow, CHART_WIDTH);
+        draw_chart(ctx, 33, y + 1, &history, *color);
+        y += CHART_HEIGHT + 2;
+    });
+
+    flows.iter().for_each(|(flow, label, _, color)| {
+        ctx.print(33, y, format!("top {}", label));
+        y += 1;
+
+        let sources = statistics.sources(now, window, &totals.item, *flow);
+        if sources.is_empty() {
+            ctx.print_color(35, y, RGB::named(GREY), RGB::named(BLACK), "nobody");
+            y += 1;
+        }
+
+        let most = sources.first().map_or(1, |(_, amount)| *amount).max(1);
+        sources.iter().take(RANKED).for_each(|(source, amount)| {
+            let bar = (*amount * 6).div_ceil(most) as usize;
+            ctx.print(35, y, format!("{:<13.13}", source));
+            ctx.print_color(49, y, *color, RGB::named(BLACK), "█".repeat(bar));
+            ctx.print(56, y, format!("{:>4}", amount));
+            y += 1;
+        });
+
+        y += 1;
+    });
+}
+
+/// Draws `history` as columns of full and half blocks, scaled so the tallest fills the chart.
+fn draw_chart(ctx: &mut BTerm, x: i32, y: i32, history: &[u32], color: RGB) {
+    let most = history.iter().copied().max().unwrap_or(0).max(1);
+    let halves = CHART_HEIGHT as u32 * 2;
+
+    history.iter().enumerate().for_each(|(column, amount)| {
+        let level = (amount * halves).div_ceil(most) as i32;
+
+        (0..CHART_HEIGHT).for_each(|row| {
+            let filled = level - row * 2;
+            let (glyph, fg) = match filled {
+                f if f >= 2 => ('█', color),
+                1 => ('▄', color),
+                _ if row == 0 => ('_', RGB::named(DARKGRAY)),
+                _ => (' ', color),
+            };
+
+            ctx.set(
+                x + column as i32,
+                y + CHART_HEIGHT - 1 - row,
+                fg,
+                RGB::named(BLACK),
+                to_cp437(glyph),
+            );
+        });
+    });
+}
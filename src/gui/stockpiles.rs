@@ -0,0 +1,141 @@
+use bracket_lib::color::{CYAN, DARKRED, GREY, WHITE};
+use specs::{Join, WorldExt};
+
+use crate::components::stockpiles::Stockpiles;
+use crate::gui::blueprints::draw_selection;
+use crate::{to_cp437, BTerm, Item, Name, State, UserInterfaceState, World, BLACK, RGB};
+
+/// The names of every item that can be picked up, sorted, for stockpile filters to pick from.
+pub fn item_kinds(world: &World) -> Vec<String> {
+    let names = world.read_storage::<Name>();
+    let items = world.read_storage::<Item>();
+
+    let mut kinds: Vec<String> = (&items, &names)
+        .join()
+        .filter(|(item, _)| item.can_be_picked)
+        .map(|(_, name)| name.name.clone())
+        .collect();
+    kinds.sort();
+    kinds.dedup();
+
+    kinds
+}
+
+pub fn show_stockpiles(state: &mut State, ctx: &mut BTerm) {
+    let kinds = item_kinds(&state.world);
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let stockpiles = state.world.fetch::<Stockpiles>();
+    let selected = stockpiles.zones.get(ui.selected_option);
+
+    // Paints every zone onto the map, the selected one brighter than the rest.
+    stockpiles
+        .zones
+        .iter()
+        .enumerate()
+        .for_each(|(index, zone)| {
+            let bg = match index == ui.selected_option {
+                true => RGB::named(CYAN) * 0.5,
+                false => RGB::named(GREY) * 0.4,
+            };
+            zone.tiles.iter().for_each(|(x, y)| ctx.set_bg(*x, *y, bg));
+        });
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, "stockpiles");
+
+    if stockpiles.zones.is_empty() {
+        ctx.print(4, 4, "add a stockpile to get going");
+    } else {
+        ctx.set(
+            4,
+            4 + ui.selected_option as i32,
+            RGB::named(WHITE),
+            RGB::named(BLACK),
+            to_cp437('→'),
+        );
+    }
+    stockpiles
+        .zones
+        .iter()
+        .take(8)
+        .enumerate()
+        .for_each(|(index, zone)| {
+            ctx.print(
+                6,
+                4 + index as i32,
+                format!("{} {} ({})", zone.priority, zone.name, zone.tiles.len()),
+            );
+        });
+
+    if let Some(zone) = selected {
+        match zone.filter.is_empty() {
+            true => ctx.print(4, 13, "takes everything"),
+            false => ctx.print(4, 13, "takes"),
+        }
+        zone.filter
+            .iter()
+            .take(5)
+            .enumerate()
+            .for_each(|(index, kind)| {
+                ctx.print(6, 14 + index as i32, format!("{:.24}", kind));
+            });
+        if zone.filter.len() > 5 {
+            ctx.print(6, 19, format!("and {} more", zone.filter.len() - 5));
+        }
+
+        if let Some(kind) = kinds.get(ui.stockpile_kind) {
+            let is_taken = zone.filter.contains(kind);
+            let mark = match is_taken {
+                true => '+',
+                false => '-',
+            };
+            ctx.print(4, 21, format!("kind {} {:.20}", mark, kind));
+        }
+    }
+
+    if let Some(zone) = stockpiles.zone_at(ui.cursor) {
+        ctx.print(4, 22, format!("here: {}", zone.name));
+    }
+
+    ctx.print_color(
+        4,
+        24,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "a: add  x: remove  tab: next",
+    );
+    let (paint, erase) = match (ui.selection, ui.is_erasing) {
+        (None, _) => ("s: paint area", "e: erase area"),
+        (Some(_), false) => ("s: paint selection", "e: erase area"),
+        (Some(_), true) => ("s: paint area", "e: erase selection"),
+    };
+    ctx.print_color(4, 25, RGB::named(GREY), RGB::named(BLACK), paint);
+    ctx.print_color(4, 26, RGB::named(GREY), RGB::named(BLACK), erase);
+    ctx.print_color(4, 27, RGB::named(GREY), RGB::named(BLACK), "-/+: priority");
+    ctx.print_color(
+        4,
+        28,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "f/F: kind  enter: take kind",
+    );
+    ctx.print_color(
+        4,
+        29,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "c: take everything",
+    );
+    ctx.print_color(4, 30, RGB::named(GREY), RGB::named(BLACK), "esc: back");
+
+    if let Some(start) = ui.selection {
+        draw_selection(ctx, start, ui.cursor);
+    }
+
+    let (x, y) = ui.cursor;
+    let bg = match ui.is_erasing && ui.selection.is_some() {
+        true => RGB::named(DARKRED),
+        false => RGB::named(CYAN),
+    };
+    ctx.set(x, y, RGB::named(BLACK), bg, to_cp437('X'));
+}
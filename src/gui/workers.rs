@@ -0,0 +1,248 @@
+use bracket_lib::color::{CYAN, GREY, RED, WHITE};
+use specs::{Entity, Join, WorldExt};
+
+use crate::components::items::{backpack_load, stacks_of, Capacity, ResourceNode, Weight};
+use crate::components::rails::Cart;
+use crate::components::research::Research;
+use crate::components::structures::{Container, Structure};
+use crate::components::workers::{Job, Worker};
+use crate::systems::craft::RECIPES;
+use crate::systems::research::is_unlocked;
+use crate::{
+    to_cp437, BTerm, InBackpack, Name, Position, State, UserInterfaceState, World, BLACK, RGB,
+};
+
+/// What the settings of a job can be set to.
+pub struct JobChoices {
+    /// What resource nodes yield, sorted.
+    pub items: Vec<String>,
+    /// Recipes that can be crafted by hand.
+    pub recipes: Vec<&'static str>,
+    /// Placed containers from top to bottom, after `None` for no container.
+    pub containers: Vec<Option<Entity>>,
+}
+
+pub fn job_choices(world: &World) -> JobChoices {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let nodes = world.read_storage::<ResourceNode>();
+    let containers = world.read_storage::<Container>();
+    let structures = world.read_storage::<Structure>();
+    let workers = world.read_storage::<Worker>();
+    let carts = world.read_storage::<Cart>();
+    let research = world.fetch::<Research>();
+
+    let mut items: Vec<String> = (&nodes, &positions)
+        .join()
+        .map(|(node, _)| node.yields.to_string())
+        .collect();
+    items.sort();
+    items.dedup();
+
+    let recipes = RECIPES
+        .iter()
+        .filter(|recipe| {
+            !recipe.smelting
+                && !recipe.needs_machine()
+                && is_unlocked(recipe.result_item_name, &research)
+        })
+        .map(|recipe| recipe.result_item_name)
+        .collect();
+
+    let mut placed: Vec<(Entity, &Position)> = (
+        &entities,
+        &containers,
+        &structures,
+        &positions,
+        !&workers,
+        !&carts,
+    )
+        .join()
+        .map(|(entity, _, _, position, _, _)| (entity, position))
+        .collect();
+    placed.sort_by_key(|(_, position)| (position.y, position.x));
+
+    JobChoices {
+        items,
+        recipes,
+        containers: [None]
+            .into_iter()
+            .chain(placed.into_iter().map(|(entity, _)| Some(entity)))
+            .collect(),
+    }
+}
+
+/// A container by name and where it stands, like `Chest 12,30`.
+pub fn describe_container(world: &World, container: Option<Entity>) -> String {
+    let names = world.read_storage::<Name>();
+    let positions = world.read_storage::<Position>();
+
+    match container.and_then(|container| names.get(container).zip(positions.get(container))) {
+        None => "none".to_string(),
+        Some((name, position)) => format!("{} {},{}", name, position.x, position.y),
+    }
+}
+
+/// The kind of job and what it is about, like `gather Wood`.
+fn describe_job(job: &Job) -> String {
+    match job {
+        Job::Gather { item, .. } => format!("gather {}", item),
+        Job::Haul { .. } => "haul".to_string(),
+        Job::Craft { recipe, .. } => format!("craft {}", recipe),
+    }
+}
+
+/// The two settings of a job by label and value, as `h/l` and `H/L` change them.
+fn job_settings(world: &World, job: &Job) -> [(&'static str, String); 2] {
+    match job {
+        Job::Gather { item, to } => [
+            ("item", item.clone()),
+            ("into", describe_container(world, *to)),
+        ],
+        Job::Haul { from, to } => [
+            ("from", describe_container(world, *from)),
+            ("to", describe_container(world, *to)),
+        ],
+        Job::Craft { recipe, at } => [
+            ("recipe", recipe.to_string()),
+            ("at", describe_container(world, *at)),
+        ],
+    }
+}
+
+pub fn show_worker(state: &mut State, ctx: &mut BTerm) {
+    let ui = state.world.fetch::<UserInterfaceState>();
+    let worker = match ui.worker {
+        None => return,
+        Some(worker) => worker,
+    };
+    let workers = state.world.read_storage::<Worker>();
+    let entities = state.world.entities();
+    let backpack = state.world.read_storage::<InBackpack>();
+    let names = state.world.read_storage::<Name>();
+    let weights = state.world.read_storage::<Weight>();
+    let capacities = state.world.read_storage::<Capacity>();
+    let positions = state.world.read_storage::<Position>();
+
+    let data = match workers.get(worker) {
+        None => return,
+        Some(data) => data,
+    };
+    let selected = data.jobs.get(ui.selected_option);
+
+    // Marks the containers the selected job uses on the map.
+    selected
+        .map_or(vec![], |assignment| assignment.job.containers())
+        .into_iter()
+        .flatten()
+        .filter_map(|container| positions.get(container))
+        .for_each(|position| ctx.set_bg(position.x, position.y, RGB::named(CYAN) * 0.5));
+
+    ctx.draw_box(2, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(17, 2, "worker");
+
+    let status_color = match data.is_stuck {
+        true => RGB::named(RED),
+        false => RGB::named(WHITE),
+    };
+    ctx.print_color(
+        4,
+        4,
+        status_color,
+        RGB::named(BLACK),
+        format!("{:.27}", data.status),
+    );
+
+    if data.jobs.is_empty() {
+        ctx.print(4, 6, "add a job to get going");
+    } else {
+        ctx.set(
+            4,
+            6 + ui.selected_option as i32,
+            RGB::named(WHITE),
+            RGB::named(BLACK),
+            to_cp437('→'),
+        );
+    }
+    data.jobs
+        .iter()
+        .take(12)
+        .enumerate()
+        .for_each(|(index, assignment)| {
+            ctx.print(
+                6,
+                6 + index as i32,
+                format!(
+                    "{} {:.22}",
+                    assignment.priority,
+                    describe_job(&assignment.job)
+                ),
+            );
+        });
+
+    if let Some(assignment) = selected {
+        job_settings(&state.world, &assignment.job)
+            .iter()
+            .enumerate()
+            .for_each(|(index, (label, value))| {
+                ctx.print(4, 19 + index as i32, format!("{:<7}{:.20}", label, value));
+            });
+    }
+
+    ctx.print_color(
+        4,
+        25,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "g/t/c: gather/haul/craft",
+    );
+    ctx.print_color(
+        4,
+        26,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "d: delete  -/+: priority",
+    );
+    ctx.print_color(
+        4,
+        27,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "h/l: change first setting",
+    );
+    ctx.print_color(
+        4,
+        28,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "H/L: change second setting",
+    );
+    ctx.print_color(4, 30, RGB::named(GREY), RGB::named(BLACK), "esc: back");
+
+    ctx.draw_box(31, 2, 30, 30, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_centered_at(46, 2, "backpack");
+
+    if let Some(capacity) = capacities.get(worker) {
+        let load = backpack_load(worker, &backpack, &weights);
+        ctx.print(
+            33,
+            4,
+            format!(
+                "{:.0}/{:.0} kg  {:.0}/{:.0} l",
+                load.weight, capacity.max_weight, load.volume, capacity.max_volume
+            ),
+        );
+    }
+
+    let stacks = stacks_of(worker, &entities, &backpack, &names);
+    if stacks.is_empty() {
+        ctx.print(33, 6, "empty");
+    }
+    stacks
+        .iter()
+        .take(24)
+        .enumerate()
+        .for_each(|(index, (name, stack))| {
+            ctx.print(33, 6 + index as i32, format!("{} x{}", name, stack.len()));
+        });
+}
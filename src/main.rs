@@ -1,37 +1,65 @@
 use std::env;
 use std::fmt::{Display, Formatter};
-use std::fs::{read_to_string, File};
-use std::process::exit;
 
 use bracket_lib::color::{BLACK, RGB, YELLOW};
 use bracket_lib::prelude::{
     main_loop, to_cp437, BError, BTerm, BTermBuilder, FontCharType, GameState, VirtualKeyCode,
 };
 use bracket_lib::random::RandomNumberGenerator;
-use knuffel::{Decode, Error};
+use specs::Component;
 use specs::DenseVecStorage;
-use specs::{Builder, World, WorldExt};
-use specs::{Component, Entity};
+use specs::{World, WorldExt};
 use specs_derive::Component;
 
+use crate::clock::Clock;
+use crate::components::blueprints::{
+    blueprint_directory, load_blueprints, BlueprintLibrary, Ghost,
+};
+use crate::components::bottlenecks::Bottlenecks;
+use crate::components::construction::ConstructionSite;
+use crate::components::farming::{Crop, PlantQueue};
+use crate::components::fluids::{FluidBox, FluidNetworks, Pipe, Pump, Tank};
+use crate::components::history::History;
 use crate::components::items::{
-    Axe, BlocksMovement, Bush, CraftQueue, FirePit, Flint, InBackpack, Item, PickupQueue, Rose,
-    Three, Tier, WoodenStick,
+    Axe, BlocksMovement, Bush, Capacity, CraftQueue, Encumbered, FirePit, Flint, HarvestQueue,
+    InBackpack, Item, Pickaxe, PickupQueue, Regrowing, ResourceNode, Rose, Three, Tier,
+    TransferQueue, Weight, WoodenStick,
+};
+use crate::components::lighting::{Light, LightMap};
+use crate::components::milestones::Milestones;
+use crate::components::power::{
+    Burner, Fuel, Generator, Heat, PowerConsumer, PowerGrids, PowerPole, Solar,
+};
+use crate::components::rails::{Cart, Rail, RailSignal, Station};
+use crate::components::research::{Lab, Research, ResearchQueue};
+use crate::components::signals::{
+    ArithmeticCombinator, Controlled, DeciderCombinator, Disabled, SignalNetworks, Wire,
+};
+use crate::components::statistics::Statistics;
+use crate::components::stockpiles::Stockpiles;
+use crate::components::structures::{
+    Belt, BuildQueue, Chest, Container, DeconstructQueue, Facing, FilterSplitter, Furnace,
+    Inserter, Machine, Merger, Splitter, Structure, Tunnel,
 };
+use crate::components::workers::Worker;
 use crate::config::{load_config, Config};
+use crate::events::Events;
 use crate::gui::{MenuMode, UserInterfaceState};
 use crate::logs::Log;
 use crate::map::new_map;
-use crate::player::{ControlMode, Player};
-use crate::spawner::{axe, generate_items, player};
+use crate::planner::print_plan;
+use crate::player::Player;
+use crate::spawner::{generate_items, player};
 use crate::state::State;
-use crate::systems::pickup::PickupSystem;
 
+mod clock;
 mod components;
 mod config;
+mod events;
 mod gui;
 mod logs;
 mod map;
+mod planner;
 mod player;
 mod spawner;
 mod state;
@@ -80,6 +108,12 @@ impl Display for Name {
 }
 
 fn main() -> BError {
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).is_some_and(|command| command == "plan") {
+        print_plan(&args[2..]);
+        return Ok(());
+    }
+
     let config = {
         let inner = load_config();
         match inner {
@@ -101,6 +135,8 @@ fn main() -> BError {
     state.world.register::<Name>();
     state.world.register::<Item>();
     state.world.register::<Tier>();
+    state.world.register::<Weight>();
+    state.world.register::<Capacity>();
 
     // Items
     state.world.register::<Flint>();
@@ -108,26 +144,110 @@ fn main() -> BError {
     state.world.register::<WoodenStick>();
     state.world.register::<Rose>();
     state.world.register::<Axe>();
+    state.world.register::<Pickaxe>();
+    state.world.register::<ResourceNode>();
+    state.world.register::<Regrowing>();
     state.world.register::<FirePit>();
     state.world.register::<Three>();
 
+    // Structures
+    state.world.register::<Structure>();
+    state.world.register::<Container>();
+    state.world.register::<Chest>();
+    state.world.register::<Facing>();
+    state.world.register::<Belt>();
+    state.world.register::<Inserter>();
+    state.world.register::<Splitter>();
+    state.world.register::<FilterSplitter>();
+    state.world.register::<Merger>();
+    state.world.register::<Tunnel>();
+    state.world.register::<Machine>();
+    state.world.register::<Furnace>();
+    state.world.register::<Ghost>();
+    state.world.register::<ConstructionSite>();
+
+    // Farming
+    state.world.register::<Crop>();
+
+    // Fluids
+    state.world.register::<FluidBox>();
+    state.world.register::<Pipe>();
+    state.world.register::<Pump>();
+    state.world.register::<Tank>();
+
+    // Power
+    state.world.register::<Fuel>();
+    state.world.register::<Burner>();
+    state.world.register::<Heat>();
+    state.world.register::<Generator>();
+    state.world.register::<PowerPole>();
+    state.world.register::<PowerConsumer>();
+    state.world.register::<Solar>();
+
+    // Lighting
+    state.world.register::<Light>();
+
+    // Research
+    state.world.register::<Lab>();
+
+    // Signals
+    state.world.register::<Wire>();
+    state.world.register::<Controlled>();
+    state.world.register::<Disabled>();
+    state.world.register::<ArithmeticCombinator>();
+    state.world.register::<DeciderCombinator>();
+
+    // Rails
+    state.world.register::<Rail>();
+    state.world.register::<Station>();
+    state.world.register::<RailSignal>();
+    state.world.register::<Cart>();
+
+    // Workers
+    state.world.register::<Worker>();
+
     // Tags
     state.world.register::<BlocksMovement>();
     state.world.register::<Player>();
     state.world.register::<InBackpack>();
+    state.world.register::<Encumbered>();
 
     // Queues
     state.world.register::<PickupQueue>();
     state.world.register::<CraftQueue>();
+    state.world.register::<TransferQueue>();
+    state.world.register::<BuildQueue>();
+    state.world.register::<DeconstructQueue>();
+    state.world.register::<HarvestQueue>();
+    state.world.register::<PlantQueue>();
+    state.world.register::<ResearchQueue>();
 
     state.world.insert(new_map());
-    state.world.insert(Log {
-        entries: vec![
-            "the game has fully loaded".to_string(),
-            "press the apostrophe/grave key to show/hide the logs".to_string(),
-            "press tab to show/hide the right side menu".to_string(),
-        ],
-    });
+    state.world.insert(Clock::default());
+    state.world.insert(RandomNumberGenerator::new());
+    state.world.insert(FluidNetworks::default());
+    state.world.insert(PowerGrids::default());
+    state.world.insert(SignalNetworks::default());
+    state.world.insert(Bottlenecks::default());
+
+    let (blueprints, errors) = load_blueprints(&blueprint_directory());
+    state.world.insert(BlueprintLibrary { blueprints });
+    state.world.insert(History::default());
+    state.world.insert(LightMap::default());
+    state.world.insert(Research::default());
+    state.world.insert(Milestones::default());
+    state.world.insert(Statistics::default());
+    state.world.insert(Stockpiles::default());
+    state.world.insert(Events::default());
+    let mut entries = vec![
+        "the game has fully loaded".to_string(),
+        "press the apostrophe/grave key to show/hide the logs".to_string(),
+        "press tab to show/hide the right side menu".to_string(),
+    ];
+    errors
+        .iter()
+        .for_each(|error| entries.push(format!("could not load blueprint {}", error)));
+    state.world.insert(Log { entries });
     state.world.insert(UserInterfaceState::new(config.show_fps));
 
     let player = player(&mut state.world, 40, 25);
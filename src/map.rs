@@ -1,17 +1,18 @@
-use bracket_lib::color::{BROWN1, BROWN2, BURLYWOOD, GREY, RED};
 use bracket_lib::random::RandomNumberGenerator;
 
-use crate::spawner::generate_items;
+use crate::components::lighting::{shade, LightMap};
 use crate::{to_cp437, BTerm, BLACK, RGB};
 
 pub const WIDTH: usize = 80;
 pub const HEIGHT: usize = 50;
 pub const MAP_COUNT: usize = HEIGHT * WIDTH;
+const LAKES: i32 = 4;
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum TileType {
     Wall,
     Floor,
+    Water,
 }
 
 impl TileType {
@@ -19,22 +20,18 @@ impl TileType {
         match self {
             TileType::Floor => ctx.set(x, y, fg, bg, to_cp437('.')),
             TileType::Wall => ctx.set(x, y, fg, bg, to_cp437('#')),
+            TileType::Water => ctx.set(x, y, fg, bg, to_cp437('≈')),
         }
     }
 
-    pub fn render(&self, ctx: &mut BTerm, x: i32, y: i32) {
-        match self {
-            TileType::Floor => {
-                self.render_custom(ctx, x, y, RGB::from_f32(0.5, 0.5, 0.5), RGB::named(BLACK))
-            }
-            TileType::Wall => self.render_custom(
-                ctx,
-                x,
-                y,
-                RGB::from_f32(0.25, 0.25, 0.25),
-                RGB::named(BLACK),
-            ),
-        }
+    pub fn render(&self, ctx: &mut BTerm, x: i32, y: i32, light: f32) {
+        let (fg, bg) = match self {
+            TileType::Floor => (RGB::from_f32(0.5, 0.5, 0.5), RGB::named(BLACK)),
+            TileType::Wall => (RGB::from_f32(0.25, 0.25, 0.25), RGB::named(BLACK)),
+            TileType::Water => (RGB::from_f32(0.25, 0.5, 1.), RGB::from_f32(0., 0.1, 0.3)),
+        };
+
+        self.render_custom(ctx, x, y, shade(fg, light), shade(bg, light))
     }
 }
 
@@ -55,17 +52,40 @@ pub fn new_map() -> Vec<TileType> {
         map[xy_to_idx((WIDTH - 1) as i32, y)] = TileType::Wall;
     }
 
+    generate_lakes(&mut map);
+
     map
 }
 
-pub fn draw_map(map: &[TileType], ctx: &mut BTerm) {
+fn generate_lakes(map: &mut [TileType]) {
+    let mut rng = RandomNumberGenerator::new();
+    let (center_x, center_y) = ((WIDTH / 2) as i32, (HEIGHT / 2) as i32);
+
+    for _ in 0..LAKES {
+        let lake_x = rng.range(4, WIDTH as i32 - 4);
+        let lake_y = rng.range(4, HEIGHT as i32 - 4);
+        let radius = rng.range(2, 5);
+
+        for x in (lake_x - radius)..=(lake_x + radius) {
+            for y in (lake_y - radius)..=(lake_y + radius) {
+                let is_inside = (x - lake_x).pow(2) + (y - lake_y).pow(2) <= radius.pow(2);
+                let is_near_spawn = (x - center_x).abs() <= 3 && (y - center_y).abs() <= 3;
+                let is_inner = x > 0 && y > 0 && x < WIDTH as i32 - 1 && y < HEIGHT as i32 - 1;
+
+                if is_inside && is_inner && !is_near_spawn {
+                    map[xy_to_idx(x, y)] = TileType::Water;
+                }
+            }
+        }
+    }
+}
+
+pub fn draw_map(map: &[TileType], light_map: &LightMap, ctx: &mut BTerm) {
     let mut x = 0;
     let mut y = 0;
 
     map.iter().for_each(|tile| {
-        match tile {
-            t => t.render(ctx, x, y),
-        };
+        tile.render(ctx, x, y, light_map.at(x, y));
 
         x += 1;
         let should_be_next_row = x > (WIDTH - 1) as i32;
@@ -77,8 +97,5 @@ pub fn draw_map(map: &[TileType], ctx: &mut BTerm) {
 }
 
 pub fn is_tile_walkable(tt: TileType) -> bool {
-    match tt {
-        TileType::Wall => false,
-        _ => true,
-    }
+    !matches!(tt, TileType::Wall | TileType::Water)
 }
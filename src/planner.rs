@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::process::exit;
+
+use crate::clock::TICKS_PER_MINUTE;
+use crate::systems::craft::{Recipe, RECIPES};
+
+/// Recipes are not expanded deeper than this, so a recipe loop can not recurse forever.
+const MAX_DEPTH: usize = 16;
+
+/// One recipe of a plan, run often enough to make `rate` items a minute.
+pub struct Step {
+    pub item: &'static str,
+    pub recipe: &'static Recipe,
+    pub rate: f32,
+    /// How many recipes make the item, so screens can say when there is a choice.
+    pub alternatives: usize,
+}
+
+impl Step {
+    /// Batches a minute it takes to make `rate` items, as every batch makes the recipe's
+    /// `result_amount` of them.
+    pub fn batches(&self) -> f32 {
+        self.rate / self.recipe.result_amount as f32
+    }
+
+    /// Machines running at full speed needed to keep up with `rate`.
+    pub fn machines(&self) -> f32 {
+        self.batches() * self.recipe.craft_ticks as f32 / TICKS_PER_MINUTE as f32
+    }
+
+    pub fn machine_name(&self) -> &'static str {
+        match self.recipe.smelting {
+            true => "Furnace",
+            false => "Assembler",
+        }
+    }
+}
+
+/// What it takes to make `rate` of `target` a minute, down to the items and fluids no recipe
+/// makes.
+pub struct Plan {
+    pub target: &'static str,
+    pub rate: f32,
+    pub steps: Vec<Step>,
+    /// Items and fluids a minute, in the order the recipe tree first needs them.
+    pub raw: Vec<(&'static str, f32)>,
+    /// Byproducts and fluid results a minute that no step of the plan uses up.
+    pub leftovers: Vec<(&'static str, f32)>,
+}
+
+/// Every recipe making `item`, the preferred one first.
+pub fn recipes_for(item: &str) -> Vec<&'static Recipe> {
+    recipes_in(RECIPES, item)
+}
+
+fn recipes_in(recipes: &'static [Recipe], item: &str) -> Vec<&'static Recipe> {
+    recipes
+        .iter()
+        .filter(|recipe| recipe.result_item_name == item)
+        .collect()
+}
+
+/// Every item some recipe makes, once each, in recipe order.
+pub fn craftable_items() -> Vec<&'static str> {
+    let mut items: Vec<&str> = vec![];
+    RECIPES.iter().for_each(|recipe| {
+        if !items.contains(&recipe.result_item_name) {
+            items.push(recipe.result_item_name);
+        }
+    });
+
+    items
+}
+
+fn add(totals: &mut Vec<(&'static str, f32)>, name: &'static str, rate: f32) {
+    match totals.iter_mut().find(|(total, _)| *total == name) {
+        Some((_, total)) => *total += rate,
+        None => totals.push((name, rate)),
+    }
+}
+
+/// Expands the recipe tree of `target`. `choices` picks which recipe makes an item, by its index
+/// in `recipes_for`; items without a choice use their preferred recipe. Returns `None` when no
+/// recipe makes `target`.
+pub fn plan(target: &str, rate: f32, choices: &HashMap<&str, usize>) -> Option<Plan> {
+    plan_in(RECIPES, target, rate, choices)
+}
+
+fn plan_in(
+    recipes: &'static [Recipe],
+    target: &str,
+    rate: f32,
+    choices: &HashMap<&str, usize>,
+) -> Option<Plan> {
+    let target = recipes_in(recipes, target).first()?.result_item_name;
+    let mut plan = Plan {
+        target,
+        rate,
+        steps: vec![],
+        raw: vec![],
+        leftovers: vec![],
+    };
+
+    expand(recipes, &mut plan, target, rate, choices, 0);
+
+    // Byproducts the plan needs elsewhere are not left over, and need not be brought in either.
+    let mut leftovers = std::mem::take(&mut plan.leftovers);
+    leftovers.iter_mut().for_each(|(name, leftover)| {
+        if let Some((_, needed)) = plan.raw.iter_mut().find(|(raw, _)| raw == name) {
+            let used = needed.min(*leftover);
+            *needed -= used;
+            *leftover -= used;
+        }
+    });
+    plan.raw.retain(|(_, rate)| *rate > 0.);
+    plan.leftovers = leftovers
+        .into_iter()
+        .filter(|(_, rate)| *rate > 0.)
+        .collect();
+
+    Some(plan)
+}
+
+fn expand(
+    all: &'static [Recipe],
+    plan: &mut Plan,
+    item: &'static str,
+    rate: f32,
+    choices: &HashMap<&str, usize>,
+    depth: usize,
+) {
+    let recipes = recipes_in(all, item);
+    let choice = choices.get(item).copied().unwrap_or(0);
+    let recipe = match recipes.get(choice).or(recipes.first()) {
+        Some(recipe) if depth < MAX_DEPTH => *recipe,
+        _ => return add(&mut plan.raw, item, rate),
+    };
+    let batches = rate / recipe.result_amount as f32;
+
+    match plan.steps.iter_mut().find(|step| step.item == item) {
+        Some(step) => step.rate += rate,
+        None => plan.steps.push(Step {
+            item,
+            recipe,
+            rate,
+            alternatives: recipes.len(),
+        }),
+    }
+
+    recipe.byproducts.iter().for_each(|byproduct| {
+        let per_batch = byproduct.amount as f32 * byproduct.chance.unwrap_or(1.);
+        add(
+            &mut plan.leftovers,
+            byproduct.item_name,
+            batches * per_batch,
+        );
+    });
+    recipe.fluid_results.iter().for_each(|result| {
+        add(
+            &mut plan.leftovers,
+            result.fluid.name(),
+            batches * result.amount,
+        );
+    });
+    recipe.fluid_requirements.iter().for_each(|requirement| {
+        add(
+            &mut plan.raw,
+            requirement.fluid.name(),
+            batches * requirement.amount,
+        );
+    });
+    recipe.requirements.iter().for_each(|requirement| {
+        expand(
+            all,
+            plan,
+            requirement.item_name,
+            batches * requirement.amount as f32,
+            choices,
+            depth + 1,
+        );
+    });
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} at {:.1} a minute", self.target, self.rate)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<22}{:>10}{:>10}  machine",
+            "step", "per min", "machines"
+        )?;
+        for step in self.steps.iter() {
+            let recipe = match step.alternatives > 1 {
+                true => format!(" ({} recipes)", step.alternatives),
+                false => String::new(),
+            };
+            writeln!(
+                f,
+                "{:<22}{:>10.1}{:>10.2}  {}{}",
+                step.item,
+                step.rate,
+                step.machines(),
+                step.machine_name(),
+                recipe
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "raw input per minute")?;
+        for (name, rate) in self.raw.iter() {
+            writeln!(f, "  {:<20}{:>10.1}", name, rate)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "leftover byproducts per minute")?;
+        if self.leftovers.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        for (name, rate) in self.leftovers.iter() {
+            writeln!(f, "  {:<20}{:>10.1}", name, rate)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The `plan` subcommand: `plan <item> [per minute] [--recipe <item>=<index>]...`, printing the
+/// plan instead of starting the game.
+pub fn print_plan(args: &[String]) {
+    let usage = "usage: plan <item> [per minute] [--recipe <item>=<index>]...";
+
+    let mut positional = vec![];
+    let mut choices: HashMap<&str, usize> = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg != "--recipe" {
+            positional.push(arg.as_str());
+            continue;
+        }
+
+        let choice = args
+            .next()
+            .and_then(|choice| choice.split_once('='))
+            .and_then(|(item, index)| Some((item, index.parse().ok()?)));
+        match choice {
+            Some((item, index)) => choices.insert(item, index),
+            None => fail(usage),
+        };
+    }
+
+    let (target, rate) = match positional[..] {
+        [target] => (target, 1.),
+        [target, rate] => match rate.parse::<f32>() {
+            Ok(rate) if rate.is_finite() && rate > 0. => (target, rate),
+            _ => fail(usage),
+        },
+        _ => fail(usage),
+    };
+
+    match plan(target, rate, &choices) {
+        Some(plan) => print!("{}", plan),
+        None => fail(&format!("no recipe makes {}", target)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::craft::Requirement;
+
+    const fn recipe(
+        result_item_name: &'static str,
+        result_amount: i32,
+        requirements: &'static [Requirement],
+    ) -> Recipe {
+        Recipe {
+            requirements,
+            fluid_requirements: &[],
+            fluid_results: &[],
+            result_item_name,
+            result_amount,
+            byproducts: &[],
+            craft_ticks: TICKS_PER_MINUTE / 2,
+            needs_fire: false,
+            smelting: false,
+        }
+    }
+
+    static TEST_RECIPES: &[Recipe] = &[
+        recipe(
+            "Plank",
+            1,
+            &[Requirement {
+                item_name: "Log",
+                amount: 2,
+            }],
+        ),
+        recipe(
+            "Box",
+            1,
+            &[Requirement {
+                item_name: "Plank",
+                amount: 3,
+            }],
+        ),
+        recipe(
+            "Wire",
+            2,
+            &[Requirement {
+                item_name: "Copper",
+                amount: 1,
+            }],
+        ),
+        recipe(
+            "Circuit",
+            1,
+            &[Requirement {
+                item_name: "Wire",
+                amount: 2,
+            }],
+        ),
+        recipe(
+            "Motor",
+            1,
+            &[
+                Requirement {
+                    item_name: "Circuit",
+                    amount: 1,
+                },
+                Requirement {
+                    item_name: "Wire",
+                    amount: 3,
+                },
+            ],
+        ),
+    ];
+
+    fn rate_of(plan: &Plan, item: &str) -> f32 {
+        plan.steps
+            .iter()
+            .find(|step| step.item == item)
+            .map_or(0., |step| step.rate)
+    }
+
+    #[test]
+    fn expands_a_simple_chain() {
+        let plan = plan_in(TEST_RECIPES, "Box", 2., &HashMap::new()).unwrap();
+
+        let items: Vec<&str> = plan.steps.iter().map(|step| step.item).collect();
+        assert_eq!(items, vec!["Box", "Plank"]);
+        assert_eq!(rate_of(&plan, "Plank"), 6.);
+        assert_eq!(plan.steps[1].machines(), 3.);
+        assert_eq!(plan.raw, vec![("Log", 12.)]);
+        assert!(plan.leftovers.is_empty());
+    }
+
+    #[test]
+    fn merges_a_shared_intermediate_into_one_step() {
+        let plan = plan_in(TEST_RECIPES, "Motor", 1., &HashMap::new()).unwrap();
+
+        assert_eq!(plan.steps.len(), 3);
+        assert_eq!(rate_of(&plan, "Circuit"), 1.);
+        assert_eq!(rate_of(&plan, "Wire"), 5.);
+        // Every batch of wire makes two.
+        let wire = plan.steps.iter().find(|step| step.item == "Wire").unwrap();
+        assert_eq!(wire.batches(), 2.5);
+        assert_eq!(plan.raw, vec![("Copper", 2.5)]);
+    }
+
+    #[test]
+    fn has_no_plan_for_raw_items() {
+        assert!(plan_in(TEST_RECIPES, "Log", 1., &HashMap::new()).is_none());
+    }
+}
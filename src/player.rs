@@ -1,131 +1,1911 @@
 use std::cmp::{max, min};
+use std::collections::HashSet;
+use std::env;
+use std::fs::{create_dir_all, write};
+use std::ops::Deref;
 
+use specs::storage::MaskedStorage;
 use specs::Component;
-use specs::{Join, WorldExt};
+use specs::{Entity, Join, Storage, WorldExt};
 use specs_derive::Component;
 use VirtualKeyCode::*;
 
 use MenuMode::{Interact, Inventory};
 
-use crate::components::items::{get_item, BlocksMovement};
+use crate::clock::Clock;
+use crate::components::blueprints::{
+    blueprint_directory, load_blueprints, Blueprint, BlueprintEntry, BlueprintLibrary, Ghost,
+};
+use crate::components::construction::ConstructionSite;
+use crate::components::farming::{plant_by_seed, PlantQueue};
+use crate::components::history::{Action, History, Replay, Setting};
+use crate::components::items::{
+    get_item, BlocksMovement, Capacity, Encumbered, HarvestQueue, ResourceNode, TransferQueue,
+    Weight,
+};
+use crate::components::power::Burner;
+use crate::components::rails::{Cart, Stop, Until};
+use crate::components::research::{Research, ResearchQueue};
+use crate::components::signals::{
+    ArithmeticCombinator, Comparison, Condition, Controlled, DeciderCombinator,
+};
+use crate::components::statistics::{Statistics, WINDOWS};
+use crate::components::stockpiles::Stockpiles;
+use crate::components::structures::{
+    BuildQueue, Container, DeconstructQueue, Direction, Facing, FilterSplitter, Furnace, Inserter,
+    Machine, Merger, Side, Splitter, Structure,
+};
+use crate::components::workers::{Assignment, Job, Worker, HIGHEST_PRIORITY};
+use crate::gui::blueprints::structure_glyph;
+use crate::gui::build::structure_stacks;
+use crate::gui::container::{focused_stacks, Pane};
 use crate::gui::menu::craft;
-use crate::map::{is_tile_walkable, xy_to_idx, TileType};
-use crate::systems::craft::RECIPES;
-use crate::MenuMode::{Craft, Default};
+use crate::gui::rails::station_names;
+use crate::gui::signals::{is_controllable, signal_fields, signal_names};
+use crate::gui::stockpiles::item_kinds;
+use crate::gui::workers::{job_choices, JobChoices};
+use crate::map::{is_tile_walkable, xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::planner::{craftable_items, recipes_for};
+use crate::spawner::ghost;
+use crate::systems::construction::refund;
+use crate::systems::craft::{Recipe, RECIPES};
+use crate::systems::research::{is_unlocked, TECHNOLOGIES};
+use crate::MenuMode::{Build, Craft, Default};
 use crate::{
-    BTerm, DenseVecStorage, Log, MenuMode, Position, State, UserInterfaceState, VirtualKeyCode,
-    World,
+    BTerm, DenseVecStorage, InBackpack, Item, Log, MenuMode, Name, Position, Renderable, State,
+    UserInterfaceState, VirtualKeyCode, World,
 };
 
-#[derive(Component, Debug)]
-pub struct Player {}
+#[derive(Component, Debug)]
+pub struct Player {}
+
+fn try_move_player(delta_x: i32, delta_y: i32, world: &mut World) {
+    {
+        let player = world.fetch::<Entity>();
+        let mut encumbered = world.write_storage::<Encumbered>();
+
+        if let Some(encumbered) = encumbered.get_mut(*player) {
+            encumbered.out_of_breath = !encumbered.out_of_breath;
+
+            if encumbered.out_of_breath {
+                return;
+            }
+        }
+    }
+
+    let blockers = world.read_storage::<BlocksMovement>();
+    let mut positions = world.write_storage::<Position>();
+    let mut players = world.write_storage::<Player>();
+    let map = world.fetch::<Vec<TileType>>();
+
+    let (player_x, player_y) = {
+        let player = (&players, &positions).join().next().unwrap();
+        (player.1.x, player.1.y)
+    };
+
+    let blocked = blocked_tiles(&positions, &blockers);
+    if is_walkable(&map, &blocked, (player_x + delta_x, player_y + delta_y)) {
+        let player = (&mut players, &mut positions).join().next().unwrap();
+        player.1.x = (player_x + delta_x).clamp(0, 79);
+        player.1.y = (player_y + delta_y).clamp(0, 49);
+    }
+}
+
+/// The tiles something stands on that blocks movement.
+pub fn blocked_tiles<P, B>(
+    positions: &Storage<Position, P>,
+    blockers: &Storage<BlocksMovement, B>,
+) -> HashSet<(i32, i32)>
+where
+    P: Deref<Target = MaskedStorage<Position>>,
+    B: Deref<Target = MaskedStorage<BlocksMovement>>,
+{
+    (positions, blockers)
+        .join()
+        .map(|(position, _)| (position.x, position.y))
+        .collect()
+}
+
+/// Whether the player, or a worker, can step onto `(x, y)`: a walkable tile on the map that is
+/// not `blocked`.
+pub fn is_walkable(map: &[TileType], blocked: &HashSet<(i32, i32)>, (x, y): (i32, i32)) -> bool {
+    let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+    is_on_map && is_tile_walkable(map[xy_to_idx(x, y)]) && !blocked.contains(&(x, y))
+}
+
+pub fn player_position(world: &World) -> (i32, i32) {
+    let player = world.fetch::<Entity>();
+    let positions = world.read_storage::<Position>();
+    let position = positions.get(*player).unwrap();
+
+    (position.x, position.y)
+}
+
+pub fn adjacent_container(world: &World) -> Option<Entity> {
+    let (player_x, player_y) = player_position(world);
+    let entities = world.entities();
+    let containers = world.read_storage::<Container>();
+    let positions = world.read_storage::<Position>();
+
+    (&entities, &containers, &positions)
+        .join()
+        .find(|(_, _, position)| {
+            (position.x - player_x).abs() <= 1 && (position.y - player_y).abs() <= 1
+        })
+        .map(|(entity, _, _)| entity)
+}
+
+fn move_cursor(delta_x: i32, delta_y: i32, world: &mut World) {
+    let mut ui = world.fetch_mut::<UserInterfaceState>();
+    let (x, y) = ui.cursor;
+
+    ui.cursor = (
+        min(WIDTH as i32 - 1, max(0, x + delta_x)),
+        min(HEIGHT as i32 - 1, max(0, y + delta_y)),
+    );
+}
+
+fn place_structure(world: &mut World) {
+    let stacks = structure_stacks(world);
+    let player = *world.fetch::<Entity>();
+    let mut ui = world.fetch_mut::<UserInterfaceState>();
+
+    let stack = match stacks.get(ui.selected_option) {
+        None => return,
+        Some((_, stack)) => stack,
+    };
+
+    let (x, y) = ui.cursor;
+    world
+        .write_storage::<BuildQueue>()
+        .insert(
+            player,
+            BuildQueue {
+                structure: stack[0],
+                x,
+                y,
+                direction: ui.facing,
+            },
+        )
+        .expect("could not use build system");
+
+    let is_last_of_stack = stack.len() == 1 && ui.selected_option + 1 == stacks.len();
+    if is_last_of_stack && ui.selected_option > 0 {
+        ui.selected_option -= 1;
+    }
+}
+
+/// Takes down the structure under the build cursor, back into the backpack.
+fn deconstruct(world: &mut World) {
+    let (x, y) = world.fetch::<UserInterfaceState>().cursor;
+    let player = *world.fetch::<Entity>();
+
+    match structure_at(x, y, world) {
+        None => Log::by_world(world, "there is nothing to take down here"),
+        Some(structure) => {
+            world
+                .write_storage::<DeconstructQueue>()
+                .insert(player, DeconstructQueue { structure })
+                .expect("could not use deconstruct system");
+        }
+    }
+}
+
+/// Rotates the structure under the build cursor, or the direction new structures are placed in
+/// when there is nothing here to rotate.
+fn rotate(world: &mut World) {
+    let (x, y) = world.fetch::<UserInterfaceState>().cursor;
+    let facing = structure_at(x, y, world).and_then(|structure| {
+        world
+            .read_storage::<Facing>()
+            .get(structure)
+            .map(|facing| (structure, facing.direction))
+    });
+
+    match facing {
+        None => {
+            let mut ui = world.fetch_mut::<UserInterfaceState>();
+            ui.facing = ui.facing.clockwise();
+        }
+        Some((structure, from)) => {
+            face(structure, from.clockwise(), world);
+            world.fetch_mut::<History>().record(Action::Rotate {
+                structure,
+                from,
+                to: from.clockwise(),
+            });
+        }
+    }
+}
+
+fn face(structure: Entity, direction: Direction, world: &mut World) {
+    let mut facings = world.write_storage::<Facing>();
+    let mut renderables = world.write_storage::<Renderable>();
+
+    if let Some(facing) = facings.get_mut(structure) {
+        facing.direction = direction;
+
+        if let Some(render) = renderables.get_mut(structure) {
+            render.glyph = facing.glyph();
+        }
+    }
+}
+
+/// The structure itself when the player still carries it, or else any they carry by its name.
+fn carried_structure(structure: Entity, name: &str, world: &World) -> Option<Entity> {
+    let player = *world.fetch::<Entity>();
+    let entities = world.entities();
+    let backpack = world.read_storage::<InBackpack>();
+    let structures = world.read_storage::<Structure>();
+    let names = world.read_storage::<Name>();
+
+    if backpack
+        .get(structure)
+        .is_some_and(|pack| pack.owner == player)
+    {
+        return Some(structure);
+    }
+
+    (&entities, &backpack, &structures, &names)
+        .join()
+        .find(|(_, pack, _, known)| pack.owner == player && known.name == name)
+        .map(|(entity, _, _, _)| entity)
+}
+
+/// Takes back the most recent build mode action when `undoing`, or else carries out the most
+/// recently undone one again. Placing and taking down are left to their systems, which settle
+/// the history once they are done.
+fn replay(undoing: bool, world: &mut World) {
+    let popped = {
+        let mut history = world.fetch_mut::<History>();
+
+        match (history.replaying.is_some(), undoing) {
+            (true, _) => return,
+            (false, true) => history.undo.pop(),
+            (false, false) => history.redo.pop(),
+        }
+    };
+    let replay = match (popped, undoing) {
+        (None, true) => return Log::by_world(world, "there is nothing to undo"),
+        (None, false) => return Log::by_world(world, "there is nothing to redo"),
+        (Some(action), _) => Replay { action, undoing },
+    };
+
+    let structure = replay.action.structure();
+    let name = world
+        .read_storage::<Name>()
+        .get(structure)
+        .map_or("structure".to_string(), |name| name.name.clone());
+    let is_placed = world.read_storage::<Position>().contains(structure);
+
+    match replay.applied() {
+        Action::Place {
+            structure,
+            name,
+            x,
+            y,
+            direction,
+        } => match carried_structure(structure, &name, world) {
+            None => {
+                Log::by_world(world, format!("you have no {} to place back", name));
+                world.fetch_mut::<History>().failed(replay);
+            }
+            Some(structure) => {
+                let player = *world.fetch::<Entity>();
+                world
+                    .write_storage::<BuildQueue>()
+                    .insert(
+                        player,
+                        BuildQueue {
+                            structure,
+                            x,
+                            y,
+                            direction,
+                        },
+                    )
+                    .expect("could not use build system");
+                world.fetch_mut::<History>().replaying = Some(replay);
+            }
+        },
+        _ if !is_placed => {
+            Log::by_world(world, format!("the {} is no longer there", name));
+            world.fetch_mut::<History>().failed(replay);
+        }
+        Action::Deconstruct { structure, .. } => {
+            let player = *world.fetch::<Entity>();
+            world
+                .write_storage::<DeconstructQueue>()
+                .insert(player, DeconstructQueue { structure })
+                .expect("could not use deconstruct system");
+            world.fetch_mut::<History>().replaying = Some(replay);
+        }
+        Action::Rotate { to, .. } => {
+            face(structure, to, world);
+            Log::by_world(world, format!("the {} faces {} again", name, to.name()));
+            world.fetch_mut::<History>().replayed(replay);
+        }
+        Action::Configure { to, .. } => {
+            set_setting(structure, &to, world);
+            world.fetch_mut::<History>().replayed(replay);
+        }
+    }
+}
+
+/// The structure at `x`, `y`. Carts and workers are picked over what they stand on.
+fn structure_at(x: i32, y: i32, world: &World) -> Option<Entity> {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let structures = world.read_storage::<Structure>();
+    let carts = world.read_storage::<Cart>();
+    let workers = world.read_storage::<Worker>();
+
+    (&entities, &positions, &structures)
+        .join()
+        .filter(|(_, position, _)| position.x == x && position.y == y)
+        .max_by_key(|(entity, _, _)| carts.contains(*entity) || workers.contains(*entity))
+        .map(|(entity, _, _)| entity)
+}
+
+fn ghost_at(x: i32, y: i32, world: &World) -> Option<Entity> {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let ghosts = world.read_storage::<Ghost>();
+
+    (&entities, &positions, &ghosts)
+        .join()
+        .find(|(_, position, _)| position.x == x && position.y == y)
+        .map(|(entity, _, _)| entity)
+}
+
+/// Takes a ghost away, giving whatever was delivered to its construction site back to the player.
+fn cancel_site(site: Entity, world: &mut World) {
+    let player = *world.fetch::<Entity>();
+    let delivered: Vec<Entity> = {
+        let entities = world.entities();
+        let backpack = world.read_storage::<InBackpack>();
+
+        (&entities, &backpack)
+            .join()
+            .filter(|(_, pack)| pack.owner == site)
+            .map(|(item, _)| item)
+            .collect()
+    };
+
+    if !delivered.is_empty() {
+        let tile = {
+            let positions = world.read_storage::<Position>();
+            let position = positions.get(site).unwrap();
+            (position.x, position.y)
+        };
+        let refunded = {
+            let mut backpack = world.write_storage::<InBackpack>();
+            let mut positions = world.write_storage::<Position>();
+            let capacities = world.read_storage::<Capacity>();
+            let weights = world.read_storage::<Weight>();
+
+            delivered
+                .iter()
+                .filter(|item| {
+                    refund(
+                        **item,
+                        player,
+                        tile,
+                        &mut backpack,
+                        &mut positions,
+                        &capacities,
+                        &weights,
+                    )
+                })
+                .count()
+        };
+        let message = match refunded == delivered.len() {
+            true => "you take back what was delivered",
+            false => "some of what was delivered is left on the ground",
+        };
+        world.fetch_mut::<Log>().log(message);
+    }
+
+    world.delete_entity(site).expect("should delete ghost");
+}
+
+fn remove_ghost(world: &mut World) {
+    let (x, y) = world.fetch::<UserInterfaceState>().cursor;
+
+    match ghost_at(x, y, world) {
+        None => Log::by_world(world, "there is no ghost here"),
+        Some(ghost) => cancel_site(ghost, world),
+    }
+}
+
+/// Starts selecting an area at the build cursor, or copies the structures and ghosts in the
+/// selected area into a new blueprint, which build mode then pastes.
+fn copy_area(world: &mut World) {
+    let (start, cursor) = {
+        let mut ui = world.fetch_mut::<UserInterfaceState>();
+
+        match ui.selection.take() {
+            None => {
+                ui.selection = Some(ui.cursor);
+                return;
+            }
+            Some(start) => (start, ui.cursor),
+        }
+    };
+    let (left, top) = (min(start.0, cursor.0), min(start.1, cursor.1));
+    let (right, bottom) = (max(start.0, cursor.0), max(start.1, cursor.1));
+    let is_inside = |position: &Position| {
+        position.x >= left && position.x <= right && position.y >= top && position.y <= bottom
+    };
+
+    let entries: Vec<BlueprintEntry> = {
+        let entities = world.entities();
+        let positions = world.read_storage::<Position>();
+        let structures = world.read_storage::<Structure>();
+        let names = world.read_storage::<Name>();
+        let facings = world.read_storage::<Facing>();
+        let machines = world.read_storage::<Machine>();
+        let furnaces = world.read_storage::<Furnace>();
+        let inserters = world.read_storage::<Inserter>();
+        let filter_splitters = world.read_storage::<FilterSplitter>();
+        let splitters = world.read_storage::<Splitter>();
+        let mergers = world.read_storage::<Merger>();
+        let ghosts = world.read_storage::<Ghost>();
+        let carts = world.read_storage::<Cart>();
+        let workers = world.read_storage::<Worker>();
+
+        // Carts and workers move on their own, so they are not part of the layout.
+        let built = (
+            &entities,
+            &positions,
+            &structures,
+            &names,
+            !&carts,
+            !&workers,
+        )
+            .join()
+            .filter(|(_, position, _, _, _, _)| is_inside(position))
+            .map(|(entity, position, _, name, _, _)| BlueprintEntry {
+                x: position.x - left,
+                y: position.y - top,
+                ghost: Ghost {
+                    structure: name.name.clone(),
+                    direction: facings
+                        .get(entity)
+                        .map_or(Direction::default(), |facing| facing.direction),
+                    // Furnaces pick their own recipe.
+                    recipe: machines
+                        .get(entity)
+                        .filter(|_| !furnaces.contains(entity))
+                        .and_then(|machine| machine.recipe)
+                        .map(str::to_string),
+                    filter: match inserters.get(entity) {
+                        Some(inserter) => inserter.filter.clone(),
+                        None => filter_splitters
+                            .get(entity)
+                            .and_then(|splitter| splitter.filter.clone()),
+                    },
+                    priority: match splitters.get(entity) {
+                        Some(splitter) => splitter.priority,
+                        None => mergers.get(entity).and_then(|merger| merger.priority),
+                    },
+                },
+            });
+        let planned = (&positions, &ghosts)
+            .join()
+            .filter(|(position, _)| is_inside(position))
+            .map(|(position, ghost)| BlueprintEntry {
+                x: position.x - left,
+                y: position.y - top,
+                ghost: ghost.clone(),
+            });
+
+        built.chain(planned).collect()
+    };
+
+    if entries.is_empty() {
+        Log::by_world(world, "there is nothing to copy here");
+        return;
+    }
+
+    let count = entries.len();
+    let name = {
+        let mut library = world.fetch_mut::<BlueprintLibrary>();
+        let name = library.next_name();
+        library.add(Blueprint {
+            name: name.clone(),
+            width: right - left + 1,
+            height: bottom - top + 1,
+            entries,
+        });
+
+        world.fetch_mut::<UserInterfaceState>().blueprint = Some(library.blueprints.len() - 1);
+        name
+    };
+
+    Log::by_world(
+        world,
+        format!("you copy {} structures into {}", count, name),
+    );
+}
+
+/// Lays out the ghosts of the blueprint build mode pastes, with its top left corner at the build
+/// cursor. Ghosts already on those tiles are replaced.
+fn paste_blueprint(world: &mut World) {
+    let (cursor, index) = {
+        let ui = world.fetch::<UserInterfaceState>();
+        (ui.cursor, ui.blueprint)
+    };
+    let blueprint = index.and_then(|index| {
+        world
+            .fetch::<BlueprintLibrary>()
+            .blueprints
+            .get(index)
+            .cloned()
+    });
+    let blueprint = match blueprint {
+        None => {
+            Log::by_world(world, "copy an area or pick a blueprint first");
+            return;
+        }
+        Some(blueprint) => blueprint,
+    };
+
+    let mut placed = 0;
+    for entry in blueprint.entries {
+        let (x, y) = (cursor.0 + entry.x, cursor.1 + entry.y);
+        let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+        if !is_on_map || !is_tile_walkable(world.fetch::<Vec<TileType>>()[xy_to_idx(x, y)]) {
+            continue;
+        }
+
+        if let Some(old) = ghost_at(x, y, world) {
+            cancel_site(old, world);
+        }
+
+        let glyph = structure_glyph(world, &entry.ghost.structure, entry.ghost.direction);
+        let site = ConstructionSite::default();
+        ghost(world.create_entity(), entry.ghost, site, x, y, glyph);
+        placed += 1;
+    }
+
+    Log::by_world(
+        world,
+        format!("you plan {} structures from {}", placed, blueprint.name),
+    );
+}
+
+/// Writes the selected blueprint as a KDL file and as a text string next to the game.
+fn export_blueprint(world: &World) {
+    let selected = world.fetch::<UserInterfaceState>().selected_option;
+    let library = world.fetch::<BlueprintLibrary>();
+    let blueprint = match library.blueprints.get(selected) {
+        None => return,
+        Some(blueprint) => blueprint,
+    };
+
+    let directory = blueprint_directory();
+    if let Err(error) = create_dir_all(&directory) {
+        Log::by_world(world, format!("could not save blueprint: {}", error));
+        return;
+    }
+
+    let file_name: String = blueprint
+        .name
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == ' ' || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    let exports = [
+        (format!("{}.kdl", file_name), blueprint.to_kdl()),
+        (format!("{}.txt", file_name), blueprint.to_text() + "\n"),
+    ];
+    for (file, contents) in exports {
+        let path = directory.join(file);
+
+        match write(&path, contents) {
+            Ok(_) => Log::by_world(world, format!("blueprint saved to {}", path.display())),
+            Err(error) => Log::by_world(world, format!("could not save blueprint: {}", error)),
+        }
+    }
+}
+
+/// Adds every blueprint in the blueprint directory that is not in the library yet.
+fn import_blueprints(world: &World) {
+    let directory = blueprint_directory();
+    let (blueprints, errors) = load_blueprints(&directory);
+
+    errors
+        .iter()
+        .for_each(|error| Log::by_world(world, format!("could not read {}", error)));
+
+    let added = {
+        let mut library = world.fetch_mut::<BlueprintLibrary>();
+        blueprints
+            .into_iter()
+            .filter(|blueprint| library.add(blueprint.clone()))
+            .count()
+    };
+
+    match added {
+        0 => Log::by_world(
+            world,
+            format!("no new blueprints in {}", directory.display()),
+        ),
+        added => Log::by_world(world, format!("you import {} blueprints", added)),
+    }
+}
+
+/// Configures the structure under the build cursor: inserters and filter splitters cycle their
+/// item filter, splitters and mergers their priority side and machines their recipe. Carts open
+/// their schedule and workers their jobs.
+fn configure(world: &mut World) {
+    let (x, y) = world.fetch::<UserInterfaceState>().cursor;
+    let structure = structure_at(x, y, world);
+    let from = structure.and_then(|structure| setting_of(structure, world));
+
+    match structure {
+        Some(inserter) if world.read_storage::<Inserter>().contains(inserter) => {
+            cycle_inserter_filter(inserter, world)
+        }
+        Some(splitter) if world.read_storage::<FilterSplitter>().contains(splitter) => {
+            cycle_splitter_filter(splitter, world)
+        }
+        Some(splitter)
+            if world.read_storage::<Splitter>().contains(splitter)
+                || world.read_storage::<Merger>().contains(splitter) =>
+        {
+            cycle_priority(splitter, world)
+        }
+        Some(furnace) if world.read_storage::<Furnace>().contains(furnace) => {
+            Log::by_world(world, "the furnace smelts whatever ore it is given")
+        }
+        Some(machine) if world.read_storage::<Machine>().contains(machine) => {
+            cycle_machine_recipe(machine, world)
+        }
+        Some(cart) if world.read_storage::<Cart>().contains(cart) => {
+            let mut ui = world.fetch_mut::<UserInterfaceState>();
+
+            ui.cart = Some(cart);
+            ui.selected_option = 0;
+            ui.control_mode = ControlMode::Schedule;
+            ui.menu_mode = MenuMode::Schedule;
+        }
+        Some(worker) if world.read_storage::<Worker>().contains(worker) => {
+            let mut ui = world.fetch_mut::<UserInterfaceState>();
+
+            ui.worker = Some(worker);
+            ui.selected_option = 0;
+            ui.control_mode = ControlMode::Worker;
+            ui.menu_mode = MenuMode::Worker;
+        }
+        _ => Log::by_world(world, "there is nothing to configure here"),
+    }
+
+    let to = structure.and_then(|structure| setting_of(structure, world));
+    if let (Some(structure), Some(from), Some(to)) = (structure, from, to) {
+        if from != to {
+            world.fetch_mut::<History>().record(Action::Configure {
+                structure,
+                from,
+                to,
+            });
+        }
+    }
+}
+
+fn setting_of(structure: Entity, world: &World) -> Option<Setting> {
+    if let Some(inserter) = world.read_storage::<Inserter>().get(structure) {
+        return Some(Setting::Filter(inserter.filter.clone()));
+    }
+    if let Some(splitter) = world.read_storage::<FilterSplitter>().get(structure) {
+        return Some(Setting::Filter(splitter.filter.clone()));
+    }
+    if let Some(splitter) = world.read_storage::<Splitter>().get(structure) {
+        return Some(Setting::Priority(splitter.priority));
+    }
+    if let Some(merger) = world.read_storage::<Merger>().get(structure) {
+        return Some(Setting::Priority(merger.priority));
+    }
+
+    world
+        .read_storage::<Machine>()
+        .get(structure)
+        .map(|machine| Setting::Recipe(machine.recipe))
+}
+
+fn set_setting(structure: Entity, setting: &Setting, world: &mut World) {
+    let mut log = world.fetch_mut::<Log>();
+    let name = world
+        .read_storage::<Name>()
+        .get(structure)
+        .unwrap()
+        .to_string();
+
+    match setting {
+        Setting::Filter(filter) => {
+            if let Some(inserter) = world.write_storage::<Inserter>().get_mut(structure) {
+                inserter.filter = filter.clone();
+            }
+            if let Some(splitter) = world.write_storage::<FilterSplitter>().get_mut(structure) {
+                splitter.filter = filter.clone();
+            }
+            match filter {
+                None => log.log(format!("the {} takes any item again", name)),
+                Some(filter) => log.log(format!("the {} filters {} again", name, filter)),
+            }
+        }
+        Setting::Priority(priority) => {
+            if let Some(splitter) = world.write_storage::<Splitter>().get_mut(structure) {
+                splitter.priority = *priority;
+            }
+            if let Some(merger) = world.write_storage::<Merger>().get_mut(structure) {
+                merger.priority = *priority;
+            }
+            match priority {
+                None => log.log(format!("the {} takes turns again", name)),
+                Some(side) => log.log(format!(
+                    "the {} prefers its {} side again",
+                    name,
+                    side.name()
+                )),
+            }
+        }
+        Setting::Recipe(recipe) => {
+            if let Some(machine) = world.write_storage::<Machine>().get_mut(structure) {
+                machine.recipe = *recipe;
+                machine.progress = None;
+            }
+            match recipe {
+                None => log.log("the machine is idle again"),
+                Some(recipe) => log.log(format!("the machine makes {} again", recipe)),
+            }
+        }
+    }
+}
+
+/// The item filter after `filter` among the items that can be picked up, going back to no filter
+/// after the last one.
+fn next_filter(filter: &Option<String>, world: &World) -> Option<String> {
+    let names = world.read_storage::<Name>();
+    let items = world.read_storage::<Item>();
+
+    let mut known: Vec<&String> = (&items, &names)
+        .join()
+        .filter(|(item, _)| item.can_be_picked)
+        .map(|(_, name)| &name.name)
+        .collect();
+    known.sort();
+    known.dedup();
+
+    let next = match filter {
+        None => known.first(),
+        Some(filter) => known
+            .iter()
+            .position(|name| *name == filter)
+            .and_then(|index| known.get(index + 1)),
+    };
+    next.map(|name| name.to_string())
+}
+
+fn cycle_inserter_filter(inserter: Entity, world: &mut World) {
+    let mut inserters = world.write_storage::<Inserter>();
+    let mut log = world.fetch_mut::<Log>();
+    let inserter = inserters.get_mut(inserter).unwrap();
+
+    inserter.filter = next_filter(&inserter.filter, world);
+
+    match &inserter.filter {
+        None => log.log("the inserter now moves any item"),
+        Some(filter) => log.log(format!("the inserter now only moves {}", filter)),
+    }
+}
+
+fn cycle_splitter_filter(splitter: Entity, world: &mut World) {
+    let mut splitters = world.write_storage::<FilterSplitter>();
+    let mut log = world.fetch_mut::<Log>();
+    let splitter = splitters.get_mut(splitter).unwrap();
+
+    splitter.filter = next_filter(&splitter.filter, world);
+
+    match &splitter.filter {
+        None => log.log("the filter splitter now splits any item"),
+        Some(filter) => log.log(format!(
+            "the filter splitter now sends {} left and everything else right",
+            filter
+        )),
+    }
+}
+
+/// Cycles a splitter or merger from taking turns to preferring its left side, then its right.
+fn cycle_priority(structure: Entity, world: &mut World) {
+    let mut splitters = world.write_storage::<Splitter>();
+    let mut mergers = world.write_storage::<Merger>();
+    let names = world.read_storage::<Name>();
+    let mut log = world.fetch_mut::<Log>();
+
+    let priority = match (splitters.get_mut(structure), mergers.get_mut(structure)) {
+        (Some(splitter), _) => &mut splitter.priority,
+        (None, Some(merger)) => &mut merger.priority,
+        (None, None) => return,
+    };
+    *priority = match priority {
+        None => Some(Side::Left),
+        Some(Side::Left) => Some(Side::Right),
+        Some(Side::Right) => None,
+    };
+
+    let name = names.get(structure).unwrap();
+    match priority {
+        None => log.log(format!("the {} now takes turns", name)),
+        Some(side) => log.log(format!("the {} now prefers its {} side", name, side.name())),
+    }
+}
+
+/// Opens the signals screen for the structure under the build cursor.
+fn open_signals(world: &mut World) {
+    let (x, y) = world.fetch::<UserInterfaceState>().cursor;
+    let target = structure_at(x, y, world).filter(|structure| {
+        is_controllable(world, *structure)
+            || world
+                .read_storage::<ArithmeticCombinator>()
+                .contains(*structure)
+            || world
+                .read_storage::<DeciderCombinator>()
+                .contains(*structure)
+    });
+
+    match target {
+        None => Log::by_world(world, "that can not use signals"),
+        Some(target) => {
+            let mut ui = world.fetch_mut::<UserInterfaceState>();
+
+            ui.signal_target = Some(target);
+            ui.signal_field = 0;
+            ui.control_mode = ControlMode::Signals;
+            ui.menu_mode = MenuMode::Signals;
+        }
+    }
+}
+
+/// The choice `steps` places after `current` in `choices`, wrapping around. Anything not among
+/// the choices counts as the first one.
+fn step_choice<T: PartialEq + Clone>(choices: &[T], current: &T, steps: i32) -> T {
+    if choices.is_empty() {
+        return current.clone();
+    }
+
+    let index = choices
+        .iter()
+        .position(|choice| choice == current)
+        .unwrap_or(0) as i32;
+    let next = (index + steps).rem_euclid(choices.len() as i32);
+
+    choices[next as usize].clone()
+}
+
+/// Changes the setting selected on the signals screen by `delta`: signals and comparisons move
+/// through their choices and values are counted up or down.
+/// Steps the selected setting of the signals screen by `delta`, then keeps the selection on a
+/// setting that is still there, as turning a condition off leaves fewer of them.
+fn change_signal_setting(delta: i32, world: &mut World) {
+    step_signal_setting(delta, world);
+
+    let count = match world.fetch::<UserInterfaceState>().signal_target {
+        None => return,
+        Some(target) => signal_fields(world, target).len(),
+    };
+    let mut ui = world.fetch_mut::<UserInterfaceState>();
+    ui.signal_field = ui.signal_field.min(count.saturating_sub(1));
+}
+
+fn step_signal_setting(delta: i32, world: &mut World) {
+    let (target, field) = {
+        let ui = world.fetch::<UserInterfaceState>();
+        match ui.signal_target {
+            None => return,
+            Some(target) => (target, ui.signal_field),
+        }
+    };
+    let names = signal_names(world);
+    // Comparisons and operations have four options each, so stepping back one is three forward.
+    let turns = delta.rem_euclid(4);
+    let step_condition = |condition: &mut Condition, field: usize| match field {
+        0 => condition.signal = step_choice(&names, &condition.signal, delta),
+        1 => (0..turns).for_each(|_| condition.comparison = condition.comparison.next()),
+        _ => condition.value = condition.value.saturating_add(delta),
+    };
+
+    if let Some(combinator) = world
+        .write_storage::<ArithmeticCombinator>()
+        .get_mut(target)
+    {
+        match field {
+            0 => combinator.signal = step_choice(&names, &combinator.signal, delta),
+            1 => (0..turns).for_each(|_| combinator.operation = combinator.operation.next()),
+            2 => combinator.value = combinator.value.saturating_add(delta),
+            _ => combinator.output = step_choice(&names, &combinator.output, delta),
+        }
+        return;
+    }
+    if let Some(combinator) = world.write_storage::<DeciderCombinator>().get_mut(target) {
+        match field {
+            3 => combinator.output = step_choice(&names, &combinator.output, delta),
+            _ => step_condition(&mut combinator.condition, field),
+        }
+        return;
+    }
+
+    let mut controlled = world.write_storage::<Controlled>();
+    match (controlled.get_mut(target), field) {
+        (Some(control), 1..) => step_condition(&mut control.condition, field),
+        (control, _) => {
+            // Running always comes before the first signal.
+            let current = control.map_or(0, |control| {
+                names
+                    .iter()
+                    .position(|name| *name == control.condition.signal)
+                    .map_or(0, |index| index as i32 + 1)
+            });
+            let next = (current + delta).rem_euclid(names.len() as i32 + 1);
+
+            if next == 0 {
+                controlled.remove(target);
+                return;
+            }
+            let signal = names[next as usize - 1].clone();
+            match controlled.get_mut(target) {
+                Some(control) => control.condition.signal = signal,
+                None => {
+                    let condition = Condition {
+                        signal,
+                        comparison: Comparison::Less,
+                        value: 50,
+                    };
+                    controlled
+                        .insert(target, Controlled { condition })
+                        .expect("unable to control structure");
+                }
+            }
+        }
+    }
+}
+
+/// Changes the schedule of the cart being edited and has it work out its way again.
+fn edit_schedule(world: &mut World, edit: impl FnOnce(&mut Vec<Stop>, usize, &[String])) {
+    let stations = station_names(world);
+    let mut ui = world.fetch_mut::<UserInterfaceState>();
+    let mut carts = world.write_storage::<Cart>();
+
+    let cart = match ui.cart.and_then(|cart| carts.get_mut(cart)) {
+        None => return,
+        Some(cart) => cart,
+    };
+
+    edit(&mut cart.schedule, ui.selected_option, &stations);
+    cart.reschedule();
+    ui.selected_option = min(ui.selected_option, max(cart.schedule.len(), 1) - 1);
+}
+
+/// How many jobs a worker can be given.
+const MAX_JOBS: usize = 12;
+
+/// Changes the jobs of the worker being edited, and drops the way it was walking.
+fn edit_jobs(world: &mut World, edit: impl FnOnce(&mut Vec<Assignment>, usize, &JobChoices)) {
+    let choices = job_choices(world);
+    let mut ui = world.fetch_mut::<UserInterfaceState>();
+    let mut workers = world.write_storage::<Worker>();
+
+    let worker = match ui.worker.and_then(|worker| workers.get_mut(worker)) {
+        None => return,
+        Some(worker) => worker,
+    };
+
+    edit(&mut worker.jobs, ui.selected_option, &choices);
+    worker.path.clear();
+    ui.selected_option = min(ui.selected_option, max(worker.jobs.len(), 1) - 1);
+}
+
+/// Adds `job` after the selected one, at a middling priority.
+fn add_job(world: &mut World, job: impl FnOnce(&JobChoices) -> Option<Job>) {
+    edit_jobs(world, |jobs, selected, choices| {
+        if jobs.len() >= MAX_JOBS {
+            return;
+        }
+        if let Some(job) = job(choices) {
+            let at = min(selected + 1, jobs.len());
+            let priority = HIGHEST_PRIORITY.div_ceil(2);
+
+            jobs.insert(at, Assignment { job, priority });
+        }
+    });
+}
+
+/// Changes the first setting of the selected job, or the second one if `second`: what it
+/// gathers or crafts and the containers it uses.
+fn change_job_setting(world: &mut World, second: bool, steps: i32) {
+    edit_jobs(world, |jobs, selected, choices| {
+        let job = match jobs.get_mut(selected) {
+            None => return,
+            Some(assignment) => &mut assignment.job,
+        };
+        let containers = &choices.containers;
+
+        match (job, second) {
+            (Job::Gather { item, .. }, false) => *item = step_choice(&choices.items, item, steps),
+            (Job::Gather { to, .. }, true) | (Job::Haul { to, .. }, true) => {
+                *to = step_choice(containers, to, steps)
+            }
+            (Job::Haul { from, .. }, false) => *from = step_choice(containers, from, steps),
+            (Job::Craft { recipe, .. }, false) => {
+                *recipe = step_choice(&choices.recipes, recipe, steps)
+            }
+            (Job::Craft { at, .. }, true) => *at = step_choice(containers, at, steps),
+        }
+    });
+}
+
+/// How many stockpiles the stockpiles screen keeps apart.
+const MAX_STOCKPILES: usize = 8;
+
+/// Starts selecting an area at the cursor, or paints the selected area onto the selected stockpile,
+/// or erases it from the stockpile when `erase`. Only tiles that can be walked on are painted.
+fn mark_stockpile(world: &mut World, erase: bool) {
+    let map = world.fetch::<Vec<TileType>>();
+    let mut ui = world.fetch_mut::<UserInterfaceState>();
+    let mut stockpiles = world.fetch_mut::<Stockpiles>();
+
+    if stockpiles.zones.is_empty() {
+        world
+            .fetch_mut::<Log>()
+            .log("add a stockpile before painting one");
+        return;
+    }
+
+    let (start, cursor) = match ui.selection.take() {
+        Some(start) if ui.is_erasing == erase => (start, ui.cursor),
+        _ => {
+            ui.selection = Some(ui.cursor);
+            ui.is_erasing = erase;
+            return;
+        }
+    };
+    let tiles: Vec<(i32, i32)> = (min(start.0, cursor.0)..=max(start.0, cursor.0))
+        .flat_map(|x| (min(start.1, cursor.1)..=max(start.1, cursor.1)).map(move |y| (x, y)))
+        .filter(|(x, y)| is_tile_walkable(map[xy_to_idx(*x, *y)]))
+        .collect();
+
+    match erase {
+        true => stockpiles.erase(ui.selected_option, &tiles),
+        false => stockpiles.paint(ui.selected_option, &tiles),
+    }
+}
+
+fn cycle_machine_recipe(machine: Entity, world: &mut World) {
+    let mut machines = world.write_storage::<Machine>();
+    let mut log = world.fetch_mut::<Log>();
+    let machine = machines.get_mut(machine).unwrap();
+
+    let research = world.fetch::<Research>();
+    let recipes: Vec<&Recipe> = RECIPES
+        .iter()
+        .filter(|recipe| {
+            recipe.is_made_by(false) && is_unlocked(recipe.result_item_name, &research)
+        })
+        .collect();
+
+    let next = match machine.recipe {
+        None => recipes.first(),
+        Some(recipe) => recipes
+            .iter()
+            .position(|candidate| candidate.result_item_name == recipe)
+            .and_then(|index| recipes.get(index + 1)),
+    };
+    machine.recipe = next.map(|recipe| recipe.result_item_name);
+    machine.progress = None;
+
+    match machine.recipe {
+        None => log.log("the machine is now idle"),
+        Some(recipe) => log.log(format!("the machine now makes {}", recipe)),
+    }
+}
+
+/// Harvests a resource node next to or under the player, preferring ones that are not depleted.
+fn harvest(world: &mut World) {
+    let player = *world.fetch::<Entity>();
+    let (player_x, player_y) = player_position(world);
+
+    let node = {
+        let entities = world.entities();
+        let nodes = world.read_storage::<ResourceNode>();
+        let positions = world.read_storage::<Position>();
+
+        (&entities, &nodes, &positions)
+            .join()
+            .filter(|(_, _, position)| {
+                (position.x - player_x).abs() <= 1 && (position.y - player_y).abs() <= 1
+            })
+            .min_by_key(|(_, node, _)| node.is_depleted())
+            .map(|(entity, _, _)| entity)
+    };
+
+    match node {
+        None => Log::by_world(world, "there is nothing to harvest here"),
+        Some(node) => {
+            world
+                .write_storage::<HarvestQueue>()
+                .insert(player, HarvestQueue { node })
+                .expect("unable to harvest");
+        }
+    }
+}
+
+/// Plants the first seed in the backpack where the player stands.
+fn sow(world: &mut World) {
+    let player = *world.fetch::<Entity>();
+
+    let seed = {
+        let entities = world.entities();
+        let backpack = world.read_storage::<InBackpack>();
+        let names = world.read_storage::<Name>();
+
+        (&entities, &backpack, &names)
+            .join()
+            .find(|(_, pack, name)| pack.owner == player && plant_by_seed(&name.name).is_some())
+            .map(|(entity, _, _)| entity)
+    };
+
+    match seed {
+        None => Log::by_world(world, "you have no seeds to plant"),
+        Some(seed) => {
+            world
+                .write_storage::<PlantQueue>()
+                .insert(player, PlantQueue { seed })
+                .expect("unable to plant");
+        }
+    }
+}
+
+/// Starts researching the selected technology.
+fn research(world: &mut World) {
+    let player = *world.fetch::<Entity>();
+    let selected = world.fetch::<UserInterfaceState>().selected_option;
+
+    if let Some(technology) = TECHNOLOGIES.get(selected) {
+        world
+            .write_storage::<ResearchQueue>()
+            .insert(
+                player,
+                ResearchQueue {
+                    technology: technology.name,
+                },
+            )
+            .expect("unable to research");
+    }
+}
+
+/// Writes the production statistics of every window next to the game as CSV and JSON.
+fn export_statistics(world: &World) {
+    let now = world.fetch::<Clock>().tick;
+    let statistics = world.fetch::<Statistics>();
+
+    let mut directory = env::current_exe().expect("could not find the game directory");
+    directory.pop(); // removes the binary
+
+    let exports = [
+        ("statistics.csv", statistics.to_csv(now)),
+        ("statistics.json", statistics.to_json(now)),
+    ];
+    for (file, contents) in exports {
+        let path = directory.join(file);
+
+        match write(&path, contents) {
+            Ok(_) => Log::by_world(world, format!("statistics saved to {}", path.display())),
+            Err(error) => Log::by_world(world, format!("could not save statistics: {}", error)),
+        }
+    }
+}
+
+/// Lights or douses the burner of the open container.
+fn toggle_burner(world: &mut World) {
+    let container = match world.fetch::<UserInterfaceState>().container {
+        None => return,
+        Some(container) => container,
+    };
+    let names = world.read_storage::<Name>();
+    let mut burners = world.write_storage::<Burner>();
+    let mut log = world.fetch_mut::<Log>();
+
+    if let Some(burner) = burners.get_mut(container) {
+        burner.ignited = !burner.ignited;
+
+        let name = names.get(container).unwrap().to_string().to_lowercase();
+        match burner.ignited {
+            true => log.log(format!("you light the {}", name)),
+            false => log.log(format!("you douse the {}", name)),
+        }
+    }
+}
+
+fn transfer(items: Vec<Entity>, to: Entity, world: &mut World) {
+    let mut transfers = world.write_storage::<TransferQueue>();
+
+    items.into_iter().for_each(|item| {
+        transfers
+            .insert(item, TransferQueue { to })
+            .expect("could not use transfer system");
+    });
+}
+
+#[derive(Copy, Clone, Default)]
+pub enum ControlMode {
+    #[default]
+    Default,
+    Inventory,
+    Craft,
+    Build,
+    Container,
+    Research,
+    Statistics,
+    Planner,
+    Blueprints,
+    Signals,
+    Schedule,
+    Worker,
+    Stockpiles,
+}
+
+impl ControlMode {
+    pub fn handle_input(&self, state: &mut State, ctx: &mut BTerm) {
+        match self {
+            ControlMode::Default => ControlMode::default(state, ctx),
+            ControlMode::Inventory => ControlMode::inventory(state, ctx),
+            ControlMode::Craft => ControlMode::craft(state, ctx),
+            ControlMode::Build => ControlMode::build(state, ctx),
+            ControlMode::Container => ControlMode::container(state, ctx),
+            ControlMode::Research => ControlMode::research(state, ctx),
+            ControlMode::Statistics => ControlMode::statistics(state, ctx),
+            ControlMode::Planner => ControlMode::planner(state, ctx),
+            ControlMode::Blueprints => ControlMode::blueprints(state, ctx),
+            ControlMode::Signals => ControlMode::signals(state, ctx),
+            ControlMode::Schedule => ControlMode::schedule(state, ctx),
+            ControlMode::Worker => ControlMode::worker(state, ctx),
+            ControlMode::Stockpiles => ControlMode::stockpiles(state, ctx),
+        }
+    }
+
+    fn default(state: &mut State, ctx: &mut BTerm) {
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                H | Left => try_move_player(-1, 0, &mut state.world),
+                L | Right => try_move_player(1, 0, &mut state.world),
+                K | Up => try_move_player(0, -1, &mut state.world),
+                J | Down => try_move_player(0, 1, &mut state.world),
+                Y => try_move_player(-1, -1, &mut state.world),
+                U => try_move_player(1, -1, &mut state.world),
+                B => try_move_player(-1, 1, &mut state.world),
+                N => try_move_player(1, 1, &mut state.world),
+                Comma | G => get_item(&mut state.world),
+                Apostrophe | Grave => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+                    ui.log = !ui.log
+                }
+                Tab => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+                    ui.menu = !ui.menu
+                }
+                O => Log::by_world(&state.world, "there are no options yet"),
+                E => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        Inventory => ui.menu_mode = Default,
+                        _ => ui.menu_mode = Inventory,
+                    }
+
+                    match ui.control_mode {
+                        ControlMode::Inventory => ui.control_mode = ControlMode::Default,
+                        _ => ui.control_mode = ControlMode::Inventory,
+                    }
+                }
+                C => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        Craft => ui.menu_mode = Default,
+                        _ => ui.menu_mode = Craft,
+                    };
+
+                    match ui.control_mode {
+                        ControlMode::Craft => ui.control_mode = ControlMode::Default,
+                        _ => ui.control_mode = ControlMode::Craft,
+                    };
+
+                    ui.selected_option = 0;
+                }
+                T => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        MenuMode::Research => ui.menu_mode = Default,
+                        _ => ui.menu_mode = MenuMode::Research,
+                    };
+
+                    match ui.control_mode {
+                        ControlMode::Research => ui.control_mode = ControlMode::Default,
+                        _ => ui.control_mode = ControlMode::Research,
+                    };
+
+                    ui.selected_option = 0;
+                }
+                V => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        MenuMode::Statistics => ui.menu_mode = Default,
+                        _ => ui.menu_mode = MenuMode::Statistics,
+                    };
+
+                    match ui.control_mode {
+                        ControlMode::Statistics => ui.control_mode = ControlMode::Default,
+                        _ => ui.control_mode = ControlMode::Statistics,
+                    };
+
+                    ui.selected_option = 0;
+                }
+                R => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        MenuMode::Planner => ui.menu_mode = Default,
+                        _ => ui.menu_mode = MenuMode::Planner,
+                    };
+
+                    match ui.control_mode {
+                        ControlMode::Planner => ui.control_mode = ControlMode::Default,
+                        _ => ui.control_mode = ControlMode::Planner,
+                    };
+
+                    ui.selected_option = 0;
+                    ui.planner_recipe = 0;
+                }
+                W => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        MenuMode::Blueprints => ui.menu_mode = Default,
+                        _ => ui.menu_mode = MenuMode::Blueprints,
+                    };
+
+                    match ui.control_mode {
+                        ControlMode::Blueprints => ui.control_mode = ControlMode::Default,
+                        _ => ui.control_mode = ControlMode::Blueprints,
+                    };
+
+                    ui.selected_option = 0;
+                }
+                I => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        Interact => ui.menu_mode = Default,
+                        _ => ui.menu_mode = Interact,
+                    }
+                }
+                F => {
+                    let container = adjacent_container(&state.world);
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match container {
+                        None => Log::by_world(&state.world, "there is nothing to open here"),
+                        Some(_) => {
+                            ui.container = container;
+                            ui.pane = Pane::Backpack;
+                            ui.selected_option = 0;
+                            ui.menu_mode = MenuMode::Container;
+                            ui.control_mode = ControlMode::Container;
+                        }
+                    }
+                }
+                M => harvest(&mut state.world),
+                S => sow(&mut state.world),
+                P => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        MenuMode::Power => ui.menu_mode = Default,
+                        _ => ui.menu_mode = MenuMode::Power,
+                    }
+                }
+                A => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    match ui.menu_mode {
+                        MenuMode::Bottlenecks => ui.menu_mode = Default,
+                        _ => ui.menu_mode = MenuMode::Bottlenecks,
+                    }
+                }
+                D => {
+                    let cursor = player_position(&state.world);
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.cursor = cursor;
+                    ui.selected_option = 0;
+                    ui.menu_mode = Build;
+                    ui.control_mode = ControlMode::Build;
+                }
+                Z => {
+                    let cursor = player_position(&state.world);
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.cursor = cursor;
+                    ui.selected_option = 0;
+                    ui.stockpile_kind = 0;
+                    ui.menu_mode = MenuMode::Stockpiles;
+                    ui.control_mode = ControlMode::Stockpiles;
+                }
+                Q | Escape => ctx.quit(),
+                _ => {}
+            },
+        }
+    }
+
+    fn inventory(state: &mut State, ctx: &mut BTerm) {
+        if let Some(Escape | Q) = ctx.key {
+            let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+            ui.control_mode = ControlMode::Default;
+            ui.menu_mode = Default
+        }
+    }
+
+    fn craft(state: &mut State, ctx: &mut BTerm) {
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.control_mode = ControlMode::Default;
+                    ui.menu_mode = Default
+                }
+                J | Down => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option + 2 > RECIPES.len() {
+                        return;
+                    }
+
+                    ui.selected_option += 1;
+                }
+                K | Up => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option == 0 {
+                        return;
+                    }
+
+                    ui.selected_option -= 1;
+                }
+                Return | Space => craft(state),
+                _ => {}
+            },
+        }
+    }
+
+    fn research(state: &mut State, ctx: &mut BTerm) {
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q | T => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.control_mode = ControlMode::Default;
+                    ui.menu_mode = Default
+                }
+                J | Down => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option + 2 > TECHNOLOGIES.len() {
+                        return;
+                    }
+
+                    ui.selected_option += 1;
+                }
+                K | Up => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option == 0 {
+                        return;
+                    }
+
+                    ui.selected_option -= 1;
+                }
+                Return | Space => research(&mut state.world),
+                _ => {}
+            },
+        }
+    }
+
+    fn statistics(state: &mut State, ctx: &mut BTerm) {
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q | V => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.control_mode = ControlMode::Default;
+                    ui.menu_mode = Default
+                }
+                J | Down => {
+                    let items = {
+                        let now = state.world.fetch::<Clock>().tick;
+                        let window = state.world.fetch::<UserInterfaceState>().statistics_window;
+
+                        state
+                            .world
+                            .fetch::<Statistics>()
+                            .items(now, &WINDOWS[window])
+                            .len()
+                    };
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option + 2 > items.min(25) {
+                        return;
+                    }
+
+                    ui.selected_option += 1;
+                }
+                K | Up => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option == 0 {
+                        return;
+                    }
 
-fn try_move_player(delta_x: i32, delta_y: i32, world: &mut World) {
-    let blockers = world.read_storage::<BlocksMovement>();
-    let mut positions = world.write_storage::<Position>();
-    let mut players = world.write_storage::<Player>();
-    let map = world.fetch::<Vec<TileType>>();
+                    ui.selected_option -= 1;
+                }
+                Tab => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-    let (player_x, player_y) = {
-        let player = (&players, &positions).join().nth(0).unwrap();
-        (player.1.x, player.1.y)
-    };
+                    ui.statistics_window = (ui.statistics_window + 1) % WINDOWS.len();
+                    ui.selected_option = 0;
+                }
+                W => export_statistics(&state.world),
+                _ => {}
+            },
+        }
+    }
 
-    let destination_idx = xy_to_idx(player_x + delta_x, player_y + delta_y);
+    fn planner(state: &mut State, ctx: &mut BTerm) {
+        let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-    let blocker = (&positions, &blockers).join().find(|(position, _)| {
-        (player_x + delta_x) == position.x && (player_y + delta_y) == position.y
-    });
-    let is_blocked = blocker.is_some();
-    if is_tile_walkable(map[destination_idx]) && !is_blocked {
-        let player = (&mut players, &mut positions).join().nth(0).unwrap();
-        player.1.x = min(79, max(0, player_x + delta_x));
-        player.1.y = min(49, max(0, player_y + delta_y));
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q | R => {
+                    ui.control_mode = ControlMode::Default;
+                    ui.menu_mode = Default
+                }
+                J | Down => {
+                    if ui.selected_option + 2 > craftable_items().len() {
+                        return;
+                    }
+
+                    ui.selected_option += 1;
+                    ui.planner_recipe = 0;
+                }
+                K | Up => {
+                    if ui.selected_option == 0 {
+                        return;
+                    }
+
+                    ui.selected_option -= 1;
+                    ui.planner_recipe = 0;
+                }
+                Equals | Plus | NumpadAdd => ui.planner_rate += 1,
+                Minus | NumpadSubtract => ui.planner_rate = max(ui.planner_rate, 2) - 1,
+                Tab => {
+                    let recipes = craftable_items()
+                        .get(ui.selected_option)
+                        .map_or(1, |item| recipes_for(item).len());
+
+                    ui.planner_recipe = (ui.planner_recipe + 1) % recipes.max(1);
+                }
+                _ => {}
+            },
+        }
     }
-}
 
-#[derive(Copy, Clone, Default)]
-pub enum ControlMode {
-    #[default]
-    Default,
-    Inventory,
-    Craft,
-}
+    fn blueprints(state: &mut State, ctx: &mut BTerm) {
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q | W => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-impl ControlMode {
-    pub fn handle_input(&self, state: &mut State, ctx: &mut BTerm) {
-        match self {
-            ControlMode::Default => ControlMode::default(state, ctx),
-            ControlMode::Inventory => ControlMode::inventory(state, ctx),
-            ControlMode::Craft => ControlMode::craft(state, ctx),
+                    ui.control_mode = ControlMode::Default;
+                    ui.menu_mode = Default
+                }
+                J | Down => {
+                    let count = state.world.fetch::<BlueprintLibrary>().blueprints.len();
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option + 2 > count {
+                        return;
+                    }
+
+                    ui.selected_option += 1;
+                }
+                K | Up => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option == 0 {
+                        return;
+                    }
+
+                    ui.selected_option -= 1;
+                }
+                Return | Space => {
+                    let selected = state.world.fetch::<UserInterfaceState>().selected_option;
+                    let name = state
+                        .world
+                        .fetch::<BlueprintLibrary>()
+                        .blueprints
+                        .get(selected)
+                        .map(|blueprint| blueprint.name.clone());
+
+                    if let Some(name) = name {
+                        state.world.fetch_mut::<UserInterfaceState>().blueprint = Some(selected);
+                        Log::by_world(&state.world, format!("build mode now pastes {}", name));
+                    }
+                }
+                D => {
+                    let mut library = state.world.fetch_mut::<BlueprintLibrary>();
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+                    let selected = ui.selected_option;
+
+                    if selected >= library.blueprints.len() {
+                        return;
+                    }
+
+                    library.blueprints.remove(selected);
+                    ui.blueprint = match ui.blueprint {
+                        Some(index) if index == selected => None,
+                        Some(index) if index > selected => Some(index - 1),
+                        other => other,
+                    };
+                    ui.selected_option = min(selected, max(library.blueprints.len(), 1) - 1);
+                }
+                X => export_blueprint(&state.world),
+                I => import_blueprints(&state.world),
+                _ => {}
+            },
         }
     }
 
-    fn default(state: &mut State, ctx: &mut BTerm) {
+    fn build(state: &mut State, ctx: &mut BTerm) {
         match ctx.key {
             None => {}
             Some(key) => match key {
-                H | Left => try_move_player(-1, 0, &mut state.world),
-                L | Right => try_move_player(1, 0, &mut state.world),
-                K | Up => try_move_player(0, -1, &mut state.world),
-                J | Down => try_move_player(0, 1, &mut state.world),
-                Y => try_move_player(-1, -1, &mut state.world),
-                U => try_move_player(1, -1, &mut state.world),
-                B => try_move_player(-1, 1, &mut state.world),
-                N => try_move_player(1, 1, &mut state.world),
-                Comma | G => get_item(&mut state.world),
-                Apostrophe | Grave => {
+                Escape | Q => {
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
-                    ui.log = !ui.log
+
+                    ui.control_mode = ControlMode::Default;
+                    ui.menu_mode = Default;
+                    ui.selection = None;
                 }
+                H | Left => move_cursor(-1, 0, &mut state.world),
+                L | Right => move_cursor(1, 0, &mut state.world),
+                K | Up => move_cursor(0, -1, &mut state.world),
+                J | Down => move_cursor(0, 1, &mut state.world),
+                Y => move_cursor(-1, -1, &mut state.world),
+                U => move_cursor(1, -1, &mut state.world),
+                B => move_cursor(-1, 1, &mut state.world),
+                N => move_cursor(1, 1, &mut state.world),
                 Tab => {
+                    let count = structure_stacks(&state.world).len();
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
-                    ui.menu = !ui.menu
+
+                    ui.selected_option = (ui.selected_option + 1) % max(count, 1);
                 }
-                O => Log::by_world(&state.world, "there are no options yet"),
-                E => {
+                Return | Space => place_structure(&mut state.world),
+                R => rotate(&mut state.world),
+                F => configure(&mut state.world),
+                G => open_signals(&mut state.world),
+                D => deconstruct(&mut state.world),
+                Z => replay(!ctx.shift, &mut state.world),
+                C => copy_area(&mut state.world),
+                P => paste_blueprint(&mut state.world),
+                X => remove_ghost(&mut state.world),
+                _ => {}
+            },
+        }
+    }
+
+    fn signals(state: &mut State, ctx: &mut BTerm) {
+        let step = match ctx.shift {
+            true => 10,
+            false => 1,
+        };
+
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q | G => {
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-                    match ui.menu_mode {
-                        Inventory => ui.menu_mode = Default,
-                        _ => ui.menu_mode = Inventory,
+                    ui.control_mode = ControlMode::Build;
+                    ui.menu_mode = Build;
+                    ui.signal_target = None;
+                }
+                J | Down => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+                    let count = ui
+                        .signal_target
+                        .map_or(0, |target| signal_fields(&state.world, target).len());
+
+                    if ui.signal_field + 1 < count {
+                        ui.signal_field += 1;
                     }
+                }
+                K | Up => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-                    match ui.control_mode {
-                        ControlMode::Inventory => ui.control_mode = ControlMode::Default,
-                        _ => ui.control_mode = ControlMode::Inventory,
+                    if ui.signal_field > 0 {
+                        ui.signal_field -= 1;
                     }
                 }
-                C => {
+                H | Left => change_signal_setting(-step, &mut state.world),
+                L | Right => change_signal_setting(step, &mut state.world),
+                _ => {}
+            },
+        }
+    }
+
+    fn schedule(state: &mut State, ctx: &mut BTerm) {
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q => {
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-                    match ui.menu_mode {
-                        Craft => ui.menu_mode = Default,
-                        _ => ui.menu_mode = Craft,
+                    ui.control_mode = ControlMode::Build;
+                    ui.menu_mode = Build;
+                    ui.cart = None;
+                    ui.selected_option = 0;
+                }
+                J | Down => {
+                    let carts = state.world.read_storage::<Cart>();
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+                    let count = ui
+                        .cart
+                        .and_then(|cart| carts.get(cart))
+                        .map_or(0, |cart| cart.schedule.len());
+
+                    if ui.selected_option + 1 < count {
+                        ui.selected_option += 1;
+                    }
+                }
+                K | Up => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option > 0 {
+                        ui.selected_option -= 1;
+                    }
+                }
+                A if station_names(&state.world).is_empty() => {
+                    Log::by_world(&state.world, "there are no stations yet")
+                }
+                // New stops alternate between loading and unloading.
+                A => edit_schedule(&mut state.world, |schedule, selected, stations| {
+                    let until = match schedule.len() % 2 {
+                        0 => Until::Full,
+                        _ => Until::Empty,
+                    };
+                    let at = min(selected + 1, schedule.len());
+                    let station = match schedule.get(selected) {
+                        None => stations[0].clone(),
+                        Some(stop) => step_choice(stations, &stop.station, 1),
                     };
 
-                    match ui.control_mode {
-                        ControlMode::Craft => ui.control_mode = ControlMode::Default,
-                        _ => ui.control_mode = ControlMode::Craft,
+                    schedule.insert(at, Stop { station, until });
+                }),
+                D => edit_schedule(&mut state.world, |schedule, selected, _| {
+                    if selected < schedule.len() {
+                        schedule.remove(selected);
+                    }
+                }),
+                H | Left | L | Right => {
+                    let steps = match key {
+                        H | Left => -1,
+                        _ => 1,
                     };
 
+                    edit_schedule(&mut state.world, |schedule, selected, stations| {
+                        if let Some(stop) = schedule.get_mut(selected) {
+                            stop.station = step_choice(stations, &stop.station, steps);
+                        }
+                    })
+                }
+                F | Space => edit_schedule(&mut state.world, |schedule, selected, _| {
+                    if let Some(stop) = schedule.get_mut(selected) {
+                        stop.until = stop.until.other();
+                    }
+                }),
+                _ => {}
+            },
+        }
+    }
+
+    fn worker(state: &mut State, ctx: &mut BTerm) {
+        match ctx.key {
+            None => {}
+            Some(key) => match key {
+                Escape | Q => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.control_mode = ControlMode::Build;
+                    ui.menu_mode = Build;
+                    ui.worker = None;
                     ui.selected_option = 0;
                 }
-                I => {
+                J | Down => {
+                    let workers = state.world.read_storage::<Worker>();
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+                    let count = ui
+                        .worker
+                        .and_then(|worker| workers.get(worker))
+                        .map_or(0, |worker| worker.jobs.len());
 
-                    match ui.menu_mode {
-                        Interact => ui.menu_mode = Default,
-                        _ => ui.menu_mode = Interact,
+                    if ui.selected_option + 1 < count {
+                        ui.selected_option += 1;
                     }
                 }
-                Q | Escape => ctx.quit(),
+                K | Up => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option > 0 {
+                        ui.selected_option -= 1;
+                    }
+                }
+                G => add_job(&mut state.world, |choices| {
+                    let item = choices.items.first()?.clone();
+                    Some(Job::Gather { item, to: None })
+                }),
+                T => add_job(&mut state.world, |_| {
+                    Some(Job::Haul {
+                        from: None,
+                        to: None,
+                    })
+                }),
+                C => add_job(&mut state.world, |choices| {
+                    let recipe = *choices.recipes.first()?;
+                    Some(Job::Craft { recipe, at: None })
+                }),
+                D => edit_jobs(&mut state.world, |jobs, selected, _| {
+                    if selected < jobs.len() {
+                        jobs.remove(selected);
+                    }
+                }),
+                Minus | Equals => edit_jobs(&mut state.world, |jobs, selected, _| {
+                    if let Some(assignment) = jobs.get_mut(selected) {
+                        assignment.priority = match key {
+                            Minus => max(assignment.priority - 1, 1),
+                            _ => min(assignment.priority + 1, HIGHEST_PRIORITY),
+                        };
+                    }
+                }),
+                H | Left => change_job_setting(&mut state.world, ctx.shift, -1),
+                L | Right => change_job_setting(&mut state.world, ctx.shift, 1),
                 _ => {}
             },
         }
     }
 
-    fn inventory(state: &mut State, ctx: &mut BTerm) {
+    fn stockpiles(state: &mut State, ctx: &mut BTerm) {
         match ctx.key {
             None => {}
             Some(key) => match key {
@@ -133,14 +1913,104 @@ impl ControlMode {
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
                     ui.control_mode = ControlMode::Default;
-                    ui.menu_mode = Default
+                    ui.menu_mode = Default;
+                    ui.selection = None;
+                    ui.selected_option = 0;
+                }
+                H | Left => move_cursor(-1, 0, &mut state.world),
+                L | Right => move_cursor(1, 0, &mut state.world),
+                K | Up => move_cursor(0, -1, &mut state.world),
+                J | Down => move_cursor(0, 1, &mut state.world),
+                Y => move_cursor(-1, -1, &mut state.world),
+                U => move_cursor(1, -1, &mut state.world),
+                B => move_cursor(-1, 1, &mut state.world),
+                N => move_cursor(1, 1, &mut state.world),
+                Tab => {
+                    let count = state.world.fetch::<Stockpiles>().zones.len();
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.selected_option = (ui.selected_option + 1) % max(count, 1);
+                }
+                A => {
+                    let mut stockpiles = state.world.fetch_mut::<Stockpiles>();
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if stockpiles.zones.len() >= MAX_STOCKPILES {
+                        return;
+                    }
+                    ui.selected_option = stockpiles.add();
+                }
+                X => {
+                    let mut stockpiles = state.world.fetch_mut::<Stockpiles>();
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    if ui.selected_option < stockpiles.zones.len() {
+                        stockpiles.zones.remove(ui.selected_option);
+                    }
+                    ui.selected_option =
+                        min(ui.selected_option, max(stockpiles.zones.len(), 1) - 1);
+                    ui.selection = None;
+                }
+                S => mark_stockpile(&mut state.world, false),
+                E => mark_stockpile(&mut state.world, true),
+                Minus | Equals => {
+                    let mut stockpiles = state.world.fetch_mut::<Stockpiles>();
+                    let ui = state.world.fetch::<UserInterfaceState>();
+
+                    if let Some(zone) = stockpiles.zones.get_mut(ui.selected_option) {
+                        zone.priority = match key {
+                            Minus => max(zone.priority - 1, 1),
+                            _ => min(zone.priority + 1, HIGHEST_PRIORITY),
+                        };
+                    }
+                }
+                F => {
+                    let count = item_kinds(&state.world).len();
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.stockpile_kind = match ctx.shift {
+                        true => (ui.stockpile_kind + max(count, 1) - 1) % max(count, 1),
+                        false => (ui.stockpile_kind + 1) % max(count, 1),
+                    };
+                }
+                Return | Space => {
+                    let kinds = item_kinds(&state.world);
+                    let mut stockpiles = state.world.fetch_mut::<Stockpiles>();
+                    let ui = state.world.fetch::<UserInterfaceState>();
+
+                    let zone = stockpiles.zones.get_mut(ui.selected_option);
+                    if let (Some(zone), Some(kind)) = (zone, kinds.get(ui.stockpile_kind)) {
+                        match zone.filter.contains(kind) {
+                            true => zone.filter.retain(|taken| taken != kind),
+                            false => zone.filter.push(kind.clone()),
+                        }
+                    }
+                }
+                C => {
+                    let mut stockpiles = state.world.fetch_mut::<Stockpiles>();
+                    let ui = state.world.fetch::<UserInterfaceState>();
+
+                    if let Some(zone) = stockpiles.zones.get_mut(ui.selected_option) {
+                        zone.filter.clear();
+                    }
                 }
                 _ => {}
             },
         }
     }
 
-    fn craft(state: &mut State, ctx: &mut BTerm) {
+    fn container(state: &mut State, ctx: &mut BTerm) {
+        let (stacks, receiver) = focused_stacks(&state.world);
+        {
+            let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+            ui.selected_option = min(ui.selected_option, max(stacks.len(), 1) - 1);
+        }
+
+        let selected = {
+            let ui = state.world.fetch::<UserInterfaceState>();
+            stacks.values().nth(ui.selected_option).cloned()
+        };
+
         match ctx.key {
             None => {}
             Some(key) => match key {
@@ -148,27 +2018,55 @@ impl ControlMode {
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
                     ui.control_mode = ControlMode::Default;
-                    ui.menu_mode = Default
+                    ui.menu_mode = Default;
+                    ui.container = None;
+                }
+                H | Left => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.pane = Pane::Backpack;
+                    ui.selected_option = 0;
+                }
+                L | Right => {
+                    let mut ui = state.world.fetch_mut::<UserInterfaceState>();
+
+                    ui.pane = Pane::Container;
+                    ui.selected_option = 0;
                 }
                 J | Down => {
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-                    if ui.selected_option + 2 > RECIPES.len() {
-                        return;
+                    if ui.selected_option + 1 < stacks.len() {
+                        ui.selected_option += 1;
                     }
-
-                    ui.selected_option += 1;
                 }
                 K | Up => {
                     let mut ui = state.world.fetch_mut::<UserInterfaceState>();
 
-                    if ui.selected_option == 0 {
-                        return;
+                    if ui.selected_option > 0 {
+                        ui.selected_option -= 1;
+                    }
+                }
+                Return | Space => {
+                    if let (Some(stack), Some(to)) = (selected, receiver) {
+                        transfer(vec![stack[0]], to, &mut state.world);
+                    }
+                }
+                S => {
+                    if let (Some(stack), Some(to)) = (selected, receiver) {
+                        transfer(stack, to, &mut state.world);
+                    }
+                }
+                A => {
+                    if let Some(to) = receiver {
+                        transfer(
+                            stacks.into_values().flatten().collect(),
+                            to,
+                            &mut state.world,
+                        );
                     }
-
-                    ui.selected_option -= 1;
                 }
-                Return | Space => craft(state, ctx),
+                F => toggle_burner(&mut state.world),
                 _ => {}
             },
         }
@@ -1,13 +1,34 @@
-use bracket_lib::color::{BURLYWOOD, GREEN, GREY, RED};
-use specs::world::{EntityResBuilder, LazyBuilder};
-use specs::{Builder, Entity, EntityBuilder, ReadExpect, WorldExt, WriteStorage};
+use bracket_lib::color::{
+    BURLYWOOD, CHOCOLATE, DARKCYAN, DARKGRAY, DIMGRAY, GOLD, GREEN, GREY, LIGHTGRAY, LIGHTGREEN,
+    LIGHTSTEELBLUE, LIGHTYELLOW, ORANGE, PINK, RED, SANDYBROWN, SILVER, SLATEGRAY, STEELBLUE,
+};
+use specs::world::LazyBuilder;
+use specs::{Builder, Entity, WorldExt};
 
-use crate::components::items;
-use crate::components::items::{name_by_tier, BlocksMovement, Flint, Item, Rose, Three};
+use crate::components::blueprints::Ghost;
+use crate::components::construction::ConstructionSite;
+use crate::components::farming::find_plant;
+use crate::components::fluids::{FluidBox, Pipe, Pump, Tank};
+use crate::components::items::{
+    name_by_tier, stow_later, BlocksMovement, Capacity, Flint, Item, Pickaxe, ResourceNode, Rose,
+    Three, Tool, Weight,
+};
+use crate::components::lighting::Light;
+use crate::components::power::{Burner, Fuel, Generator, Heat, PowerConsumer, PowerPole, Solar};
+use crate::components::rails::{Cart, Rail, RailSignal, Station};
+use crate::components::research::Lab;
+use crate::components::signals::{
+    ArithmeticCombinator, Comparison, Condition, DeciderCombinator, Operation, Wire,
+};
+use crate::components::structures::{
+    Belt, Chest, Container, Direction, Facing, FilterSplitter, Furnace, Inserter, Machine, Merger,
+    Side, Splitter, Structure, Tunnel,
+};
+use crate::components::workers::Worker;
 use crate::map::{xy_to_idx, TileType, HEIGHT, MAP_COUNT, WIDTH};
 use crate::{
-    to_cp437, Axe, Bush, FirePit, InBackpack, Name, Player, Position, RandomNumberGenerator,
-    Renderable, Tier, WoodenStick, World, BLACK, RGB, YELLOW,
+    to_cp437, Axe, Bush, FirePit, FontCharType, InBackpack, Name, Player, Position,
+    RandomNumberGenerator, Renderable, Tier, WoodenStick, World, BLACK, RGB, YELLOW,
 };
 
 pub fn player(world: &mut World, x: i32, y: i32) -> Entity {
@@ -21,17 +42,72 @@ pub fn player(world: &mut World, x: i32, y: i32) -> Entity {
         })
         .with(Player {})
         .with(Name::new("Player"))
+        .with(Light { radius: 2 })
+        .with(Capacity {
+            max_weight: 20.,
+            max_volume: 40.,
+        })
+        .build()
+}
+
+/// Marks where a structure is planned, drawn with the glyph the structure will have, and serves as
+/// the construction site it is built on.
+pub fn ghost(
+    builder: impl Builder,
+    ghost: Ghost,
+    site: ConstructionSite,
+    x: i32,
+    y: i32,
+    glyph: FontCharType,
+) -> Entity {
+    builder
+        .with(Position { x, y })
+        .with(Renderable::new(glyph, RGB::named(DARKCYAN)))
+        .with(Name::new(&format!("{} Ghost", ghost.structure)))
+        .with(ghost)
+        .with(site)
         .build()
 }
 
 pub fn generate_items(world: &mut World) {
+    generate_deposits(world, 3, copper_deposit);
+    generate_deposits(world, 2, iron_deposit);
     generate_item(world, 8, three);
     generate_item(world, 32, bush);
     generate_item(world, 64, wooden_stick);
+    generate_item(world, 128, brushwood);
+    generate_item(world, 256, log);
     generate_item(world, 64, rose);
     generate_item(world, 128, flint);
 }
 
+/// Scatters `patches` small clusters of deposits over the floor.
+fn generate_deposits(
+    world: &mut World,
+    patches: usize,
+    generator: fn(&mut World, x: i32, y: i32) -> Entity,
+) {
+    let mut rng = RandomNumberGenerator::new();
+    let (center_x, center_y) = ((WIDTH / 2) as i32, (HEIGHT / 2) as i32);
+
+    (0..patches).for_each(|_| {
+        let patch_x = rng.range(3, WIDTH as i32 - 3);
+        let patch_y = rng.range(3, HEIGHT as i32 - 3);
+
+        for x in (patch_x - 1)..=(patch_x + 1) {
+            for y in (patch_y - 1)..=(patch_y + 1) {
+                let is_near_spawn = (x - center_x).abs() <= 2 && (y - center_y).abs() <= 2;
+                let is_on_floor =
+                    world.fetch::<Vec<TileType>>()[xy_to_idx(x, y)] == TileType::Floor;
+
+                if is_on_floor && !is_near_spawn && rng.roll_dice(1, 3) > 1 {
+                    generator(world, x, y);
+                }
+            }
+        }
+    });
+}
+
 fn generate_item(
     world: &mut World,
     chances: usize,
@@ -39,13 +115,14 @@ fn generate_item(
 ) {
     let mut rng = RandomNumberGenerator::new();
 
-    (0..(MAP_COUNT / chances)).into_iter().for_each(|_| {
+    (0..(MAP_COUNT / chances)).for_each(|_| {
         let x = rng.roll_dice(1, (WIDTH - 2) as i32);
         let y = rng.roll_dice(1, (HEIGHT - 2) as i32);
         let idx = xy_to_idx(x, y);
 
         let is_at_center = idx == xy_to_idx((WIDTH / 2) as i32, (HEIGHT / 2) as i32);
-        if !is_at_center {
+        let is_on_floor = world.fetch::<Vec<TileType>>()[idx] == TileType::Floor;
+        if !is_at_center && is_on_floor {
             generator(world, x, y);
         }
     });
@@ -58,6 +135,53 @@ fn three(world: &mut World, x: i32, y: i32) -> Entity {
         .with(Renderable::new(to_cp437('♣'), RGB::named(GREEN)))
         .with(Name::new("Three"))
         .with(Three {})
+        .with(ResourceNode {
+            yields: "Wooden Stick",
+            remaining: 8,
+            max_yield: 8,
+            tool: Some(Tool::Axe),
+            tier: 0,
+            regrow_interval: Some(900),
+            seed: None,
+        })
+        .with(BlocksMovement {})
+        .build()
+}
+
+fn copper_deposit(world: &mut World, x: i32, y: i32) -> Entity {
+    world
+        .create_entity()
+        .with(Position { x, y })
+        .with(Renderable::new(to_cp437('▓'), RGB::named(CHOCOLATE)))
+        .with(Name::new("Copper Deposit"))
+        .with(ResourceNode {
+            yields: "Copper Ore",
+            remaining: 25,
+            max_yield: 25,
+            tool: Some(Tool::Pickaxe),
+            tier: 0,
+            regrow_interval: None,
+            seed: None,
+        })
+        .with(BlocksMovement {})
+        .build()
+}
+
+fn iron_deposit(world: &mut World, x: i32, y: i32) -> Entity {
+    world
+        .create_entity()
+        .with(Position { x, y })
+        .with(Renderable::new(to_cp437('▓'), RGB::named(LIGHTSTEELBLUE)))
+        .with(Name::new("Iron Deposit"))
+        .with(ResourceNode {
+            yields: "Iron Ore",
+            remaining: 25,
+            max_yield: 25,
+            tool: Some(Tool::Pickaxe),
+            tier: 1,
+            regrow_interval: None,
+            seed: None,
+        })
         .with(BlocksMovement {})
         .build()
 }
@@ -69,10 +193,13 @@ fn flint(world: &mut World, x: i32, y: i32) -> Entity {
         .with(Renderable::new(to_cp437('°'), RGB::named(GREY)))
         .with(Item {
             can_be_picked: true,
-            can_be_crafted: false,
         })
         .with(Flint {})
         .with(Name::new("Flint"))
+        .with(Weight {
+            weight: 0.5,
+            volume: 1.,
+        })
         .build()
 }
 
@@ -83,6 +210,7 @@ fn bush(world: &mut World, x: i32, y: i32) -> Entity {
         .with(Renderable::new(to_cp437('%'), RGB::from_f32(0., 0.75, 0.)))
         .with(Item::default())
         .with(Bush {})
+        .with(find_plant("Bush").unwrap().node())
         .with(Name::new("Bush"))
         .build()
 }
@@ -94,10 +222,49 @@ fn wooden_stick(world: &mut World, x: i32, y: i32) -> Entity {
         .with(Renderable::new(to_cp437('\\'), RGB::named(BURLYWOOD)))
         .with(Item {
             can_be_picked: true,
-            can_be_crafted: false,
         })
         .with(WoodenStick {})
+        .with(Fuel { burn_ticks: 600 })
         .with(Name::new("Wooden Stick"))
+        .with(Weight {
+            weight: 0.3,
+            volume: 2.,
+        })
+        .build()
+}
+
+/// Dry twigs shed by bushes, the quickest burning fuel.
+fn brushwood(world: &mut World, x: i32, y: i32) -> Entity {
+    world
+        .create_entity()
+        .with(Position { x, y })
+        .with(Renderable::new(to_cp437('"'), RGB::named(BURLYWOOD)))
+        .with(Item {
+            can_be_picked: true,
+        })
+        .with(Fuel { burn_ticks: 300 })
+        .with(Name::new("Brushwood"))
+        .with(Weight {
+            weight: 0.2,
+            volume: 2.,
+        })
+        .build()
+}
+
+fn log(world: &mut World, x: i32, y: i32) -> Entity {
+    world
+        .create_entity()
+        .with(Position { x, y })
+        .with(Renderable::new(to_cp437('='), RGB::named(BURLYWOOD)))
+        .with(Item {
+            can_be_picked: true,
+        })
+        .with(Fuel { burn_ticks: 1800 })
+        .with(Name::new("Log"))
+        .with(Weight {
+            weight: 3.,
+            volume: 6.,
+        })
         .build()
 }
 
@@ -108,14 +275,98 @@ fn rose(world: &mut World, x: i32, y: i32) -> Entity {
         .with(Renderable::new(to_cp437('±'), RGB::named(RED)))
         .with(Item::default())
         .with(Rose {})
+        .with(find_plant("Rose").unwrap().node())
         .with(Name::new("Rose"))
         .build()
 }
 
+/// Spawns the grown plant called `name` at `x`, `y`, once a crop of it finishes growing.
+pub fn grown_plant(world: &mut World, name: &str, x: i32, y: i32) -> Option<Entity> {
+    match name {
+        "Bush" => Some(bush(world, x, y)),
+        "Rose" => Some(rose(world, x, y)),
+        _ => None,
+    }
+}
+
+/// Spawns an item into the inventory of its owner.
+type Spawner = fn(LazyBuilder, Entity);
+
+/// Every item with a `Structure`, by name, and what builds it. These are the only things a ghost
+/// can plan.
+const STRUCTURES: &[(&str, Spawner)] = &[
+    ("Fire Pit", fire_pit),
+    ("Chest", chest),
+    ("Belt", belt),
+    ("Inserter", inserter),
+    ("Splitter", splitter),
+    ("Filter Splitter", filter_splitter),
+    ("Merger", merger),
+    ("Underground Belt", underground_belt),
+    ("Pipe", pipe),
+    ("Pipe Tunnel", pipe_tunnel),
+    ("Pump", pump),
+    ("Tank", tank),
+    ("Assembler", assembler),
+    ("Burner Generator", burner_generator),
+    ("Power Pole", power_pole),
+    ("Furnace", furnace),
+    ("Torch", torch),
+    ("Lamp", lamp),
+    ("Solar Panel", solar_panel),
+    ("Lab", lab),
+    ("Wire", wire),
+    ("Arithmetic Combinator", arithmetic_combinator),
+    ("Decider Combinator", decider_combinator),
+    ("Rail", rail),
+    ("Rail Station", rail_station),
+    ("Rail Signal", rail_signal),
+    ("Cart", cart),
+    ("Worker", worker),
+];
+
+pub fn is_structure_name(name: &str) -> bool {
+    STRUCTURES.iter().any(|(structure, _)| *structure == name)
+}
+
+/// Spawns the result of a finished recipe into the inventory of `owner`, or at its feet when it
+/// does not fit.
+pub fn crafted(item_name: &str, builder: LazyBuilder, owner: Entity) {
+    let (item, lazy) = (builder.entity, builder.lazy);
+    let structure = STRUCTURES
+        .iter()
+        .find(|(structure, _)| *structure == item_name);
+    if let Some((_, build)) = structure {
+        build(builder, owner);
+        return stow_later(lazy, item, owner);
+    }
+
+    match item_name {
+        "Flint Axe" => axe(builder, owner, 0),
+        "Copper Axe" => axe(builder, owner, 1),
+        "Iron Axe" => axe(builder, owner, 2),
+        "Flint Pickaxe" => pickaxe(builder, owner, 0),
+        "Copper Pickaxe" => pickaxe(builder, owner, 1),
+        "Iron Pickaxe" => pickaxe(builder, owner, 2),
+        "Mortar" => mortar(builder, owner),
+        "Charcoal" => charcoal(builder, owner),
+        "Fire-Hardened Spear" => spear(builder, owner),
+        "Copper Ore" | "Iron Ore" => ore(builder, owner, item_name),
+        "Copper Ingot" | "Iron Ingot" => ingot(builder, owner, item_name),
+        "Sharp Flake" => sharp_flake(builder, owner),
+        "Wooden Stick" => stick(builder, owner),
+        "Rose Petal" => rose_petal(builder, owner),
+        "Bush Seed" | "Rose Seed" => seed(builder, owner, item_name),
+        "Slag" => slag(builder, owner),
+        "Ash" => ash(builder, owner),
+        _ => println!("tried to craft {}", item_name),
+    }
+    stow_later(lazy, item, owner);
+}
+
 fn craftable() -> Item {
     Item {
         can_be_picked: true,
-        can_be_crafted: true,
     }
 }
 
@@ -125,10 +376,28 @@ pub fn axe(builder: LazyBuilder, owner: Entity, level: u8) {
         .with(Renderable::new(to_cp437('P'), RGB::named(GREY)))
         .with(Axe {})
         .with(Name::new(format!("{} Axe", name_by_tier(level)).as_ref()))
-        .with(Tier {
-            alternative_name: Some(format!("{} Axe", name_by_tier(level))),
-            level,
+        .with(Weight {
+            weight: 1.5,
+            volume: 4.,
+        })
+        .with(Tier { level })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn pickaxe(builder: LazyBuilder, owner: Entity, level: u8) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('T'), RGB::named(GREY)))
+        .with(Pickaxe {})
+        .with(Name::new(
+            format!("{} Pickaxe", name_by_tier(level)).as_ref(),
+        ))
+        .with(Weight {
+            weight: 2.,
+            volume: 4.,
         })
+        .with(Tier { level })
         .with(InBackpack { owner })
         .build();
 }
@@ -138,7 +407,775 @@ pub fn fire_pit(builder: LazyBuilder, owner: Entity) {
         .with(craftable())
         .with(Renderable::new(to_cp437('▬'), RGB::named(BURLYWOOD)))
         .with(FirePit {})
+        .with(Burner {
+            remaining: 0,
+            ignited: false,
+            leaves_ash: true,
+        })
+        .with(Heat {
+            output: 5.,
+            light_radius: 6,
+        })
+        .with(Container {})
+        .with(Structure {})
         .with(Name::new("Fire Pit"))
+        .with(Weight {
+            weight: 6.,
+            volume: 12.,
+        })
+        .with(Capacity {
+            max_weight: 10.,
+            max_volume: 20.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn chest(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('■'), RGB::named(BURLYWOOD)))
+        .with(Chest {})
+        .with(Structure {})
+        .with(Container {})
+        .with(BlocksMovement {})
+        .with(Name::new("Chest"))
+        .with(Weight {
+            weight: 5.,
+            volume: 16.,
+        })
+        .with(Capacity {
+            max_weight: 100.,
+            max_volume: 200.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn belt(builder: LazyBuilder, owner: Entity) {
+    let facing = Facing {
+        direction: Direction::default(),
+        glyphs: [to_cp437('↑'), to_cp437('→'), to_cp437('↓'), to_cp437('←')],
+    };
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(facing.glyph(), RGB::named(SLATEGRAY)))
+        .with(facing)
+        .with(Belt { interval: 15 })
+        .with(Structure {})
+        .with(Name::new("Belt"))
+        .with(Weight {
+            weight: 1.,
+            volume: 2.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn inserter(builder: LazyBuilder, owner: Entity) {
+    let facing = Facing {
+        direction: Direction::default(),
+        glyphs: [to_cp437('▲'), to_cp437('►'), to_cp437('▼'), to_cp437('◄')],
+    };
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(facing.glyph(), RGB::named(GOLD)))
+        .with(facing)
+        .with(Inserter {
+            interval: 30,
+            filter: None,
+            stalled: false,
+        })
+        .with(Structure {})
+        .with(Name::new("Inserter"))
+        .with(Weight {
+            weight: 2.,
+            volume: 3.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+fn splitter_builder<'a>(builder: LazyBuilder<'a>, name: &str) -> LazyBuilder<'a> {
+    let facing = Facing {
+        direction: Direction::default(),
+        glyphs: [to_cp437('┬'), to_cp437('┤'), to_cp437('┴'), to_cp437('├')],
+    };
+
+    builder
+        .with(craftable())
+        .with(facing)
+        .with(Belt { interval: 15 })
+        .with(Splitter {
+            priority: None,
+            next: Side::Left,
+        })
+        .with(Structure {})
+        .with(Name::new(name))
+        .with(Weight {
+            weight: 2.,
+            volume: 3.,
+        })
+}
+
+pub fn splitter(builder: LazyBuilder, owner: Entity) {
+    splitter_builder(builder, "Splitter")
+        .with(Renderable::new(to_cp437('┬'), RGB::named(SLATEGRAY)))
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn filter_splitter(builder: LazyBuilder, owner: Entity) {
+    splitter_builder(builder, "Filter Splitter")
+        .with(Renderable::new(to_cp437('┬'), RGB::named(ORANGE)))
+        .with(FilterSplitter { filter: None })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn merger(builder: LazyBuilder, owner: Entity) {
+    let facing = Facing {
+        direction: Direction::default(),
+        glyphs: [to_cp437('╩'), to_cp437('╠'), to_cp437('╦'), to_cp437('╣')],
+    };
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(facing.glyph(), RGB::named(SLATEGRAY)))
+        .with(facing)
+        .with(Belt { interval: 15 })
+        .with(Merger {
+            priority: None,
+            next: Side::Left,
+        })
+        .with(Structure {})
+        .with(Name::new("Merger"))
+        .with(Weight {
+            weight: 2.,
+            volume: 3.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+fn tunnel_facing() -> Facing {
+    Facing {
+        direction: Direction::default(),
+        glyphs: [to_cp437('╨'), to_cp437('╞'), to_cp437('╥'), to_cp437('╡')],
+    }
+}
+
+pub fn underground_belt(builder: LazyBuilder, owner: Entity) {
+    let facing = tunnel_facing();
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(facing.glyph(), RGB::named(SLATEGRAY)))
+        .with(facing)
+        .with(Belt { interval: 15 })
+        .with(Tunnel {
+            reach: 5,
+            partner: None,
+            is_entrance: false,
+        })
+        .with(Structure {})
+        .with(Name::new("Underground Belt"))
+        .with(Weight {
+            weight: 3.,
+            volume: 4.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn pipe_tunnel(builder: LazyBuilder, owner: Entity) {
+    let facing = tunnel_facing();
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(facing.glyph(), RGB::named(STEELBLUE)))
+        .with(facing)
+        .with(FluidBox::new(10.))
+        .with(Tunnel {
+            reach: 10,
+            partner: None,
+            is_entrance: false,
+        })
+        .with(Structure {})
+        .with(Name::new("Pipe Tunnel"))
+        .with(Weight {
+            weight: 3.,
+            volume: 4.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn pipe(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('○'), RGB::named(STEELBLUE)))
+        .with(Pipe {})
+        .with(FluidBox::new(10.))
+        .with(Structure {})
+        .with(Name::new("Pipe"))
+        .with(Weight {
+            weight: 1.,
+            volume: 2.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn pump(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('¶'), RGB::named(STEELBLUE)))
+        .with(Pump { rate: 0.25 })
+        .with(FluidBox::new(20.))
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Pump"))
+        .with(Weight {
+            weight: 3.,
+            volume: 4.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn tank(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('Θ'), RGB::named(STEELBLUE)))
+        .with(Tank {})
+        .with(FluidBox::new(500.))
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Tank"))
+        .with(Weight {
+            weight: 6.,
+            volume: 16.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn assembler(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('Æ'), RGB::named(LIGHTGRAY)))
+        .with(Machine {
+            recipe: None,
+            progress: None,
+        })
+        .with(FluidBox::new(50.))
+        .with(PowerConsumer {
+            draw: 10.,
+            satisfaction: 0.,
+        })
+        .with(Container {})
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Assembler"))
+        .with(Weight {
+            weight: 8.,
+            volume: 16.,
+        })
+        .with(Capacity {
+            max_weight: 50.,
+            max_volume: 100.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn mortar(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('▪'), RGB::named(DIMGRAY)))
+        .with(Name::new("Mortar"))
+        .with(Weight {
+            weight: 2.,
+            volume: 2.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn charcoal(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('▪'), RGB::named(DARKGRAY)))
+        .with(Fuel { burn_ticks: 2400 })
+        .with(Name::new("Charcoal"))
+        .with(Weight {
+            weight: 0.2,
+            volume: 1.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn burner_generator(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('Ω'), RGB::named(ORANGE)))
+        .with(Generator { output: 20. })
+        .with(Burner {
+            remaining: 0,
+            ignited: true,
+            leaves_ash: false,
+        })
+        .with(Container {})
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Burner Generator"))
+        .with(Weight {
+            weight: 8.,
+            volume: 12.,
+        })
+        .with(Capacity {
+            max_weight: 10.,
+            max_volume: 20.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn power_pole(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('┼'), RGB::named(BURLYWOOD)))
+        .with(PowerPole { radius: 4 })
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Power Pole"))
+        .with(Weight {
+            weight: 2.,
+            volume: 4.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+/// Ash left by a burner, in its inventory or on its tile when there is no room for it.
+pub fn ash(builder: LazyBuilder, owner: Entity) {
+    let (item, lazy) = (builder.entity, builder.lazy);
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('░'), RGB::named(GREY)))
+        .with(Name::new("Ash"))
+        .with(Weight {
+            weight: 0.1,
+            volume: 0.5,
+        })
+        .with(InBackpack { owner })
+        .build();
+    stow_later(lazy, item, owner);
+}
+
+pub fn spear(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('/'), RGB::named(BURLYWOOD)))
+        .with(Name::new("Fire-Hardened Spear"))
+        .with(Weight {
+            weight: 1.,
+            volume: 3.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn furnace(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('∩'), RGB::named(ORANGE)))
+        .with(Machine {
+            recipe: None,
+            progress: None,
+        })
+        .with(Furnace {})
+        .with(Burner {
+            remaining: 0,
+            ignited: true,
+            leaves_ash: true,
+        })
+        .with(Heat {
+            output: 10.,
+            light_radius: 3,
+        })
+        .with(Container {})
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Furnace"))
+        .with(Weight {
+            weight: 12.,
+            volume: 16.,
+        })
+        .with(Capacity {
+            max_weight: 40.,
+            max_volume: 60.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn ore(builder: LazyBuilder, owner: Entity, name: &str) {
+    let color = match name {
+        "Copper Ore" => RGB::named(CHOCOLATE),
+        _ => RGB::named(LIGHTSTEELBLUE),
+    };
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('*'), color))
+        .with(Name::new(name))
+        .with(Weight {
+            weight: 2.,
+            volume: 1.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn ingot(builder: LazyBuilder, owner: Entity, name: &str) {
+    let color = match name {
+        "Copper Ingot" => RGB::named(SANDYBROWN),
+        _ => RGB::named(SILVER),
+    };
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('='), color))
+        .with(Name::new(name))
+        .with(Weight {
+            weight: 1.,
+            volume: 0.5,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn sharp_flake(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('\''), RGB::named(DARKGRAY)))
+        .with(Name::new("Sharp Flake"))
+        .with(Weight {
+            weight: 0.05,
+            volume: 0.05,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn slag(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('%'), RGB::named(DIMGRAY)))
+        .with(Name::new("Slag"))
+        .with(Weight {
+            weight: 1.5,
+            volume: 1.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn stick(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('\\'), RGB::named(BURLYWOOD)))
+        .with(WoodenStick {})
+        .with(Fuel { burn_ticks: 600 })
+        .with(Name::new("Wooden Stick"))
+        .with(Weight {
+            weight: 0.3,
+            volume: 2.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn rose_petal(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437(','), RGB::named(RED)))
+        .with(Name::new("Rose Petal"))
+        .with(Weight {
+            weight: 0.01,
+            volume: 0.1,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn seed(builder: LazyBuilder, owner: Entity, name: &str) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('·'), RGB::named(BURLYWOOD)))
+        .with(Name::new(name))
+        .with(Weight {
+            weight: 0.01,
+            volume: 0.01,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn torch(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('i'), RGB::named(GOLD)))
+        .with(Light { radius: 5 })
+        .with(Burner {
+            remaining: 7200,
+            ignited: true,
+            leaves_ash: false,
+        })
+        .with(Structure {})
+        .with(Name::new("Torch"))
+        .with(Weight {
+            weight: 0.5,
+            volume: 1.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn lamp(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('¥'), RGB::named(LIGHTYELLOW)))
+        .with(Light { radius: 8 })
+        .with(PowerConsumer {
+            draw: 2.,
+            satisfaction: 0.,
+        })
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Lamp"))
+        .with(Weight {
+            weight: 3.,
+            volume: 4.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn solar_panel(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('▒'), RGB::named(STEELBLUE)))
+        .with(Generator { output: 10. })
+        .with(Solar {})
+        .with(Structure {})
+        .with(BlocksMovement {})
+        .with(Name::new("Solar Panel"))
+        .with(Weight {
+            weight: 6.,
+            volume: 10.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn wire(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('·'), RGB::named(RED)))
+        .with(Wire {})
+        .with(Structure {})
+        .with(Name::new("Wire"))
+        .with(Weight {
+            weight: 0.2,
+            volume: 0.5,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+fn combinator_facing() -> Facing {
+    Facing {
+        direction: Direction::default(),
+        glyphs: [to_cp437('╧'), to_cp437('╟'), to_cp437('╤'), to_cp437('╢')],
+    }
+}
+
+pub fn arithmetic_combinator(builder: LazyBuilder, owner: Entity) {
+    let facing = combinator_facing();
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(facing.glyph(), RGB::named(LIGHTGREEN)))
+        .with(facing)
+        .with(ArithmeticCombinator {
+            signal: "Flint".to_string(),
+            operation: Operation::Add,
+            value: 0,
+            output: "A".to_string(),
+            result: 0,
+        })
+        .with(Structure {})
+        .with(Name::new("Arithmetic Combinator"))
+        .with(Weight {
+            weight: 2.,
+            volume: 2.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn decider_combinator(builder: LazyBuilder, owner: Entity) {
+    let facing = combinator_facing();
+
+    builder
+        .with(craftable())
+        .with(Renderable::new(facing.glyph(), RGB::named(PINK)))
+        .with(facing)
+        .with(DeciderCombinator {
+            condition: Condition {
+                signal: "Flint".to_string(),
+                comparison: Comparison::Greater,
+                value: 0,
+            },
+            output: "A".to_string(),
+            result: 0,
+        })
+        .with(Structure {})
+        .with(Name::new("Decider Combinator"))
+        .with(Weight {
+            weight: 2.,
+            volume: 2.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn rail(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('║'), RGB::named(SILVER)))
+        .with(Rail {})
+        .with(Structure {})
+        .with(Name::new("Rail"))
+        .with(Weight {
+            weight: 1.,
+            volume: 1.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn rail_station(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('≡'), RGB::named(GOLD)))
+        .with(Rail {})
+        .with(Station::default())
+        .with(Structure {})
+        .with(Name::new("Rail Station"))
+        .with(Weight {
+            weight: 3.,
+            volume: 4.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn rail_signal(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('♦'), RGB::named(RED)))
+        .with(Rail {})
+        .with(RailSignal::default())
+        .with(Structure {})
+        .with(Name::new("Rail Signal"))
+        .with(Weight {
+            weight: 2.,
+            volume: 2.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn cart(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('▲'), RGB::named(CHOCOLATE)))
+        .with(Cart {
+            schedule: vec![],
+            stop: 0,
+            route: vec![],
+            interval: 5,
+            is_waiting: false,
+            held: (0, 0),
+            is_lost: false,
+        })
+        .with(Structure {})
+        .with(Container {})
+        .with(Name::new("Cart"))
+        .with(Weight {
+            weight: 20.,
+            volume: 30.,
+        })
+        .with(Capacity {
+            max_weight: 400.,
+            max_volume: 800.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn worker(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('☺'), RGB::named(GREY)))
+        .with(Worker {
+            jobs: vec![],
+            path: vec![],
+            interval: 6,
+            status: "idle".to_string(),
+            is_stuck: false,
+            hauling: vec![],
+            delivering: vec![],
+        })
+        .with(Structure {})
+        .with(Container {})
+        .with(Name::new("Worker"))
+        .with(Weight {
+            weight: 15.,
+            volume: 20.,
+        })
+        .with(Capacity {
+            max_weight: 30.,
+            max_volume: 60.,
+        })
+        .with(InBackpack { owner })
+        .build();
+}
+
+pub fn lab(builder: LazyBuilder, owner: Entity) {
+    builder
+        .with(craftable())
+        .with(Renderable::new(to_cp437('Φ'), RGB::named(LIGHTSTEELBLUE)))
+        .with(Lab {})
+        .with(Structure {})
+        .with(Container {})
+        .with(BlocksMovement {})
+        .with(Name::new("Lab"))
+        .with(Weight {
+            weight: 6.,
+            volume: 12.,
+        })
+        .with(Capacity {
+            max_weight: 40.,
+            max_volume: 80.,
+        })
         .with(InBackpack { owner })
         .build();
 }
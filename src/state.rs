@@ -1,18 +1,59 @@
-use std::borrow::{Borrow, BorrowMut};
-
 use bracket_lib::color::WHITE;
 use specs::{Join, RunNow, WorldExt};
 
 use gui::draw_log;
 
-use crate::components::items::PickupQueue;
+use crate::clock::Clock;
+use crate::components::lighting::{shade, LightMap};
+use crate::components::rails::Cart;
+use crate::components::structures::Structure;
+use crate::events::Events;
+use crate::gui::blueprints::show_blueprints;
+use crate::gui::bottlenecks::draw_bottleneck_overlay;
+use crate::gui::build::show_build;
+use crate::gui::container::show_container;
 use crate::gui::menu::{draw_menu, show_craft, show_inventory};
+use crate::gui::planner::show_planner;
+use crate::gui::power::draw_power_overlay;
+use crate::gui::rails::show_schedule;
+use crate::gui::research::show_research;
+use crate::gui::signals::show_signals;
+use crate::gui::statistics::show_statistics;
+use crate::gui::stockpiles::show_stockpiles;
+use crate::gui::workers::show_worker;
 use crate::map::{draw_map, TileType};
+use crate::systems::belt::BeltSystem;
+use crate::systems::bottlenecks::BottleneckSystem;
+use crate::systems::build::BuildSystem;
+use crate::systems::burner::BurnerSystem;
+use crate::systems::construction::ConstructionSystem;
 use crate::systems::craft::CraftSystem;
+use crate::systems::deconstruct::DeconstructSystem;
+use crate::systems::encumbrance::EncumbranceSystem;
+use crate::systems::fire_pit::FirePitSystem;
+use crate::systems::fluid::FluidSystem;
+use crate::systems::ghost::GhostSystem;
+use crate::systems::growth::GrowthSystem;
+use crate::systems::harvest::HarvestSystem;
+use crate::systems::history::HistorySystem;
+use crate::systems::inserter::InserterSystem;
+use crate::systems::lighting::LightingSystem;
+use crate::systems::machine::MachineSystem;
+use crate::systems::milestones::MilestoneSystem;
 use crate::systems::pickup::PickupSystem;
+use crate::systems::planting::PlantingSystem;
+use crate::systems::power::PowerSystem;
+use crate::systems::rails::RailSystem;
+use crate::systems::regrowth::RegrowthSystem;
+use crate::systems::research::ResearchSystem;
+use crate::systems::signals::SignalSystem;
+use crate::systems::statistics::StatisticsSystem;
+use crate::systems::transfer::TransferSystem;
+use crate::systems::tunnel::TunnelSystem;
+use crate::systems::workers::WorkerSystem;
 use crate::{
-    gui, BTerm, ControlMode, GameState, MenuMode, Name, Player, Position, Renderable,
-    UserInterfaceState, World, BLACK, RGB,
+    gui, BTerm, GameState, MenuMode, Name, Player, Position, Renderable, UserInterfaceState, World,
+    BLACK, RGB,
 };
 
 pub struct State {
@@ -29,12 +70,126 @@ impl Default for State {
 
 impl State {
     fn run_systems(&mut self) {
+        self.world.fetch_mut::<Clock>().advance();
+        self.world.fetch_mut::<Events>().clear();
+
+        let mut workers = WorkerSystem {};
+        workers.run_now(&self.world);
+        self.world.maintain();
+
         let mut pickup = PickupSystem {};
         pickup.run_now(&self.world);
         self.world.maintain();
 
         let mut craft = CraftSystem {};
         craft.run_now(&self.world);
+        self.world.maintain();
+
+        let mut research = ResearchSystem {};
+        research.run_now(&self.world);
+        self.world.maintain();
+
+        let mut harvest = HarvestSystem {};
+        harvest.run_now(&self.world);
+        self.world.maintain();
+
+        let mut regrowth = RegrowthSystem {};
+        regrowth.run_now(&self.world);
+        self.world.maintain();
+
+        let mut planting = PlantingSystem {};
+        planting.run_now(&self.world);
+        self.world.maintain();
+
+        let mut growth = GrowthSystem {};
+        growth.run_now(&self.world);
+        self.world.maintain();
+
+        let mut transfer = TransferSystem {};
+        transfer.run_now(&self.world);
+        self.world.maintain();
+
+        let mut ghost = GhostSystem {};
+        ghost.run_now(&self.world);
+        self.world.maintain();
+
+        let mut build = BuildSystem {};
+        build.run_now(&self.world);
+        self.world.maintain();
+
+        let mut construction = ConstructionSystem {};
+        construction.run_now(&self.world);
+        self.world.maintain();
+
+        let mut deconstruct = DeconstructSystem {};
+        deconstruct.run_now(&self.world);
+        self.world.maintain();
+
+        let mut history = HistorySystem {};
+        history.run_now(&self.world);
+        self.world.maintain();
+
+        let mut tunnel = TunnelSystem {};
+        tunnel.run_now(&self.world);
+        self.world.maintain();
+
+        let mut signals = SignalSystem {};
+        signals.run_now(&self.world);
+        self.world.maintain();
+
+        let mut belt = BeltSystem {};
+        belt.run_now(&self.world);
+        self.world.maintain();
+
+        let mut rails = RailSystem {};
+        rails.run_now(&self.world);
+        self.world.maintain();
+
+        let mut inserter = InserterSystem {};
+        inserter.run_now(&self.world);
+        self.world.maintain();
+
+        let mut fluid = FluidSystem {};
+        fluid.run_now(&self.world);
+        self.world.maintain();
+
+        let mut burner = BurnerSystem {};
+        burner.run_now(&self.world);
+        self.world.maintain();
+
+        let mut fire_pit = FirePitSystem {};
+        fire_pit.run_now(&self.world);
+        self.world.maintain();
+
+        let mut power = PowerSystem {};
+        power.run_now(&self.world);
+        self.world.maintain();
+
+        let mut machine = MachineSystem {};
+        machine.run_now(&self.world);
+        self.world.maintain();
+
+        // The analysis is only worth its joins while the overlay shows it.
+        if self.world.fetch::<UserInterfaceState>().menu_mode == MenuMode::Bottlenecks {
+            let mut bottlenecks = BottleneckSystem {};
+            bottlenecks.run_now(&self.world);
+            self.world.maintain();
+        }
+
+        let mut lighting = LightingSystem {};
+        lighting.run_now(&self.world);
+        self.world.maintain();
+
+        let mut milestones = MilestoneSystem {};
+        milestones.run_now(&self.world);
+        self.world.maintain();
+
+        let mut statistics = StatisticsSystem {};
+        statistics.run_now(&self.world);
+        self.world.maintain();
+
+        let mut encumbrance = EncumbranceSystem {};
+        encumbrance.run_now(&self.world);
 
         self.world.maintain();
     }
@@ -53,7 +208,8 @@ impl GameState for State {
 
         {
             let map = self.world.fetch::<Vec<TileType>>();
-            draw_map(&map, ctx);
+            let light_map = self.world.fetch::<LightMap>();
+            draw_map(&map, &light_map, ctx);
         }
 
         {
@@ -61,13 +217,47 @@ impl GameState for State {
             let players = self.world.read_storage::<Player>();
             let positions = self.world.read_storage::<Position>();
             let renderables = self.world.read_storage::<Renderable>();
+            let structures = self.world.read_storage::<Structure>();
+            let carts = self.world.read_storage::<Cart>();
+            let light_map = self.world.fetch::<LightMap>();
 
-            for (pos, render, name) in (&positions, &renderables, &names).join() {
-                if name.name == "Player".to_string() {
+            // Carts are drawn last so they show on top of the rails under them.
+            let structures_then_carts = (&positions, &renderables, &structures, !&carts)
+                .join()
+                .map(|(pos, render, _, _)| (pos, render))
+                .chain(
+                    (&positions, &renderables, &carts)
+                        .join()
+                        .map(|(pos, render, _)| (pos, render)),
+                );
+            for (pos, render) in structures_then_carts {
+                if !light_map.is_visible(pos.x, pos.y) {
                     continue;
                 }
 
-                ctx.set(pos.x, pos.y, render.fg, render.bg, render.glyph);
+                let light = light_map.at(pos.x, pos.y);
+                ctx.set(
+                    pos.x,
+                    pos.y,
+                    shade(render.fg, light),
+                    shade(render.bg, light),
+                    render.glyph,
+                );
+            }
+
+            for (pos, render, name, _) in (&positions, &renderables, &names, !&structures).join() {
+                if name.name == "Player" || !light_map.is_visible(pos.x, pos.y) {
+                    continue;
+                }
+
+                let light = light_map.at(pos.x, pos.y);
+                ctx.set(
+                    pos.x,
+                    pos.y,
+                    shade(render.fg, light),
+                    shade(render.bg, light),
+                    render.glyph,
+                );
             }
 
             for (pos, render, _player) in (&positions, &renderables, &players).join() {
@@ -84,6 +274,18 @@ impl GameState for State {
         match mode {
             MenuMode::Inventory => show_inventory(self, ctx),
             MenuMode::Craft => show_craft(self, ctx),
+            MenuMode::Build => show_build(self, ctx),
+            MenuMode::Container => show_container(self, ctx),
+            MenuMode::Power => draw_power_overlay(&self.world, ctx),
+            MenuMode::Bottlenecks => draw_bottleneck_overlay(&self.world, ctx),
+            MenuMode::Research => show_research(self, ctx),
+            MenuMode::Statistics => show_statistics(self, ctx),
+            MenuMode::Planner => show_planner(self, ctx),
+            MenuMode::Blueprints => show_blueprints(self, ctx),
+            MenuMode::Signals => show_signals(self, ctx),
+            MenuMode::Schedule => show_schedule(self, ctx),
+            MenuMode::Worker => show_worker(self, ctx),
+            MenuMode::Stockpiles => show_stockpiles(self, ctx),
             _ => {}
         }
 
@@ -0,0 +1,199 @@
+use std::collections::{HashMap, HashSet};
+
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::clock::Clock;
+use crate::components::items::BlocksMovement;
+use crate::components::signals::Disabled;
+use crate::components::structures::{
+    Belt, Facing, FilterSplitter, Merger, Side, Splitter, Structure, Tunnel,
+};
+use crate::map::{is_tile_walkable, xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::{Item, Name, Position};
+
+type Tile = (i32, i32);
+
+pub struct BeltSystem {}
+
+impl<'a> System<'a> for BeltSystem {
+    type SystemData = (
+        ReadExpect<'a, Clock>,
+        ReadExpect<'a, Vec<TileType>>,
+        Entities<'a>,
+        ReadStorage<'a, Belt>,
+        ReadStorage<'a, Facing>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Structure>,
+        ReadStorage<'a, BlocksMovement>,
+        WriteStorage<'a, Splitter>,
+        ReadStorage<'a, FilterSplitter>,
+        WriteStorage<'a, Merger>,
+        ReadStorage<'a, Tunnel>,
+        ReadStorage<'a, Disabled>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            clock,
+            map,
+            entities,
+            belts,
+            facings,
+            mut positions,
+            items,
+            names,
+            structures,
+            blockers,
+            mut splitters,
+            filter_splitters,
+            mut mergers,
+            tunnels,
+            disabled,
+        ) = data;
+
+        let moving_belts: HashMap<Tile, &Facing> = (&belts, &facings, &positions, !&disabled)
+            .join()
+            .filter(|(belt, _, _, _)| clock.every(belt.interval))
+            .map(|(_, facing, position, _)| ((position.x, position.y), facing))
+            .collect();
+        if moving_belts.is_empty() {
+            return;
+        }
+
+        let splitter_tiles: HashMap<Tile, Entity> = (&entities, &splitters, &positions)
+            .join()
+            .map(|(entity, _, position)| ((position.x, position.y), entity))
+            .collect();
+        let merger_tiles: HashMap<Tile, Entity> = (&entities, &mergers, &positions)
+            .join()
+            .map(|(entity, _, position)| ((position.x, position.y), entity))
+            .collect();
+
+        // Items on a paired tunnel entrance come up at its exit.
+        let tunnel_exits: HashMap<Tile, Tile> = (&belts, &tunnels, &positions)
+            .join()
+            .filter(|(_, tunnel, _)| tunnel.is_entrance)
+            .filter_map(|(_, tunnel, position)| {
+                let exit = positions.get(tunnel.partner?)?;
+                Some(((position.x, position.y), (exit.x, exit.y)))
+            })
+            .collect();
+
+        let blocked: HashSet<Tile> = (&positions, &blockers)
+            .join()
+            .map(|(position, _)| (position.x, position.y))
+            .collect();
+        let mut occupied: HashSet<Tile> = HashSet::new();
+        let mut loose = vec![];
+        for (entity, item, position, _) in (&entities, &items, &positions, !&structures).join() {
+            if item.can_be_picked {
+                occupied.insert((position.x, position.y));
+                loose.push((entity, (position.x, position.y)));
+            }
+        }
+
+        let is_free = |(x, y): Tile, occupied: &HashSet<Tile>| {
+            let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+            is_on_map
+                && is_tile_walkable(map[xy_to_idx(x, y)])
+                && !blocked.contains(&(x, y))
+                && !occupied.contains(&(x, y))
+        };
+
+        // Items at the front of a line move first, so keep passing over them until nothing moves.
+        let mut moved = HashSet::new();
+        loop {
+            let mut progress = false;
+
+            for index in 0..loose.len() {
+                let (entity, tile) = loose[index];
+                if moved.contains(&entity) {
+                    continue;
+                }
+
+                let facing = match moving_belts.get(&tile) {
+                    None => continue,
+                    Some(facing) => facing,
+                };
+
+                let (target, splitter) = match splitter_tiles.get(&tile) {
+                    None => match tunnel_exits.get(&tile) {
+                        Some(exit) => (*exit, None),
+                        None => (facing.front(tile.0, tile.1), None),
+                    },
+                    Some(splitter) => {
+                        let name = names.get(entity).map_or("", |name| name.name.as_str());
+                        let filter = filter_splitters
+                            .get(*splitter)
+                            .and_then(|filter| filter.filter.as_deref());
+                        let output = splitters
+                            .get(*splitter)
+                            .unwrap()
+                            .outputs(name, filter)
+                            .into_iter()
+                            .map(|side| (side, side.beside(tile.0, tile.1, facing.direction)))
+                            .find(|(_, target)| is_free(*target, &occupied));
+
+                        match output {
+                            None => continue,
+                            Some((side, target)) => (target, Some((*splitter, side))),
+                        }
+                    }
+                };
+                if !is_free(target, &occupied) {
+                    continue;
+                }
+
+                // A merger lets in the side it prefers first, as long as that side has an item
+                // about to come in.
+                if let Some(merger) = merger_tiles.get(&target) {
+                    let direction = facings.get(*merger).unwrap().direction;
+                    let side = [Side::Left, Side::Right]
+                        .into_iter()
+                        .find(|side| side.beside(target.0, target.1, direction) == tile);
+
+                    if let Some(side) = side {
+                        let preferred = mergers.get(*merger).unwrap().preferred();
+                        let waiting = preferred.beside(target.0, target.1, direction);
+                        let is_preferred_waiting = !splitter_tiles.contains_key(&waiting)
+                            && moving_belts
+                                .get(&waiting)
+                                .is_some_and(|facing| facing.front(waiting.0, waiting.1) == target)
+                            && loose
+                                .iter()
+                                .any(|(other, at)| *at == waiting && !moved.contains(other));
+                        if side != preferred && is_preferred_waiting {
+                            continue;
+                        }
+
+                        mergers.get_mut(*merger).unwrap().next = side.other();
+                    }
+                }
+
+                if let Some((splitter, side)) = splitter {
+                    splitters.get_mut(splitter).unwrap().next = side.other();
+                }
+
+                occupied.remove(&tile);
+                occupied.insert(target);
+                loose[index].1 = target;
+                moved.insert(entity);
+                progress = true;
+            }
+
+            if !progress {
+                break;
+            }
+        }
+
+        for (entity, (x, y)) in loose.into_iter() {
+            if moved.contains(&entity) {
+                positions
+                    .insert(entity, Position { x, y })
+                    .expect("unable to move item on belt");
+            }
+        }
+    }
+}
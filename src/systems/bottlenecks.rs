@@ -0,0 +1,407 @@
+use std::collections::{HashMap, HashSet};
+
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect};
+
+use crate::components::bottlenecks::{Bottlenecks, Cause, Finding, Status};
+use crate::components::fluids::FluidBox;
+use crate::components::items::BlocksMovement;
+use crate::components::power::{Burner, Heat, PowerConsumer};
+use crate::components::structures::{
+    Belt, Chest, Container, Facing, Furnace, Inserter, Machine, Structure,
+};
+use crate::map::{is_tile_walkable, xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::systems::craft::{find_recipe, has_materials, is_near_fire};
+use crate::systems::machine::MAX_MACHINE_OUTPUT;
+use crate::{InBackpack, Item, Name, Position};
+
+type Tile = (i32, i32);
+
+/// Where containers, belts, inserters and loose items are, for following supply lines upstream.
+struct Layout {
+    containers: HashMap<Tile, Entity>,
+    /// Belts by tile, with the tile each one moves items onto.
+    belts: HashMap<Tile, (Entity, Tile)>,
+    /// Inserters with the tiles they take from and put onto.
+    inserters: Vec<(Entity, Tile, Tile)>,
+    loose: HashSet<Tile>,
+    stocked: HashSet<Entity>,
+    statuses: HashMap<Entity, Status>,
+}
+
+impl Layout {
+    /// Follows the inserters feeding a starved machine upstream and returns whatever first fails
+    /// to supply it, or the machine itself when every input is flowing or nothing feeds it.
+    fn cause(&self, machine: Entity, tile: Tile, visited: &mut HashSet<Entity>) -> Entity {
+        self.inserters
+            .iter()
+            .filter(|(_, _, front)| *front == tile)
+            .find_map(|(inserter, back, _)| self.supply(*inserter, *back, visited))
+            .unwrap_or(machine)
+    }
+
+    /// What keeps `tile` from handing items to `taker`, if anything.
+    fn supply(&self, taker: Entity, tile: Tile, visited: &mut HashSet<Entity>) -> Option<Entity> {
+        if let Some(container) = self.containers.get(&tile) {
+            if !visited.insert(*container) {
+                return None;
+            }
+
+            return match self.statuses.get(container) {
+                Some(Status::InputStarved) => Some(self.cause(*container, tile, visited)),
+                Some(Status::Idle) | Some(Status::Unpowered) => Some(*container),
+                Some(_) => None,
+                None if self.stocked.contains(container) => None,
+                None => Some(*container),
+            };
+        }
+
+        if self.loose.contains(&tile) {
+            return None;
+        }
+
+        let (belt, _) = match self.belts.get(&tile) {
+            None => return Some(taker),
+            Some(belt) => belt,
+        };
+        if !visited.insert(*belt) {
+            return None;
+        }
+
+        // A belt tile is fed by the belts pointing at it and the inserters dropping onto it; it is
+        // only starved when all of them are.
+        let feeders: Vec<(Entity, Tile)> = self
+            .belts
+            .iter()
+            .filter(|(_, (_, front))| *front == tile)
+            .map(|(back, (previous, _))| (*previous, *back))
+            .chain(
+                self.inserters
+                    .iter()
+                    .filter(|(_, _, front)| *front == tile)
+                    .map(|(inserter, back, _)| (*inserter, *back)),
+            )
+            .collect();
+        if feeders.is_empty() {
+            return Some(*belt);
+        }
+
+        let causes: Option<Vec<Entity>> = feeders
+            .into_iter()
+            .map(|(feeder, back)| self.supply(feeder, back, visited))
+            .collect();
+
+        causes.map(|causes| causes[0])
+    }
+
+    /// The belt at the head of the jam an item on the belt at `tile` is stuck in, if it can not
+    /// move on because what is in front of it is closed or holds an item that is stuck too.
+    fn jam(
+        &self,
+        tile: Tile,
+        is_closed: &dyn Fn(Tile) -> bool,
+        visited: &mut HashSet<Tile>,
+    ) -> Option<Entity> {
+        let (belt, front) = self.belts.get(&tile)?;
+        if is_closed(*front) {
+            return Some(*belt);
+        }
+        if !self.loose.contains(front) {
+            return None;
+        }
+
+        match self.belts.contains_key(front) && visited.insert(*front) {
+            true => self.jam(*front, is_closed, visited),
+            // Items left on the ground in front of a belt, or a loop of belts full of items.
+            false => Some(*belt),
+        }
+    }
+}
+
+pub struct BottleneckSystem {}
+
+impl<'a> System<'a> for BottleneckSystem {
+    type SystemData = (
+        WriteExpect<'a, Bottlenecks>,
+        Entities<'a>,
+        ReadStorage<'a, Machine>,
+        ReadStorage<'a, Furnace>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, FluidBox>,
+        ReadStorage<'a, Burner>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, PowerConsumer>,
+        ReadStorage<'a, Container>,
+        ReadStorage<'a, Belt>,
+        ReadStorage<'a, Inserter>,
+        ReadStorage<'a, Facing>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Structure>,
+        ReadStorage<'a, Chest>,
+        ReadStorage<'a, BlocksMovement>,
+        ReadExpect<'a, Vec<TileType>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut bottlenecks,
+            entities,
+            machines,
+            furnaces,
+            positions,
+            backpack,
+            names,
+            boxes,
+            burners,
+            heats,
+            consumers,
+            containers,
+            belts,
+            inserters,
+            facings,
+            items,
+            structures,
+            chests,
+            blockers,
+            map,
+        ) = data;
+
+        let mut layout = Layout {
+            containers: (&entities, &containers, &positions)
+                .join()
+                .map(|(entity, _, position)| ((position.x, position.y), entity))
+                .collect(),
+            belts: (&entities, &belts, &facings, &positions)
+                .join()
+                .map(|(entity, _, facing, position)| {
+                    (
+                        (position.x, position.y),
+                        (entity, facing.front(position.x, position.y)),
+                    )
+                })
+                .collect(),
+            inserters: (&entities, &inserters, &facings, &positions)
+                .join()
+                .map(|(entity, _, facing, position)| {
+                    (
+                        entity,
+                        facing.back(position.x, position.y),
+                        facing.front(position.x, position.y),
+                    )
+                })
+                .collect(),
+            loose: (&items, &positions, !&structures)
+                .join()
+                .filter(|(item, _, _)| item.can_be_picked)
+                .map(|(_, position, _)| (position.x, position.y))
+                .collect(),
+            stocked: backpack.join().map(|pack| pack.owner).collect(),
+            statuses: HashMap::new(),
+        };
+
+        let mut statuses = HashMap::new();
+        for (entity, machine, position) in (&entities, &machines, &positions).join() {
+            let recipe = machine.recipe.and_then(find_recipe);
+            let is_powered = match (burners.get(entity), consumers.get(entity)) {
+                (Some(burner), _) => burner.is_burning() || machine.progress.is_none(),
+                (None, Some(consumer)) => consumer.satisfaction > 0.,
+                (None, None) => true,
+            };
+
+            // Mirrors what the machine system checks before starting and finishing a batch.
+            let status = match (recipe, machine.progress) {
+                // Furnaces drop their recipe whenever they have nothing left to smelt.
+                (None, _) if furnaces.contains(entity) => Status::InputStarved,
+                (None, _) => Status::Idle,
+                (Some(recipe), Some(progress)) if progress >= recipe.craft_ticks as f32 => {
+                    let outputs = (&backpack, &names)
+                        .join()
+                        .filter(|(pack, name)| pack.owner == entity && !recipe.requires(&name.name))
+                        .count();
+
+                    match outputs >= MAX_MACHINE_OUTPUT {
+                        true => Status::OutputBlocked,
+                        false => Status::Working,
+                    }
+                }
+                _ if !is_powered => Status::Unpowered,
+                (Some(recipe), None) => {
+                    let has_fluids = recipe.fluid_requirements.iter().all(|requirement| {
+                        boxes.get(entity).is_some_and(|fluid_box| {
+                            fluid_box.fluid == Some(requirement.fluid)
+                                && fluid_box.volume >= requirement.amount
+                        })
+                    });
+                    let has_fire = !recipe.needs_fire
+                        || is_near_fire(position.x, position.y, &positions, &burners, &heats);
+
+                    match has_fluids && has_fire && has_materials(entity, recipe, &backpack, &names)
+                    {
+                        true => Status::Working,
+                        false => Status::InputStarved,
+                    }
+                }
+                (Some(_), Some(_)) => Status::Working,
+            };
+
+            statuses.insert(entity, status);
+        }
+
+        // Chests are starved while empty and blocked while an inserter can not put into them.
+        for (entity, _, position) in (&entities, &chests, &positions).join() {
+            let tile = (position.x, position.y);
+            let is_refused = (&inserters, &facings, &positions)
+                .join()
+                .any(|(inserter, facing, at)| inserter.stalled && facing.front(at.x, at.y) == tile);
+
+            let status = match (is_refused, layout.stocked.contains(&entity)) {
+                (true, _) => Status::OutputBlocked,
+                (false, true) => Status::Working,
+                (false, false) => Status::InputStarved,
+            };
+            statuses.insert(entity, status);
+        }
+        layout.statuses = statuses;
+
+        // What stalls each belt and inserter, found while classifying them.
+        let mut traced = HashMap::new();
+
+        // Inserters are starved while their supply line is and blocked while what they hold is
+        // refused, which is then what refuses it.
+        for (entity, inserter, facing, position) in
+            (&entities, &inserters, &facings, &positions).join()
+        {
+            let (back, front) = (
+                facing.back(position.x, position.y),
+                facing.front(position.x, position.y),
+            );
+            let mut visited = HashSet::from([entity]);
+            let starved_by = match layout.containers.get(&back) {
+                Some(container) => {
+                    let has_item = (&backpack, &names).join().any(|(pack, name)| {
+                        pack.owner == *container
+                            && inserter
+                                .filter
+                                .as_ref()
+                                .is_none_or(|filter| &name.name == filter)
+                    });
+
+                    (!has_item).then(|| {
+                        layout
+                            .supply(entity, back, &mut visited)
+                            .unwrap_or(*container)
+                    })
+                }
+                None => layout.supply(entity, back, &mut visited),
+            };
+
+            let status = match (inserter.stalled, starved_by) {
+                (true, _) => {
+                    let refuser = layout.containers.get(&front).copied();
+                    traced.insert(entity, refuser.unwrap_or(entity));
+                    Status::OutputBlocked
+                }
+                (false, Some(cause)) => {
+                    traced.insert(entity, cause);
+                    Status::InputStarved
+                }
+                (false, None) => Status::Working,
+            };
+            layout.statuses.insert(entity, status);
+        }
+
+        // Belts are starved while nothing lies on them and nothing flows their way, and blocked
+        // while what lies on them is stuck in a jam.
+        let blocked: HashSet<Tile> = (&positions, &blockers)
+            .join()
+            .map(|(position, _)| (position.x, position.y))
+            .collect();
+        let is_closed = |(x, y): Tile| {
+            let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+            !is_on_map || !is_tile_walkable(map[xy_to_idx(x, y)]) || blocked.contains(&(x, y))
+        };
+        let mut belt_statuses = vec![];
+        for (tile, (belt, _)) in layout.belts.iter() {
+            let stall = match layout.loose.contains(tile) {
+                true => layout
+                    .jam(*tile, &is_closed, &mut HashSet::from([*tile]))
+                    .map(|head| (Status::OutputBlocked, head)),
+                false => layout
+                    .supply(*belt, *tile, &mut HashSet::new())
+                    .map(|cause| (Status::InputStarved, cause)),
+            };
+
+            let status = match stall {
+                None => Status::Working,
+                Some((status, cause)) => {
+                    traced.insert(*belt, cause);
+                    status
+                }
+            };
+            belt_statuses.push((*belt, status));
+        }
+        layout.statuses.extend(belt_statuses);
+
+        // Starved machines and chests are traced upstream to what fails to supply them.
+        let mut findings: Vec<Finding> = layout
+            .statuses
+            .iter()
+            .filter_map(|(entity, status)| {
+                let position = positions.get(*entity)?;
+                let cause = match traced.get(entity) {
+                    Some(cause) => Some(*cause),
+                    None => (*status == Status::InputStarved).then(|| {
+                        let mut visited = HashSet::from([*entity]);
+                        layout.cause(*entity, (position.x, position.y), &mut visited)
+                    }),
+                };
+
+                Some(Finding {
+                    entity: *entity,
+                    status: *status,
+                    cause,
+                })
+            })
+            .collect();
+        findings.sort_by_key(|finding| finding.entity.id());
+        bottlenecks.findings = findings;
+
+        // Everything stalled counts against what stalls it: starved and jammed things against the
+        // cause they were traced to, the rest against themselves.
+        let mut impacts: HashMap<Entity, usize> = HashMap::new();
+        bottlenecks
+            .findings
+            .iter()
+            .filter(|finding| finding.status != Status::Working)
+            .for_each(|finding| {
+                *impacts
+                    .entry(finding.cause.unwrap_or(finding.entity))
+                    .or_insert(0) += 1;
+            });
+
+        let mut causes: Vec<Cause> = impacts
+            .into_iter()
+            .map(|(entity, impact)| Cause {
+                entity,
+                // A starved chest, belt or inserter that a line traces back to is where that line
+                // runs dry.
+                status: match layout.statuses.get(&entity) {
+                    Some(status)
+                        if *status != Status::InputStarved || machines.contains(entity) =>
+                    {
+                        *status
+                    }
+                    _ => Status::Unsupplied,
+                },
+                impact,
+            })
+            .collect();
+        causes.sort_by(|a, b| {
+            b.impact
+                .cmp(&a.impact)
+                .then(a.entity.id().cmp(&b.entity.id()))
+        });
+        bottlenecks.causes = causes;
+    }
+}
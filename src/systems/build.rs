@@ -0,0 +1,183 @@
+use specs::{
+    Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, WriteExpect,
+    WriteStorage,
+};
+
+use crate::components::blueprints::Ghost;
+use crate::components::construction::ConstructionSite;
+use crate::components::items::{BlocksMovement, InBackpack};
+use crate::components::rails::{Cart, Rail};
+use crate::components::structures::{
+    BuildQueue, Facing, FilterSplitter, Furnace, Inserter, Machine, Merger, Splitter, Structure,
+};
+use crate::map::{is_tile_walkable, xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::spawner::ghost;
+use crate::{Log, Name, Player, Position, Renderable};
+
+/// How many tiles away from the player a structure can still be placed.
+pub const BUILD_REACH: i32 = 6;
+
+/// Marks out a construction site for the structure the player places, with the structure already
+/// delivered to it. The site puts it down once it is built.
+pub struct BuildSystem {}
+
+impl<'a> System<'a> for BuildSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        Entities<'a>,
+        WriteExpect<'a, Log>,
+        ReadExpect<'a, Vec<TileType>>,
+        WriteStorage<'a, BuildQueue>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Structure>,
+        ReadStorage<'a, BlocksMovement>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Facing>,
+        WriteStorage<'a, Renderable>,
+        ReadStorage<'a, Rail>,
+        ReadStorage<'a, Cart>,
+        ReadStorage<'a, Ghost>,
+        ReadStorage<'a, Machine>,
+        ReadStorage<'a, Furnace>,
+        ReadStorage<'a, Inserter>,
+        ReadStorage<'a, FilterSplitter>,
+        ReadStorage<'a, Splitter>,
+        ReadStorage<'a, Merger>,
+        Read<'a, LazyUpdate>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player,
+            entities,
+            mut log,
+            map,
+            mut wants_build,
+            positions,
+            mut backpack,
+            structures,
+            blockers,
+            players,
+            names,
+            mut facings,
+            mut renderables,
+            rails,
+            carts,
+            ghosts,
+            machines,
+            furnaces,
+            inserters,
+            filter_splitters,
+            splitters,
+            mergers,
+            lazy,
+        ) = data;
+
+        for build in wants_build.join() {
+            let is_owned = backpack
+                .get(build.structure)
+                .is_some_and(|pack| pack.owner == *player);
+            if !is_owned || !structures.contains(build.structure) {
+                continue;
+            }
+
+            let (player_x, player_y) = {
+                let position = positions.get(*player).unwrap();
+                (position.x, position.y)
+            };
+            let is_in_reach = (build.x - player_x).abs() <= BUILD_REACH
+                && (build.y - player_y).abs() <= BUILD_REACH;
+            if !is_in_reach {
+                log.log("that is too far away to build");
+                continue;
+            }
+
+            let is_on_map =
+                build.x >= 0 && build.y >= 0 && build.x < WIDTH as i32 && build.y < HEIGHT as i32;
+            if !is_on_map || !is_tile_walkable(map[xy_to_idx(build.x, build.y)]) {
+                log.log("you can not build there");
+                continue;
+            }
+
+            // Carts go on top of rails and nowhere else.
+            let is_cart = carts.contains(build.structure);
+            let is_on_rail = (&positions, &rails)
+                .join()
+                .any(|(position, _)| position.x == build.x && position.y == build.y);
+            if is_cart && !is_on_rail {
+                log.log("carts can only be placed on rails");
+                continue;
+            }
+
+            let is_occupied =
+                (&entities, &positions, &structures)
+                    .join()
+                    .any(|(entity, position, _)| {
+                        position.x == build.x
+                            && position.y == build.y
+                            && !(is_cart && rails.contains(entity))
+                    })
+                    || (&positions, &blockers)
+                        .join()
+                        .any(|(position, _)| position.x == build.x && position.y == build.y)
+                    || (&positions, &ghosts)
+                        .join()
+                        .any(|(position, _)| position.x == build.x && position.y == build.y);
+            let is_on_player = (&positions, &players)
+                .join()
+                .any(|(position, _)| position.x == build.x && position.y == build.y);
+            if is_occupied || (is_on_player && blockers.contains(build.structure)) {
+                log.log("there is something in the way");
+                continue;
+            }
+
+            if let Some(facing) = facings.get_mut(build.structure) {
+                facing.direction = build.direction;
+
+                if let Some(render) = renderables.get_mut(build.structure) {
+                    render.glyph = facing.glyph();
+                }
+            }
+
+            // The site hands the structure's own configuration back to it once it is built.
+            let name = names.get(build.structure).unwrap();
+            let plan = Ghost {
+                structure: name.to_string(),
+                direction: build.direction,
+                recipe: machines
+                    .get(build.structure)
+                    .filter(|_| !furnaces.contains(build.structure))
+                    .and_then(|machine| machine.recipe)
+                    .map(str::to_string),
+                filter: match inserters.get(build.structure) {
+                    Some(inserter) => inserter.filter.clone(),
+                    None => filter_splitters
+                        .get(build.structure)
+                        .and_then(|splitter| splitter.filter.clone()),
+                },
+                priority: match splitters.get(build.structure) {
+                    Some(splitter) => splitter.priority,
+                    None => mergers
+                        .get(build.structure)
+                        .and_then(|merger| merger.priority),
+                },
+            };
+            let glyph = renderables.get(build.structure).unwrap().glyph;
+            let placed = ConstructionSite {
+                is_placed_by_player: true,
+                ..Default::default()
+            };
+            let builder = lazy.create_entity(&entities);
+            let site = ghost(builder, plan, placed, build.x, build.y, glyph);
+            backpack
+                .insert(build.structure, InBackpack { owner: site })
+                .expect("unable to deliver structure");
+
+            log.log(format!("you start building the {}", name));
+        }
+
+        wants_build.clear();
+    }
+}
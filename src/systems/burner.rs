@@ -0,0 +1,93 @@
+use specs::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage,
+};
+
+use crate::components::power::{Burner, Fuel, Generator, PowerGrids};
+use crate::components::structures::Machine;
+use crate::events::{Event, Events};
+use crate::spawner::ash;
+use crate::{InBackpack, Name};
+
+pub struct BurnerSystem {}
+
+impl<'a> System<'a> for BurnerSystem {
+    type SystemData = (
+        ReadExpect<'a, PowerGrids>,
+        Entities<'a>,
+        WriteStorage<'a, Burner>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Fuel>,
+        ReadStorage<'a, Generator>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, Machine>,
+        ReadStorage<'a, Name>,
+        WriteExpect<'a, Events>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            grids,
+            entities,
+            mut burners,
+            backpack,
+            fuels,
+            generators,
+            lazy,
+            machines,
+            names,
+            mut events,
+        ) = data;
+
+        for (entity, burner, _) in (&entities, &mut burners, !&backpack).join() {
+            if !burner.ignited {
+                continue;
+            }
+
+            // Generators idle, keeping what is left of their fuel, while nothing on their grid
+            // draws power.
+            let is_idle = generators.contains(entity)
+                && !grids
+                    .grids
+                    .iter()
+                    .any(|grid| grid.generators.contains(&entity) && grid.has_demand());
+            if is_idle {
+                continue;
+            }
+
+            if burner.remaining > 0 {
+                burner.remaining -= 1;
+
+                if burner.remaining == 0 && burner.leaves_ash {
+                    ash(lazy.create_entity(&entities), entity);
+                }
+
+                continue;
+            }
+
+            // Idle machines keep their fuel until there is something to work on.
+            if machines
+                .get(entity)
+                .is_some_and(|machine| machine.progress.is_none())
+            {
+                continue;
+            }
+
+            let fuel = (&entities, &backpack, &fuels)
+                .join()
+                .find(|(_, pack, _)| pack.owner == entity);
+
+            if let Some((item, _, fuel)) = fuel {
+                burner.remaining = fuel.burn_ticks;
+                entities.delete(item).expect("should burn fuel");
+
+                if let Some(name) = names.get(item) {
+                    events.emit(Event::Consumed {
+                        item: name.name.clone(),
+                        amount: 1,
+                        by: entity,
+                    });
+                }
+            }
+        }
+    }
+}
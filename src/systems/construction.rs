@@ -0,0 +1,278 @@
+use std::ops::{Deref, DerefMut};
+
+use bracket_lib::random::RandomNumberGenerator;
+use specs::storage::MaskedStorage;
+use specs::{
+    Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, Storage, System,
+    WriteExpect, WriteStorage,
+};
+
+use crate::components::blueprints::Ghost;
+use crate::components::construction::ConstructionSite;
+use crate::components::items::{fits_into, BlocksMovement, Capacity, InBackpack, Weight};
+use crate::components::power::{Burner, Heat};
+use crate::components::rails::{Cart, Rail};
+use crate::components::research::Research;
+use crate::components::structures::{Facing, Structure};
+use crate::events::{Event, Events};
+use crate::spawner::{crafted, is_structure_name};
+use crate::systems::build::BUILD_REACH;
+use crate::systems::craft::{consume_materials, find_recipe, is_near_fire, materials, Recipe};
+use crate::systems::research::is_unlocked;
+use crate::{Log, Name, Player, Position, Renderable};
+
+/// Ticks a site is built for when its structure has no recipe to take the time from.
+const DEFAULT_BUILD_TICKS: u64 = 120;
+
+/// How many ticks a site for `structure` is built for once everything is delivered.
+pub fn build_ticks(structure: &str) -> u64 {
+    find_recipe(structure).map_or(DEFAULT_BUILD_TICKS, |recipe| recipe.craft_ticks)
+}
+
+/// The recipe a site for `structure` can be built from. Smelted structures and those that need a
+/// machine are only built from the crafted structure.
+fn site_recipe(structure: &str) -> Option<&'static Recipe> {
+    find_recipe(structure).filter(|recipe| !recipe.smelting && !recipe.needs_machine())
+}
+
+/// What still has to be delivered to a site for `structure` that holds the items named `held`.
+/// The crafted structure stands in for all of its ingredients, and is the only thing a structure
+/// without a site recipe can be built from.
+pub fn missing_materials(structure: &str, held: &[String]) -> Vec<(String, usize)> {
+    if held.iter().any(|name| name == structure) {
+        return vec![];
+    }
+
+    let mut missing: Vec<(String, usize)> = match site_recipe(structure) {
+        None => vec![(structure.to_string(), 1)],
+        Some(recipe) => materials(recipe.requirements)
+            .into_iter()
+            .map(|(name, amount)| {
+                let delivered = held.iter().filter(|held| *held == name).count();
+                (name.to_string(), amount.saturating_sub(delivered))
+            })
+            .filter(|(_, amount)| *amount > 0)
+            .collect(),
+    };
+    missing.sort();
+
+    missing
+}
+
+/// What is worth bringing to a site: the crafted structure while nothing was delivered yet and
+/// `has_structure` says one is at hand, or else whatever is missing.
+pub fn wanted_materials(
+    structure: &str,
+    held: &[String],
+    has_structure: bool,
+) -> Vec<(String, usize)> {
+    match held.is_empty() && has_structure {
+        true => vec![(structure.to_string(), 1)],
+        false => missing_materials(structure, held),
+    }
+}
+
+/// Hands `item`, delivered to the site at `(x, y)`, back to `player` when they are within building
+/// reach and have room for it, and leaves it on the site's tile otherwise. Returns whether the
+/// player got it back.
+pub fn refund<B, P, C, W>(
+    item: Entity,
+    player: Entity,
+    (x, y): (i32, i32),
+    backpack: &mut Storage<InBackpack, B>,
+    positions: &mut Storage<Position, P>,
+    capacities: &Storage<Capacity, C>,
+    weights: &Storage<Weight, W>,
+) -> bool
+where
+    B: DerefMut<Target = MaskedStorage<InBackpack>>,
+    P: DerefMut<Target = MaskedStorage<Position>>,
+    C: Deref<Target = MaskedStorage<Capacity>>,
+    W: Deref<Target = MaskedStorage<Weight>>,
+{
+    backpack.remove(item);
+    let is_in_reach = positions.get(player).is_some_and(|position| {
+        (position.x - x).abs() <= BUILD_REACH && (position.y - y).abs() <= BUILD_REACH
+    });
+
+    match is_in_reach && fits_into(player, item, backpack, capacities, weights) {
+        true => {
+            backpack
+                .insert(item, InBackpack { owner: player })
+                .expect("unable to refund item");
+            true
+        }
+        false => {
+            positions
+                .insert(item, Position { x, y })
+                .expect("unable to drop item");
+            false
+        }
+    }
+}
+
+/// Builds the construction sites that have everything delivered and puts the finished structure
+/// down where the site stands. A site built from ingredients first turns them into the structure,
+/// once the structure is researched and any fire its recipe needs burns nearby.
+pub struct ConstructionSystem {}
+
+impl<'a> System<'a> for ConstructionSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Research>,
+        WriteExpect<'a, Log>,
+        WriteExpect<'a, Events>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, Ghost>,
+        WriteStorage<'a, ConstructionSite>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Structure>,
+        ReadStorage<'a, BlocksMovement>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Rail>,
+        ReadStorage<'a, Cart>,
+        ReadStorage<'a, Burner>,
+        ReadStorage<'a, Heat>,
+        WriteStorage<'a, Facing>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            research,
+            mut log,
+            mut events,
+            mut rng,
+            lazy,
+            ghosts,
+            mut sites,
+            mut positions,
+            mut backpack,
+            names,
+            structures,
+            blockers,
+            players,
+            rails,
+            carts,
+            burners,
+            heats,
+            mut facings,
+            mut renderables,
+        ) = data;
+
+        let planned: Vec<(Entity, Ghost, i32, i32)> = (&entities, &ghosts, &positions, &sites)
+            .join()
+            .map(|(site, ghost, position, _)| (site, ghost.clone(), position.x, position.y))
+            .collect();
+
+        for (site, ghost, x, y) in planned {
+            // Only structures are ever put down, so nothing else is crafted over and over.
+            if !is_structure_name(&ghost.structure) {
+                continue;
+            }
+
+            let held: Vec<(Entity, String)> = (&entities, &backpack, &names)
+                .join()
+                .filter(|(_, pack, _)| pack.owner == site)
+                .map(|(item, _, name)| (item, name.to_string()))
+                .collect();
+            let held_names: Vec<String> = held.iter().map(|(_, name)| name.clone()).collect();
+            if !missing_materials(&ghost.structure, &held_names).is_empty() {
+                continue;
+            }
+
+            let construction = sites.get_mut(site).unwrap();
+            if construction.progress < build_ticks(&ghost.structure) {
+                construction.progress += 1;
+                continue;
+            }
+
+            let built = held
+                .iter()
+                .find(|(item, name)| *name == ghost.structure && structures.contains(*item));
+            let structure = match built {
+                Some((structure, _)) => *structure,
+                None => {
+                    // Everything is there, so the recipe is known. The structure it makes is put
+                    // down once it exists, and any byproducts go back with the leftovers.
+                    let recipe = site_recipe(&ghost.structure).unwrap();
+                    let problem = if !is_unlocked(recipe.result_item_name, &research) {
+                        Some("not researched yet".to_string())
+                    } else if recipe.needs_fire && !is_near_fire(x, y, &positions, &burners, &heats)
+                    {
+                        Some("needs a lit fire nearby".to_string())
+                    } else {
+                        None
+                    };
+                    let is_stuck = problem.is_some();
+                    sites.get_mut(site).unwrap().problem = problem;
+                    if is_stuck {
+                        continue;
+                    }
+
+                    consume_materials(site, recipe, &entities, &backpack, &names, &mut events);
+                    for result in recipe.roll_results(&mut rng) {
+                        crafted(result, lazy.create_entity(&entities), site);
+                        events.emit(Event::Crafted {
+                            item: result.to_string(),
+                            by: site,
+                        });
+                    }
+                    continue;
+                }
+            };
+
+            let is_here = |position: &Position| position.x == x && position.y == y;
+            let is_cart = carts.contains(structure);
+            let is_blocked =
+                (&entities, &positions, &structures)
+                    .join()
+                    .any(|(entity, position, _)| {
+                        is_here(position) && !(is_cart && rails.contains(entity))
+                    })
+                    || (&positions, &blockers)
+                        .join()
+                        .any(|(position, _)| is_here(position))
+                    || (is_cart
+                        && !(&positions, &rails)
+                            .join()
+                            .any(|(position, _)| is_here(position)))
+                    || (blockers.contains(structure)
+                        && (&positions, &players)
+                            .join()
+                            .any(|(position, _)| is_here(position)));
+            sites.get_mut(site).unwrap().problem =
+                is_blocked.then(|| "something is in the way".to_string());
+            if is_blocked {
+                continue;
+            }
+
+            backpack.remove(structure);
+            positions
+                .insert(structure, Position { x, y })
+                .expect("unable to place structure");
+
+            if let Some(facing) = facings.get_mut(structure) {
+                facing.direction = ghost.direction;
+
+                if let Some(render) = renderables.get_mut(structure) {
+                    render.glyph = facing.glyph();
+                }
+            }
+
+            log.log(format!("the {} is built", ghost.structure));
+            events.emit(Event::Built {
+                structure: ghost.structure.clone(),
+                entity: structure,
+                x,
+                y,
+                direction: ghost.direction,
+                by_player: sites.get(site).unwrap().is_placed_by_player,
+            });
+        }
+    }
+}
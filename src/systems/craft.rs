@@ -1,28 +1,103 @@
-use std::borrow::Borrow;
 use std::collections::HashMap;
+use std::ops::Deref;
 
-use specs::hibitset::{BitSetLike, DrainableBitSet};
+use bracket_lib::random::RandomNumberGenerator;
+use specs::storage::MaskedStorage;
+use specs::world::EntitiesRes;
 use specs::{
-    Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, WriteStorage,
+    Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, Storage, System,
+    WriteExpect, WriteStorage,
 };
 
+use crate::components::fluids::{Fluid, FluidAmount};
 use crate::components::items::CraftQueue;
-use crate::spawner::{axe, fire_pit};
-use crate::{InBackpack, Name};
+use crate::components::power::{Burner, Heat};
+use crate::components::research::Research;
+use crate::components::workers::Worker;
+use crate::events::{Event, Events};
+use crate::spawner::crafted;
+use crate::systems::research::is_unlocked;
+use crate::{InBackpack, Log, Name, Position};
 
 pub struct Requirement {
     pub item_name: &'static str,
     pub amount: i32,
 }
 
+/// A secondary output of a recipe, made with the given chance per batch, or every batch when the
+/// chance is `None`.
+pub struct Byproduct {
+    pub item_name: &'static str,
+    pub amount: i32,
+    pub chance: Option<f32>,
+}
+
+/// Crafted by hand from the backpack, or by a machine from its own inventory and fluid box.
+/// Recipes that use or make fluids can only be crafted by machines, and recipes that need fire
+/// can only be crafted within `FIRE_REACH` of something burning. Smelting recipes can only be
+/// crafted by furnaces, which craft nothing else.
 pub struct Recipe {
-    pub requirements: [Requirement; 2],
+    pub requirements: &'static [Requirement],
+    pub fluid_requirements: &'static [FluidAmount],
+    pub fluid_results: &'static [FluidAmount],
     pub result_item_name: &'static str,
+    pub result_amount: i32,
+    pub byproducts: &'static [Byproduct],
+    pub craft_ticks: u64,
+    pub needs_fire: bool,
+    pub smelting: bool,
+}
+
+impl Recipe {
+    pub fn needs_machine(&self) -> bool {
+        !self.fluid_requirements.is_empty() || !self.fluid_results.is_empty()
+    }
+
+    /// Whether `item_name` is consumed by this recipe.
+    pub fn requires(&self, item_name: &str) -> bool {
+        self.requirements
+            .iter()
+            .any(|requirement| requirement.item_name == item_name)
+    }
+
+    /// Every item one batch makes, the primary result first, with the byproduct chances rolled.
+    pub fn roll_results(&self, rng: &mut RandomNumberGenerator) -> Vec<&'static str> {
+        let mut results = vec![self.result_item_name; self.result_amount as usize];
+
+        self.byproducts
+            .iter()
+            .filter(|byproduct| {
+                byproduct
+                    .chance
+                    .is_none_or(|chance| rng.rand::<f32>() < chance)
+            })
+            .for_each(|byproduct| {
+                (0..byproduct.amount).for_each(|_| results.push(byproduct.item_name));
+            });
+
+        results
+    }
+
+    /// Whether a machine that is or is not a furnace can craft this recipe.
+    pub fn is_made_by(&self, furnace: bool) -> bool {
+        self.smelting == furnace
+    }
+}
+
+/// Adds up `requirements` that name the same item.
+pub fn materials(requirements: &[Requirement]) -> HashMap<&'static str, usize> {
+    let mut map: HashMap<&str, usize> = HashMap::new();
+
+    requirements.iter().for_each(|requirement| {
+        *map.entry(requirement.item_name).or_insert(0) += requirement.amount as usize;
+    });
+
+    map
 }
 
-pub const RECIPES: [Recipe; 2] = [
+pub const RECIPES: &[Recipe] = &[
     Recipe {
-        requirements: [
+        requirements: &[
             Requirement {
                 item_name: "Flint",
                 amount: 3,
@@ -32,10 +107,21 @@ pub const RECIPES: [Recipe; 2] = [
                 amount: 2,
             },
         ],
+        fluid_requirements: &[],
+        fluid_results: &[],
         result_item_name: "Flint Axe",
+        result_amount: 1,
+        byproducts: &[Byproduct {
+            item_name: "Sharp Flake",
+            amount: 1,
+            chance: Some(0.1),
+        }],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
     },
     Recipe {
-        requirements: [
+        requirements: &[
             Requirement {
                 item_name: "Flint",
                 amount: 2,
@@ -45,10 +131,881 @@ pub const RECIPES: [Recipe; 2] = [
                 amount: 1,
             },
         ],
+        fluid_requirements: &[],
+        fluid_results: &[],
         result_item_name: "Fire Pit",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 8,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Chest",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Belt",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 60,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 4,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Inserter",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Belt",
+                amount: 2,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Splitter",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Belt",
+                amount: 2,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Merger",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Splitter",
+                amount: 1,
+            },
+            Requirement {
+                item_name: "Inserter",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Filter Splitter",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Belt",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Underground Belt",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 1,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Pipe",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 60,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Pipe",
+                amount: 4,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Pipe Tunnel",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 3,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Pump",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 8,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Tank",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 240,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 5,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 5,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Assembler",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 240,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[Requirement {
+            item_name: "Flint",
+            amount: 2,
+        }],
+        fluid_requirements: &[FluidAmount {
+            fluid: Fluid::Water,
+            amount: 20.,
+        }],
+        fluid_results: &[],
+        result_item_name: "Mortar",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[Requirement {
+            item_name: "Wooden Stick",
+            amount: 3,
+        }],
+        fluid_requirements: &[],
+        fluid_results: &[FluidAmount {
+            fluid: Fluid::Sap,
+            amount: 10.,
+        }],
+        result_item_name: "Charcoal",
+        result_amount: 1,
+        byproducts: &[Byproduct {
+            item_name: "Ash",
+            amount: 1,
+            chance: None,
+        }],
+        craft_ticks: 240,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 6,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 4,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Burner Generator",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 240,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 4,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Power Pole",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 60,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Fire-Hardened Spear",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: true,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Flint Pickaxe",
+        result_amount: 1,
+        byproducts: &[Byproduct {
+            item_name: "Sharp Flake",
+            amount: 1,
+            chance: Some(0.1),
+        }],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 8,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Furnace",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 240,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[Requirement {
+            item_name: "Copper Ore",
+            amount: 2,
+        }],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Copper Ingot",
+        result_amount: 1,
+        byproducts: &[Byproduct {
+            item_name: "Slag",
+            amount: 1,
+            chance: Some(0.25),
+        }],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: true,
+    },
+    Recipe {
+        requirements: &[Requirement {
+            item_name: "Iron Ore",
+            amount: 2,
+        }],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Iron Ingot",
+        result_amount: 1,
+        byproducts: &[Byproduct {
+            item_name: "Slag",
+            amount: 1,
+            chance: Some(0.25),
+        }],
+        craft_ticks: 240,
+        needs_fire: false,
+        smelting: true,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Copper Axe",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Copper Pickaxe",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Iron Axe",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Iron Pickaxe",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 1,
+            },
+            Requirement {
+                item_name: "Charcoal",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Torch",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 60,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 2,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Lamp",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 2,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Solar Panel",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 300,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 6,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 4,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Lab",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 240,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[Requirement {
+            item_name: "Copper Ingot",
+            amount: 1,
+        }],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Wire",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 30,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wire",
+                amount: 5,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Arithmetic Combinator",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Wire",
+                amount: 5,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Decider Combinator",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 180,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 1,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 1,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Rail",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 30,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Rail",
+                amount: 1,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 3,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Rail Station",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 120,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Rail",
+                amount: 1,
+            },
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 2,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Rail Signal",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 90,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Chest",
+                amount: 1,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 10,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Cart",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 300,
+        needs_fire: false,
+        smelting: false,
+    },
+    Recipe {
+        requirements: &[
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 5,
+            },
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 3,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 4,
+            },
+        ],
+        fluid_requirements: &[],
+        fluid_results: &[],
+        result_item_name: "Worker",
+        result_amount: 1,
+        byproducts: &[],
+        craft_ticks: 600,
+        needs_fire: false,
+        smelting: false,
     },
 ];
 
+/// How many tiles away something burning can be and still count as nearby fire.
+pub const FIRE_REACH: i32 = 2;
+
+/// Whether a burner giving off heat is burning within `FIRE_REACH` of `x`, `y`.
+pub fn is_near_fire<P, B, H>(
+    x: i32,
+    y: i32,
+    positions: &Storage<Position, P>,
+    burners: &Storage<Burner, B>,
+    heats: &Storage<Heat, H>,
+) -> bool
+where
+    P: Deref<Target = MaskedStorage<Position>>,
+    B: Deref<Target = MaskedStorage<Burner>>,
+    H: Deref<Target = MaskedStorage<Heat>>,
+{
+    (positions, burners, heats)
+        .join()
+        .any(|(position, burner, _)| {
+            burner.is_burning()
+                && (position.x - x).abs() <= FIRE_REACH
+                && (position.y - y).abs() <= FIRE_REACH
+        })
+}
+
+/// Whether any furnace recipe consumes `item_name`.
+pub fn is_smelted(item_name: &str) -> bool {
+    RECIPES
+        .iter()
+        .any(|recipe| recipe.smelting && recipe.requires(item_name))
+}
+
+pub fn find_recipe(result_item_name: &str) -> Option<&'static Recipe> {
+    RECIPES
+        .iter()
+        .find(|recipe| recipe.result_item_name == result_item_name)
+}
+
+pub fn has_materials<B, N>(
+    owner: Entity,
+    recipe: &Recipe,
+    backpack: &Storage<InBackpack, B>,
+    names: &Storage<Name, N>,
+) -> bool
+where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    N: Deref<Target = MaskedStorage<Name>>,
+{
+    has_requirements(owner, recipe.requirements, backpack, names)
+}
+
+pub fn has_requirements<B, N>(
+    owner: Entity,
+    requirements: &[Requirement],
+    backpack: &Storage<InBackpack, B>,
+    names: &Storage<Name, N>,
+) -> bool
+where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    N: Deref<Target = MaskedStorage<Name>>,
+{
+    materials(requirements).iter().all(|(item_name, amount)| {
+        let owned = (backpack, names)
+            .join()
+            .filter(|(pack, name)| pack.owner == owner && &name.name == item_name)
+            .count();
+
+        owned >= *amount
+    })
+}
+
+/// Deletes the items `recipe` needs from the inventory of `owner`.
+pub fn consume_materials<B, N>(
+    owner: Entity,
+    recipe: &Recipe,
+    entities: &EntitiesRes,
+    backpack: &Storage<InBackpack, B>,
+    names: &Storage<Name, N>,
+    events: &mut Events,
+) where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    N: Deref<Target = MaskedStorage<Name>>,
+{
+    consume_requirements(
+        owner,
+        recipe.requirements,
+        entities,
+        backpack,
+        names,
+        events,
+    )
+}
+
+pub fn consume_requirements<B, N>(
+    owner: Entity,
+    requirements: &[Requirement],
+    entities: &EntitiesRes,
+    backpack: &Storage<InBackpack, B>,
+    names: &Storage<Name, N>,
+    events: &mut Events,
+) where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    N: Deref<Target = MaskedStorage<Name>>,
+{
+    materials(requirements)
+        .iter()
+        .for_each(|(item_name, amount)| {
+            let deleted = (entities, backpack, names)
+                .join()
+                .filter(|(_, pack, name)| pack.owner == owner && &name.name == item_name)
+                .take(*amount)
+                .filter(|item| entities.delete(item.0).is_ok())
+                .count();
+
+            if deleted > 0 {
+                events.emit(Event::Consumed {
+                    item: item_name.to_string(),
+                    amount: deleted as u32,
+                    by: owner,
+                });
+            }
+        });
+}
+
 pub struct CraftSystem {}
 
 impl<'a> System<'a> for CraftSystem {
@@ -56,67 +1013,102 @@ impl<'a> System<'a> for CraftSystem {
         ReadExpect<'a, Entity>,
         Entities<'a>,
         WriteStorage<'a, CraftQueue>,
-        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, InBackpack>,
         ReadStorage<'a, Name>,
         Read<'a, LazyUpdate>,
+        WriteExpect<'a, Log>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Burner>,
+        ReadStorage<'a, Heat>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadExpect<'a, Research>,
+        WriteExpect<'a, Events>,
+        WriteStorage<'a, Worker>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (player, entities, mut to_craft, mut backpack, names, lazy) = data;
+        let (
+            player,
+            entities,
+            mut to_craft,
+            backpack,
+            names,
+            lazy,
+            mut log,
+            positions,
+            burners,
+            heats,
+            mut rng,
+            research,
+            mut events,
+            mut workers,
+        ) = data;
 
-        let item_to_craft = &to_craft.join().nth(0);
-        if item_to_craft.is_none() {
-            return;
-        }
-
-        let item_name = &item_to_craft.unwrap().item_name;
-        let recipe = RECIPES
-            .iter()
-            .find(|recipe| item_name == recipe.result_item_name)
-            .unwrap();
+        for (crafter, craft) in (&entities, &to_craft).join() {
+            let is_player = crafter == *player;
+            let item_name = &craft.item_name;
+            let recipe = match find_recipe(item_name) {
+                None => continue,
+                Some(recipe) => recipe,
+            };
 
-        let to_remove = {
-            let mut map = HashMap::new();
+            // What went wrong, as told to the player and as shown on a worker.
+            let problem = if !is_unlocked(item_name, &research) {
+                Some((
+                    format!("you have not researched the {} yet", item_name),
+                    format!("the {} is not researched yet", item_name),
+                ))
+            } else if recipe.smelting {
+                let problem = format!("the {} can only be made in a furnace", item_name);
+                Some((problem.clone(), problem))
+            } else if recipe.needs_machine() {
+                let problem = format!("the {} can only be made in an assembler", item_name);
+                Some((problem.clone(), problem))
+            } else if recipe.needs_fire
+                && !positions.get(crafter).is_some_and(|position| {
+                    is_near_fire(position.x, position.y, &positions, &burners, &heats)
+                })
+            {
+                Some((
+                    format!("you need a lit fire nearby to make a {}", item_name),
+                    format!("needs a lit fire to make a {}", item_name),
+                ))
+            } else if !has_materials(crafter, recipe, &backpack, &names) {
+                Some((
+                    format!("you lack the materials for a {}", item_name),
+                    format!("lacks the materials for a {}", item_name),
+                ))
+            } else {
+                None
+            };
 
-            (&to_craft, &backpack, &names)
-                .join()
-                .for_each(|(_, _, item_in_backpack)| {
-                    let count = (&backpack, &names)
-                        .join()
-                        .filter(|(_, name)| &name.name == item_name)
-                        .count();
-
-                    map.insert(&item_in_backpack.name, count);
-                });
+            if let Some((message, status)) = problem {
+                if is_player {
+                    log.log(message);
+                }
+                if let Some(worker) = workers.get_mut(crafter) {
+                    (worker.status, worker.is_stuck) = (status, true);
+                }
+                continue;
+            }
 
-            map
-        };
+            consume_materials(crafter, recipe, &entities, &backpack, &names, &mut events);
 
-        if to_remove.len() == 0 {
-            to_craft.clear();
-            return;
-        };
-
-        to_remove.iter().for_each(|(item_name, _)| {
-            let name = item_name.clone().to_owned();
-            let amount = recipe
-                .requirements
+            recipe
+                .roll_results(&mut rng)
                 .iter()
-                .find(|requirement| name == requirement.item_name.to_string())
-                .expect("could not find correct item to remove")
-                .amount as usize;
+                .enumerate()
+                .for_each(|(index, result)| {
+                    crafted(result, lazy.create_entity(&entities), crafter);
+                    events.emit(Event::Crafted {
+                        item: result.to_string(),
+                        by: crafter,
+                    });
 
-            (&entities, &backpack, &names)
-                .join()
-                .filter(|(_, _, name)| &&name.name == item_name)
-                .take(amount)
-                .for_each(|item| entities.delete(item.0).expect("should delete item"));
-        });
-
-        match item_name.as_ref() {
-            "Flint Axe" => axe(lazy.create_entity(&entities), *player, 0),
-            "Fire Pit" => fire_pit(lazy.create_entity(&entities), *player),
-            _ => println!("tried to craft {}", item_name),
+                    if index >= recipe.result_amount as usize && is_player {
+                        log.log(format!("you also get a {}", result));
+                    }
+                });
         }
 
         to_craft.clear();
@@ -0,0 +1,139 @@
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::fluids::FluidBox;
+use crate::components::items::{backpack_load, Capacity, InBackpack, Weight};
+use crate::components::structures::{DeconstructQueue, Facing, Inserter, Machine, Structure};
+use crate::events::{Event, Events};
+use crate::systems::build::BUILD_REACH;
+use crate::{Log, Name, Position};
+
+/// Takes structures down into the player's backpack, together with whatever they held. Fluid
+/// in them is lost.
+pub struct DeconstructSystem {}
+
+impl<'a> System<'a> for DeconstructSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Log>,
+        Entities<'a>,
+        WriteStorage<'a, DeconstructQueue>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Structure>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Facing>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, Capacity>,
+        WriteStorage<'a, Machine>,
+        WriteStorage<'a, Inserter>,
+        WriteStorage<'a, FluidBox>,
+        WriteExpect<'a, Events>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player,
+            mut log,
+            entities,
+            mut wants_deconstruct,
+            mut positions,
+            mut backpack,
+            structures,
+            names,
+            facings,
+            weights,
+            capacities,
+            mut machines,
+            mut inserters,
+            mut fluid_boxes,
+            mut events,
+        ) = data;
+
+        for deconstruct in wants_deconstruct.join() {
+            let structure = deconstruct.structure;
+            let (x, y) = match positions.get(structure) {
+                Some(position) if structures.contains(structure) => (position.x, position.y),
+                _ => continue,
+            };
+
+            let (player_x, player_y) = {
+                let position = positions.get(*player).unwrap();
+                (position.x, position.y)
+            };
+            let is_in_reach =
+                (x - player_x).abs() <= BUILD_REACH && (y - player_y).abs() <= BUILD_REACH;
+            if !is_in_reach {
+                log.log("that is too far away to take down");
+                continue;
+            }
+
+            let contents: Vec<Entity> = (&entities, &backpack)
+                .join()
+                .filter(|(_, pack)| pack.owner == structure)
+                .map(|(item, _)| item)
+                .collect();
+
+            if let Some(capacity) = capacities.get(*player) {
+                let taken = contents
+                    .iter()
+                    .chain([structure].iter())
+                    .filter_map(|entity| weights.get(*entity))
+                    .fold(
+                        Weight {
+                            weight: 0.,
+                            volume: 0.,
+                        },
+                        |total, weight| Weight {
+                            weight: total.weight + weight.weight,
+                            volume: total.volume + weight.volume,
+                        },
+                    );
+                if !backpack_load(*player, &backpack, &weights).fits(&taken, capacity) {
+                    log.log(format!(
+                        "the {} does not fit in your backpack",
+                        names.get(structure).unwrap()
+                    ));
+                    continue;
+                }
+            }
+
+            contents.into_iter().for_each(|item| {
+                backpack
+                    .insert(item, InBackpack { owner: *player })
+                    .expect("unable to add to backpack");
+            });
+            positions.remove(structure);
+            backpack
+                .insert(structure, InBackpack { owner: *player })
+                .expect("unable to add to backpack");
+
+            if let Some(machine) = machines.get_mut(structure) {
+                machine.progress = None;
+            }
+            if let Some(inserter) = inserters.get_mut(structure) {
+                inserter.stalled = false;
+            }
+            if let Some(fluid_box) = fluid_boxes.get_mut(structure) {
+                fluid_box.volume = 0.;
+                fluid_box.fluid = None;
+            }
+
+            log.log(format!(
+                "you take down the {}",
+                names.get(structure).unwrap()
+            ));
+            events.emit(Event::Deconstructed {
+                structure: names.get(structure).unwrap().to_string(),
+                entity: structure,
+                x,
+                y,
+                direction: facings
+                    .get(structure)
+                    .map(|facing| facing.direction)
+                    .unwrap_or_default(),
+            });
+        }
+
+        wants_deconstruct.clear();
+    }
+}
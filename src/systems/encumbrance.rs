@@ -0,0 +1,46 @@
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::items::{backpack_load, Capacity, Encumbered, InBackpack, Weight};
+use crate::components::structures::Container;
+use crate::Log;
+
+pub struct EncumbranceSystem {}
+
+impl<'a> System<'a> for EncumbranceSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Log>,
+        Entities<'a>,
+        ReadStorage<'a, Capacity>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Weight>,
+        WriteStorage<'a, Encumbered>,
+        ReadStorage<'a, Container>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (player, mut log, entities, capacities, backpack, weights, mut encumbered, containers) =
+            data;
+
+        for (owner, capacity, _) in (&entities, &capacities, !&containers).join() {
+            let load = backpack_load(owner, &backpack, &weights);
+            let was_encumbered = encumbered.contains(owner);
+
+            if load.is_encumbering(capacity) && !was_encumbered {
+                encumbered
+                    .insert(owner, Encumbered::default())
+                    .expect("could not mark as encumbered");
+
+                if owner == *player {
+                    log.log("you are over-encumbered and move slower");
+                }
+            } else if !load.is_encumbering(capacity) && was_encumbered {
+                encumbered.remove(owner);
+
+                if owner == *player {
+                    log.log("you are no longer over-encumbered");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use bracket_lib::color::{BURLYWOOD, ORANGE};
+use specs::{Join, ReadStorage, System, WriteStorage};
+
+use crate::components::items::FirePit;
+use crate::components::power::Burner;
+use crate::{to_cp437, Renderable, RGB};
+
+/// Shows whether each fire pit is burning.
+pub struct FirePitSystem {}
+
+impl<'a> System<'a> for FirePitSystem {
+    type SystemData = (
+        ReadStorage<'a, FirePit>,
+        ReadStorage<'a, Burner>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (fire_pits, burners, mut renderables) = data;
+
+        for (_fire_pit, burner, render) in (&fire_pits, &burners, &mut renderables).join() {
+            (render.glyph, render.fg) = match burner.is_burning() {
+                true => (to_cp437('☼'), RGB::named(ORANGE)),
+                false => (to_cp437('▬'), RGB::named(BURLYWOOD)),
+            };
+        }
+    }
+}
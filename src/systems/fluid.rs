@@ -0,0 +1,223 @@
+use std::collections::{HashMap, HashSet};
+
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::fluids::{Fluid, FluidBox, FluidNetwork, FluidNetworks, Pipe, Pump};
+use crate::components::structures::{Facing, Tunnel};
+use crate::events::{Event, Events};
+use crate::map::{xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::{to_cp437, FontCharType, Position, Renderable};
+
+const NEIGHBORS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Double-line box drawing glyph for a pipe, indexed by a north, east, south, west bit mask.
+fn pipe_glyph(connections: usize) -> FontCharType {
+    let glyph = match connections {
+        0b0001 | 0b0100 | 0b0101 => '║',
+        0b0010 | 0b1000 | 0b1010 => '═',
+        0b0011 => '╚',
+        0b0110 => '╔',
+        0b1100 => '╗',
+        0b1001 => '╝',
+        0b0111 => '╠',
+        0b1110 => '╦',
+        0b1101 => '╣',
+        0b1011 => '╩',
+        0b1111 => '╬',
+        _ => '○',
+    };
+
+    to_cp437(glyph)
+}
+
+fn is_water(map: &[TileType], x: i32, y: i32) -> bool {
+    let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+    is_on_map && map[xy_to_idx(x, y)] == TileType::Water
+}
+
+pub struct FluidSystem {}
+
+impl<'a> System<'a> for FluidSystem {
+    type SystemData = (
+        ReadExpect<'a, Vec<TileType>>,
+        WriteExpect<'a, FluidNetworks>,
+        Entities<'a>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, FluidBox>,
+        ReadStorage<'a, Pump>,
+        ReadStorage<'a, Pipe>,
+        WriteStorage<'a, Renderable>,
+        WriteExpect<'a, Events>,
+        ReadStorage<'a, Tunnel>,
+        ReadStorage<'a, Facing>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            map,
+            mut networks,
+            entities,
+            positions,
+            mut boxes,
+            pumps,
+            pipes,
+            mut renderables,
+            mut events,
+            tunnels,
+            facings,
+        ) = data;
+
+        let tiles: HashMap<(i32, i32), Entity> = (&entities, &positions, &boxes)
+            .join()
+            .map(|(entity, position, _)| ((position.x, position.y), entity))
+            .collect();
+
+        // A tunnel end only connects on the side away from its partner, or on both ends of its
+        // axis while it has none, so the lines it passes under stay apart.
+        let mut openings: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::new();
+        let mut links: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+        for (tunnel, facing, position, _) in (&tunnels, &facings, &positions, &boxes).join() {
+            let (x, y) = (position.x, position.y);
+            let partner = tunnel.partner.and_then(|partner| positions.get(partner));
+            let opening = match (partner, tunnel.is_entrance) {
+                (None, _) => vec![facing.back(x, y), facing.front(x, y)],
+                (Some(_), true) => vec![facing.back(x, y)],
+                (Some(_), false) => vec![facing.front(x, y)],
+            };
+            openings.insert((x, y), opening);
+
+            if let Some(partner) = partner {
+                links.insert((x, y), (partner.x, partner.y));
+            }
+        }
+        let connects = |from: (i32, i32), to: (i32, i32)| {
+            tiles.contains_key(&to)
+                && openings
+                    .get(&from)
+                    .is_none_or(|opening| opening.contains(&to))
+                && openings
+                    .get(&to)
+                    .is_none_or(|opening| opening.contains(&from))
+        };
+
+        for (pump, position, fluid_box) in (&pumps, &positions, &mut boxes).join() {
+            let is_by_water = NEIGHBORS
+                .iter()
+                .any(|(dx, dy)| is_water(&map, position.x + dx, position.y + dy));
+
+            if is_by_water {
+                fluid_box.fill(Fluid::Water, pump.rate);
+            }
+        }
+
+        for (entity, position, _pipe) in (&entities, &positions, &pipes).join() {
+            let connections = NEIGHBORS
+                .iter()
+                .enumerate()
+                .filter(|(_, (dx, dy))| {
+                    connects((position.x, position.y), (position.x + dx, position.y + dy))
+                })
+                .fold(0, |mask, (bit, _)| mask | (1 << bit));
+
+            if let Some(render) = renderables.get_mut(entity) {
+                render.glyph = pipe_glyph(connections);
+            }
+        }
+
+        let largest_before = largest_network(&networks);
+        networks.networks.clear();
+        let mut visited: HashSet<(i32, i32)> = HashSet::new();
+
+        for start in tiles.keys() {
+            if !visited.insert(*start) {
+                continue;
+            }
+
+            let mut members = vec![];
+            let mut frontier = vec![*start];
+            while let Some((x, y)) = frontier.pop() {
+                members.push(tiles[&(x, y)]);
+
+                let neighbors = NEIGHBORS
+                    .iter()
+                    .map(|(dx, dy)| (x + dx, y + dy))
+                    .filter(|next| connects((x, y), *next))
+                    .chain(links.get(&(x, y)).copied());
+                for next in neighbors {
+                    if visited.insert(next) {
+                        frontier.push(next);
+                    }
+                }
+            }
+
+            networks.networks.push(equalize(members, &mut boxes));
+        }
+
+        let largest = largest_network(&networks);
+        if largest > largest_before {
+            events.emit(Event::FluidNetworkGrown { members: largest });
+        }
+    }
+}
+
+fn largest_network(networks: &FluidNetworks) -> usize {
+    networks
+        .networks
+        .iter()
+        .map(|network| network.members.len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Spreads the fluid of a network over its members so every box is equally full. Boxes holding a
+/// different fluid than the bulk of the network keep their contents.
+fn equalize(members: Vec<Entity>, boxes: &mut WriteStorage<FluidBox>) -> FluidNetwork {
+    let mut volumes: HashMap<&'static str, (Fluid, f32)> = HashMap::new();
+    for member in members.iter() {
+        let fluid_box = boxes.get(*member).unwrap();
+
+        if let Some(fluid) = fluid_box.fluid {
+            volumes.entry(fluid.name()).or_insert((fluid, 0.)).1 += fluid_box.volume;
+        }
+    }
+
+    let fluid = volumes
+        .values()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(fluid, _)| *fluid);
+
+    let sharing: Vec<Entity> = members
+        .iter()
+        .filter(|member| {
+            let fluid_box = boxes.get(**member).unwrap();
+            fluid.is_none_or(|fluid| fluid_box.can_hold(fluid))
+        })
+        .copied()
+        .collect();
+
+    let (volume, capacity) = sharing.iter().fold((0., 0.), |(volume, capacity), member| {
+        let fluid_box = boxes.get(*member).unwrap();
+        (volume + fluid_box.volume, capacity + fluid_box.capacity)
+    });
+
+    let pressure = match capacity > 0. {
+        true => volume / capacity,
+        false => 0.,
+    };
+    for member in sharing.iter() {
+        let fluid_box = boxes.get_mut(*member).unwrap();
+
+        fluid_box.volume = fluid_box.capacity * pressure;
+        fluid_box.fluid = match fluid_box.volume > 0. {
+            true => fluid,
+            false => None,
+        };
+    }
+
+    FluidNetwork {
+        members,
+        fluid,
+        volume,
+        capacity,
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::components::blueprints::Ghost;
+use crate::components::items::{Capacity, InBackpack, TransferQueue, Weight};
+use crate::components::research::Research;
+use crate::components::structures::{
+    FilterSplitter, Inserter, Machine, Merger, Splitter, Structure,
+};
+use crate::systems::build::BUILD_REACH;
+use crate::systems::construction::{refund, wanted_materials};
+use crate::systems::craft::find_recipe;
+use crate::systems::research::is_unlocked;
+use crate::{Name, Position};
+
+/// Hands the configuration of a ghost over to the structure built where it stands, and has the
+/// player deliver what the construction sites in reach need from their backpack.
+pub struct GhostSystem {}
+
+impl<'a> System<'a> for GhostSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Research>,
+        Entities<'a>,
+        ReadStorage<'a, Ghost>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Structure>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, TransferQueue>,
+        WriteStorage<'a, Machine>,
+        WriteStorage<'a, Inserter>,
+        WriteStorage<'a, FilterSplitter>,
+        WriteStorage<'a, Splitter>,
+        WriteStorage<'a, Merger>,
+        ReadStorage<'a, Capacity>,
+        ReadStorage<'a, Weight>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player,
+            research,
+            entities,
+            ghosts,
+            mut positions,
+            structures,
+            names,
+            mut backpack,
+            mut transfers,
+            mut machines,
+            mut inserters,
+            mut filter_splitters,
+            mut splitters,
+            mut mergers,
+            capacities,
+            weights,
+        ) = data;
+
+        let structure_at = |x: i32, y: i32| {
+            (&entities, &structures, &positions)
+                .join()
+                .find(|(_, _, position)| position.x == x && position.y == y)
+                .map(|(structure, _, _)| structure)
+        };
+
+        // A ghost whose structure got built hands over its configuration and goes away.
+        let mut finished: Vec<(Entity, i32, i32)> = vec![];
+        for (entity, ghost, position) in (&entities, &ghosts, &positions).join() {
+            let structure = match structure_at(position.x, position.y) {
+                Some(structure)
+                    if names
+                        .get(structure)
+                        .is_some_and(|name| name.name == ghost.structure) =>
+                {
+                    structure
+                }
+                _ => continue,
+            };
+
+            if let Some(machine) = machines.get_mut(structure) {
+                let recipe = ghost
+                    .recipe
+                    .as_deref()
+                    .and_then(find_recipe)
+                    .filter(|recipe| is_unlocked(recipe.result_item_name, &research))
+                    .map(|recipe| recipe.result_item_name);
+                if recipe.is_some() && machine.recipe != recipe {
+                    machine.recipe = recipe;
+                    machine.progress = None;
+                }
+            }
+            if let Some(inserter) = inserters.get_mut(structure) {
+                inserter.filter = ghost.filter.clone();
+            }
+            if let Some(splitter) = filter_splitters.get_mut(structure) {
+                splitter.filter = ghost.filter.clone();
+            }
+            if let Some(splitter) = splitters.get_mut(structure) {
+                splitter.priority = ghost.priority;
+            }
+            if let Some(merger) = mergers.get_mut(structure) {
+                merger.priority = ghost.priority;
+            }
+
+            finished.push((entity, position.x, position.y));
+        }
+
+        for (entity, x, y) in finished {
+            // Whatever was delivered and not used up goes back to the player.
+            let leftovers: Vec<Entity> = (&entities, &backpack)
+                .join()
+                .filter(|(_, pack)| pack.owner == entity)
+                .map(|(item, _)| item)
+                .collect();
+            leftovers.into_iter().for_each(|item| {
+                refund(
+                    item,
+                    *player,
+                    (x, y),
+                    &mut backpack,
+                    &mut positions,
+                    &capacities,
+                    &weights,
+                );
+            });
+
+            entities.delete(entity).expect("should delete ghost");
+        }
+
+        let (player_x, player_y) = match positions.get(*player) {
+            None => return,
+            Some(position) => (position.x, position.y),
+        };
+        let carried: Vec<(Entity, String)> = (&entities, &backpack, &names)
+            .join()
+            .filter(|(_, pack, _)| pack.owner == *player)
+            .map(|(item, _, name)| (item, name.to_string()))
+            .collect();
+
+        // Sites in reach get what they still need out of the player's backpack.
+        let mut handed_over: HashSet<Entity> = HashSet::new();
+        for (site, ghost, position) in (&entities, &ghosts, &positions).join() {
+            let is_in_reach = (position.x - player_x).abs() <= BUILD_REACH
+                && (position.y - player_y).abs() <= BUILD_REACH;
+            if !is_in_reach {
+                continue;
+            }
+
+            let held: Vec<String> = (&backpack, &names)
+                .join()
+                .filter(|(pack, _)| pack.owner == site)
+                .map(|(_, name)| name.to_string())
+                .collect();
+            let has_structure = carried
+                .iter()
+                .any(|(item, name)| *name == ghost.structure && structures.contains(*item));
+
+            for (wanted, amount) in wanted_materials(&ghost.structure, &held, has_structure) {
+                let items: Vec<Entity> = carried
+                    .iter()
+                    .filter(|(item, name)| *name == wanted && !handed_over.contains(item))
+                    .take(amount)
+                    .map(|(item, _)| *item)
+                    .collect();
+                for item in items {
+                    handed_over.insert(item);
+                    transfers
+                        .insert(item, TransferQueue { to: site })
+                        .expect("unable to deliver item");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use specs::{Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::components::farming::{find_plant, Crop};
+use crate::components::lighting::LightMap;
+use crate::map::{xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::spawner::grown_plant;
+use crate::{to_cp437, Name, Position, Renderable, RGB};
+
+/// Crops within this many tiles of water grow faster.
+const WATER_REACH: i32 = 2;
+const WATER_BONUS: f32 = 0.5;
+/// Bonus for crops on fully lit tiles.
+const LIGHT_BONUS: f32 = 0.25;
+
+pub struct GrowthSystem {}
+
+impl<'a> System<'a> for GrowthSystem {
+    type SystemData = (
+        ReadExpect<'a, Vec<TileType>>,
+        Entities<'a>,
+        WriteStorage<'a, Crop>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Renderable>,
+        WriteStorage<'a, Name>,
+        ReadExpect<'a, LightMap>,
+        Read<'a, LazyUpdate>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (map, entities, mut crops, positions, mut renderables, mut names, light_map, lazy) =
+            data;
+
+        for (entity, crop, position, render, name) in (
+            &entities,
+            &mut crops,
+            &positions,
+            &mut renderables,
+            &mut names,
+        )
+            .join()
+        {
+            let plant = match find_plant(crop.plant) {
+                None => continue,
+                Some(plant) => plant,
+            };
+
+            let is_watered = (-WATER_REACH..=WATER_REACH).any(|dx| {
+                (-WATER_REACH..=WATER_REACH).any(|dy| {
+                    let (x, y) = (position.x + dx, position.y + dy);
+                    let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+
+                    is_on_map && map[xy_to_idx(x, y)] == TileType::Water
+                })
+            });
+            let light = light_map.at(position.x, position.y);
+
+            let mut rate = 1. + light * LIGHT_BONUS;
+            if is_watered {
+                rate += WATER_BONUS;
+            }
+            if plant.needs_light {
+                rate *= light;
+            }
+            crop.progress += rate;
+
+            if crop.progress < plant.stages[crop.stage].ticks as f32 {
+                continue;
+            }
+
+            crop.stage += 1;
+            crop.progress = 0.;
+
+            // The grown plant is a new entity, made just like the wild ones.
+            if crop.stage + 1 == plant.stages.len() {
+                let (name, x, y) = (plant.name, position.x, position.y);
+                entities.delete(entity).expect("should replace crop");
+                lazy.exec_mut(move |world| {
+                    grown_plant(world, name, x, y);
+                });
+                continue;
+            }
+
+            let stage = &plant.stages[crop.stage];
+            render.glyph = to_cp437(stage.glyph);
+            render.fg = RGB::from_u8(stage.color.0, stage.color.1, stage.color.2);
+            *name = Name::new(&format!("{} {}", plant.name, stage.name));
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use bracket_lib::random::RandomNumberGenerator;
+use specs::{
+    Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, WriteExpect,
+    WriteStorage,
+};
+
+use crate::clock::Clock;
+use crate::components::farming::SEED_CHANCE;
+use crate::components::items::{
+    name_by_tier, Axe, HarvestQueue, Pickaxe, Regrowing, ResourceNode, Tier, Tool,
+};
+use crate::events::{Event, Events};
+use crate::spawner::crafted;
+use crate::{InBackpack, Log, Name};
+
+pub struct HarvestSystem {}
+
+impl<'a> System<'a> for HarvestSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Log>,
+        Entities<'a>,
+        WriteStorage<'a, HarvestQueue>,
+        WriteStorage<'a, ResourceNode>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Axe>,
+        ReadStorage<'a, Pickaxe>,
+        ReadStorage<'a, Tier>,
+        ReadStorage<'a, Name>,
+        Read<'a, LazyUpdate>,
+        ReadExpect<'a, Clock>,
+        WriteStorage<'a, Regrowing>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteExpect<'a, Events>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player,
+            mut log,
+            entities,
+            mut wants_harvest,
+            mut nodes,
+            backpack,
+            axes,
+            pickaxes,
+            tiers,
+            names,
+            lazy,
+            clock,
+            mut regrowing,
+            mut rng,
+            mut events,
+        ) = data;
+
+        for (harvester, harvest) in (&entities, &wants_harvest).join() {
+            let node = match nodes.get_mut(harvest.node) {
+                None => continue,
+                Some(node) => node,
+            };
+            let node_name = names.get(harvest.node).unwrap().to_string().to_lowercase();
+
+            if node.is_depleted() {
+                if harvester == *player {
+                    log.log(format!("the {} has nothing left to give", node_name));
+                }
+                continue;
+            }
+
+            if let Some(tool) = node.tool {
+                let best_tool = (&entities, &backpack, &tiers)
+                    .join()
+                    .filter(|(item, pack, _)| {
+                        pack.owner == harvester
+                            && match tool {
+                                Tool::Axe => axes.contains(*item),
+                                Tool::Pickaxe => pickaxes.contains(*item),
+                            }
+                    })
+                    .map(|(_, _, tier)| tier.level)
+                    .max();
+
+                match best_tool {
+                    None => {
+                        if harvester == *player {
+                            log.log(format!(
+                                "you need a {} to harvest the {}",
+                                tool.name(),
+                                node_name
+                            ));
+                        }
+                        continue;
+                    }
+                    Some(level) if level < node.tier => {
+                        if harvester == *player {
+                            log.log(format!(
+                                "you need a {} {} or better to harvest the {}",
+                                name_by_tier(node.tier).to_lowercase(),
+                                tool.name(),
+                                node_name
+                            ));
+                        }
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            crafted(node.yields, lazy.create_entity(&entities), harvester);
+            events.emit(Event::Harvested {
+                item: node.yields.to_string(),
+                by: harvester,
+            });
+            node.remaining -= 1;
+
+            if node.regrow_interval.is_some() && !regrowing.contains(harvest.node) {
+                regrowing
+                    .insert(harvest.node, Regrowing { since: clock.tick })
+                    .expect("unable to start regrowing");
+            }
+
+            if harvester == *player {
+                log.log(format!("you get a {} from the {}", node.yields, node_name));
+            }
+
+            if let Some(seed) = node.seed.filter(|_| rng.rand::<f32>() < SEED_CHANCE) {
+                crafted(seed, lazy.create_entity(&entities), harvester);
+                events.emit(Event::Harvested {
+                    item: seed.to_string(),
+                    by: harvester,
+                });
+
+                if harvester == *player {
+                    log.log(format!("you also get a {}", seed));
+                }
+            }
+
+            if node.is_depleted() {
+                if harvester == *player {
+                    log.log(format!("the {} is depleted", node_name));
+                }
+
+                if node.regrow_interval.is_none() {
+                    entities.delete(harvest.node).expect("should delete node");
+                }
+            }
+        }
+
+        wants_harvest.clear();
+    }
+}
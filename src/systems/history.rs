@@ -0,0 +1,92 @@
+use specs::{Join, ReadExpect, ReadStorage, System, WriteExpect};
+
+use crate::components::blueprints::Ghost;
+use crate::components::history::{Action, History, Replay};
+use crate::events::{Event, Events};
+use crate::Position;
+
+/// Records the structures the player placed by hand and took down this tick, and settles the undo
+/// or redo that was waiting on them. One that places a structure waits for as long as its site is
+/// built.
+pub struct HistorySystem {}
+
+impl<'a> System<'a> for HistorySystem {
+    type SystemData = (
+        WriteExpect<'a, History>,
+        ReadExpect<'a, Events>,
+        ReadStorage<'a, Ghost>,
+        ReadStorage<'a, Position>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut history, events, ghosts, positions) = data;
+
+        let mut replay = history.replaying.take();
+        for event in events.this_tick.iter() {
+            let done = match event.clone() {
+                Event::Built {
+                    structure,
+                    entity,
+                    x,
+                    y,
+                    direction,
+                    by_player: true,
+                } => Action::Place {
+                    structure: entity,
+                    name: structure,
+                    x,
+                    y,
+                    direction,
+                },
+                Event::Deconstructed {
+                    structure,
+                    entity,
+                    x,
+                    y,
+                    direction,
+                } => Action::Deconstruct {
+                    structure: entity,
+                    name: structure,
+                    x,
+                    y,
+                    direction,
+                },
+                _ => continue,
+            };
+
+            match replay.take() {
+                Some(pending) if pending.applied().is_done_by(&done) => {
+                    history.replace(pending.action.structure(), done.structure());
+                    let action = match pending.undoing {
+                        true => done.inverse(),
+                        false => done,
+                    };
+                    history.replayed(Replay {
+                        action,
+                        undoing: pending.undoing,
+                    });
+                }
+                pending => {
+                    replay = pending;
+                    history.record(done);
+                }
+            }
+        }
+
+        if let Some(pending) = replay {
+            let is_being_built = match pending.applied() {
+                Action::Place { name, x, y, .. } => {
+                    (&ghosts, &positions).join().any(|(ghost, position)| {
+                        ghost.structure == name && (position.x, position.y) == (x, y)
+                    })
+                }
+                _ => false,
+            };
+
+            match is_being_built {
+                true => history.replaying = Some(pending),
+                false => history.failed(pending),
+            }
+        }
+    }
+}
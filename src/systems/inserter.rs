@@ -0,0 +1,257 @@
+use bracket_lib::color::DARKRED;
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::clock::Clock;
+use crate::components::items::{fits_into, BlocksMovement, Capacity, Weight};
+use crate::components::power::{Burner, Fuel};
+use crate::components::rails::Cart;
+use crate::components::signals::Disabled;
+use crate::components::structures::{Container, Facing, Furnace, Inserter, Machine, Structure};
+use crate::components::workers::Worker;
+use crate::map::{is_tile_walkable, xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::systems::craft::{find_recipe, is_smelted, Recipe, RECIPES};
+use crate::{InBackpack, Item, Name, Position, Renderable, BLACK, RGB};
+
+/// Inserters stop feeding a burner once it holds this many fuel items.
+const MAX_BURNER_FUEL: usize = 5;
+
+pub struct InserterSystem {}
+
+impl<'a> System<'a> for InserterSystem {
+    type SystemData = (
+        ReadExpect<'a, Clock>,
+        ReadExpect<'a, Vec<TileType>>,
+        Entities<'a>,
+        WriteStorage<'a, Inserter>,
+        ReadStorage<'a, Facing>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Structure>,
+        ReadStorage<'a, Container>,
+        ReadStorage<'a, Capacity>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, BlocksMovement>,
+        WriteStorage<'a, Renderable>,
+        ReadStorage<'a, Machine>,
+        ReadStorage<'a, Burner>,
+        ReadStorage<'a, Fuel>,
+        ReadStorage<'a, Furnace>,
+        ReadStorage<'a, Disabled>,
+        ReadStorage<'a, Cart>,
+        ReadStorage<'a, Worker>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            clock,
+            map,
+            entities,
+            mut inserters,
+            facings,
+            mut positions,
+            mut backpack,
+            items,
+            names,
+            structures,
+            containers,
+            capacities,
+            weights,
+            blockers,
+            mut renderables,
+            machines,
+            burners,
+            fuels,
+            furnaces,
+            disabled,
+            carts,
+            workers,
+        ) = data;
+
+        for (entity, inserter, facing, _) in
+            (&entities, &mut inserters, &facings, !&disabled).join()
+        {
+            if !clock.every(inserter.interval) {
+                continue;
+            }
+
+            let (back, front) = match positions.get(entity) {
+                None => continue,
+                Some(position) => (
+                    facing.back(position.x, position.y),
+                    facing.front(position.x, position.y),
+                ),
+            };
+
+            // Carts are only loaded and unloaded while they wait at a station, and workers look
+            // after their own inventory.
+            let container_at = |tile: (i32, i32)| -> Option<Entity> {
+                (&entities, &containers, &positions, !&workers)
+                    .join()
+                    .find(|(container, _, position, _)| {
+                        (position.x, position.y) == tile
+                            && carts.get(*container).is_none_or(|cart| cart.is_waiting)
+                    })
+                    .map(|(container, _, _, _)| container)
+            };
+            let is_wanted = |item: Entity| match &inserter.filter {
+                None => true,
+                Some(filter) => names.get(item).is_some_and(|name| &name.name == filter),
+            };
+            // Machines only give away what their recipe does not consume, furnaces what they can
+            // not smelt and burners what they can not burn.
+            let is_output = |container: Entity, item: Entity| {
+                if burners.contains(container) && fuels.contains(item) {
+                    return false;
+                }
+                if furnaces.contains(container) {
+                    return names.get(item).is_none_or(|name| !is_smelted(&name.name));
+                }
+
+                let recipe = machines
+                    .get(container)
+                    .map(|machine| machine.recipe.and_then(find_recipe));
+
+                match (recipe, names.get(item)) {
+                    (Some(Some(recipe)), Some(name)) => !recipe.requires(&name.name),
+                    _ => true,
+                }
+            };
+
+            let source = container_at(back);
+            let item = match source {
+                Some(container) => (&entities, &backpack)
+                    .join()
+                    .find(|(item, pack)| {
+                        pack.owner == container && is_wanted(*item) && is_output(container, *item)
+                    })
+                    .map(|(item, _)| item),
+                None => (&entities, &items, &positions, !&structures)
+                    .join()
+                    .find(|(item, data, position, _)| {
+                        data.can_be_picked && (position.x, position.y) == back && is_wanted(*item)
+                    })
+                    .map(|(item, _, _, _)| item),
+            };
+
+            let item = match item {
+                None => {
+                    inserter.stalled = false;
+                    if let Some(render) = renderables.get_mut(entity) {
+                        render.bg = RGB::named(BLACK);
+                    }
+                    continue;
+                }
+                Some(item) => item,
+            };
+
+            let target = container_at(front);
+            let accepts = match target {
+                Some(container) => {
+                    // What a machine may be fed: any smelting input for furnaces, otherwise the
+                    // ingredients of its configured recipe.
+                    let recipes =
+                        machines
+                            .get(container)
+                            .map(|machine| match furnaces.contains(container) {
+                                true => RECIPES.iter().filter(|recipe| recipe.smelting).collect(),
+                                false => machine.recipe.and_then(find_recipe).into_iter().collect(),
+                            });
+
+                    fits_into(container, item, &backpack, &capacities, &weights)
+                        && accepts(
+                            container, item, recipes, &burners, &fuels, &backpack, &names,
+                        )
+                }
+                None => {
+                    let (x, y) = front;
+                    let is_on_map = x >= 0 && y >= 0 && x < WIDTH as i32 && y < HEIGHT as i32;
+                    let is_taken = (&positions, &blockers)
+                        .join()
+                        .any(|(position, _)| (position.x, position.y) == front)
+                        || (&items, &positions, !&structures)
+                            .join()
+                            .any(|(_, position, _)| (position.x, position.y) == front);
+
+                    is_on_map && is_tile_walkable(map[xy_to_idx(x, y)]) && !is_taken
+                }
+            };
+
+            inserter.stalled = !accepts;
+            if accepts {
+                match target {
+                    Some(container) => {
+                        positions.remove(item);
+                        backpack
+                            .insert(item, InBackpack { owner: container })
+                            .expect("unable to insert item");
+                    }
+                    None => {
+                        backpack.remove(item);
+                        positions
+                            .insert(
+                                item,
+                                Position {
+                                    x: front.0,
+                                    y: front.1,
+                                },
+                            )
+                            .expect("unable to drop item");
+                    }
+                }
+            }
+
+            if let Some(render) = renderables.get_mut(entity) {
+                render.bg = match inserter.stalled {
+                    true => RGB::named(DARKRED),
+                    false => RGB::named(BLACK),
+                };
+            }
+        }
+    }
+}
+
+/// Burners only take a few fuel items. Machines only take ingredients of their recipe, furnaces
+/// anything they can smelt, and at most two batches worth of each. Anything else takes whatever
+/// fits.
+fn accepts(
+    container: Entity,
+    item: Entity,
+    recipes: Option<Vec<&Recipe>>,
+    burners: &ReadStorage<Burner>,
+    fuels: &ReadStorage<Fuel>,
+    backpack: &WriteStorage<InBackpack>,
+    names: &ReadStorage<Name>,
+) -> bool {
+    if burners.contains(container) && (fuels.contains(item) || recipes.is_none()) {
+        let held = (backpack, fuels)
+            .join()
+            .filter(|(pack, _)| pack.owner == container)
+            .count();
+
+        return fuels.contains(item) && held < MAX_BURNER_FUEL;
+    }
+
+    let recipes = match recipes {
+        None => return true,
+        Some(recipes) => recipes,
+    };
+    let name = match names.get(item) {
+        None => return false,
+        Some(name) => name,
+    };
+
+    let needed: i32 = recipes
+        .iter()
+        .flat_map(|recipe| recipe.requirements.iter())
+        .filter(|requirement| requirement.item_name == name.name)
+        .map(|requirement| requirement.amount * 2)
+        .sum();
+    let held = (backpack, names)
+        .join()
+        .filter(|(pack, held)| pack.owner == container && held.name == name.name)
+        .count() as i32;
+
+    held < needed
+}
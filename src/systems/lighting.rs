@@ -0,0 +1,84 @@
+use bracket_lib::prelude::{field_of_view, Algorithm2D, BaseMap, Point};
+use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect};
+
+use crate::clock::Clock;
+use crate::components::lighting::{Light, LightMap};
+use crate::components::power::{Burner, Heat, PowerConsumer};
+use crate::map::{xy_to_idx, TileType, HEIGHT, WIDTH};
+use crate::Position;
+
+/// The map as seen by light, which walls stop.
+struct Sight<'a>(&'a [TileType]);
+
+impl BaseMap for Sight<'_> {
+    fn is_opaque(&self, idx: usize) -> bool {
+        self.0[idx] == TileType::Wall
+    }
+}
+
+impl Algorithm2D for Sight<'_> {
+    fn dimensions(&self) -> Point {
+        Point::new(WIDTH, HEIGHT)
+    }
+}
+
+pub struct LightingSystem {}
+
+impl<'a> System<'a> for LightingSystem {
+    type SystemData = (
+        ReadExpect<'a, Clock>,
+        ReadExpect<'a, Vec<TileType>>,
+        WriteExpect<'a, LightMap>,
+        Entities<'a>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Light>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, Burner>,
+        ReadStorage<'a, PowerConsumer>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (clock, map, mut light_map, entities, positions, lights, heats, burners, consumers) =
+            data;
+
+        let daylight = clock.daylight();
+        light_map
+            .levels
+            .iter_mut()
+            .for_each(|level| *level = daylight);
+
+        let is_burning = |entity| burners.get(entity).is_none_or(|burner| burner.is_burning());
+        let is_powered = |entity| {
+            consumers
+                .get(entity)
+                .is_none_or(|consumer| consumer.satisfaction > 0.)
+        };
+
+        let mut sources = vec![];
+        for (entity, position) in (&entities, &positions).join() {
+            let radius = match (lights.get(entity), heats.get(entity)) {
+                (Some(light), _) => light.radius,
+                (None, Some(heat)) if burners.contains(entity) => heat.light_radius,
+                _ => continue,
+            };
+
+            if is_burning(entity) && is_powered(entity) && radius > 0 {
+                sources.push((Point::new(position.x, position.y), radius));
+            }
+        }
+
+        let sight = Sight(&map);
+        for (center, radius) in sources {
+            for tile in field_of_view(center, radius, &sight)
+                .into_iter()
+                .filter(|tile| sight.in_bounds(*tile))
+            {
+                let distance = ((tile.x - center.x).pow(2) + (tile.y - center.y).pow(2)) as f32;
+                let brightness = 1. - distance.sqrt() / (radius + 1) as f32;
+
+                let level = &mut light_map.levels[xy_to_idx(tile.x, tile.y)];
+                *level = level.max(brightness);
+            }
+        }
+    }
+}
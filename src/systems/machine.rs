@@ -0,0 +1,150 @@
+use bracket_lib::random::RandomNumberGenerator;
+use specs::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage,
+};
+
+use crate::components::fluids::FluidBox;
+use crate::components::power::{Burner, Heat, PowerConsumer};
+use crate::components::research::Research;
+use crate::components::signals::Disabled;
+use crate::components::structures::{Furnace, Machine};
+use crate::events::{Event, Events};
+use crate::spawner::crafted;
+use crate::systems::craft::{consume_materials, find_recipe, has_materials, is_near_fire, RECIPES};
+use crate::systems::research::is_unlocked;
+use crate::{InBackpack, Name, Position};
+
+/// A machine stops once it holds this many finished items that were not taken out.
+pub const MAX_MACHINE_OUTPUT: usize = 10;
+
+pub struct MachineSystem {}
+
+impl<'a> System<'a> for MachineSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Machine>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, FluidBox>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, PowerConsumer>,
+        ReadStorage<'a, Burner>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, Furnace>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadExpect<'a, Research>,
+        WriteExpect<'a, Events>,
+        ReadStorage<'a, Disabled>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut machines,
+            positions,
+            backpack,
+            names,
+            mut boxes,
+            lazy,
+            consumers,
+            burners,
+            heats,
+            furnaces,
+            mut rng,
+            research,
+            mut events,
+            disabled,
+        ) = data;
+
+        for (entity, machine, position, _) in
+            (&entities, &mut machines, &positions, !&disabled).join()
+        {
+            let is_furnace = furnaces.contains(entity);
+
+            // Furnaces pick whichever smelting recipe their ore allows before each batch.
+            if is_furnace && machine.progress.is_none() {
+                machine.recipe = RECIPES
+                    .iter()
+                    .find(|recipe| {
+                        recipe.smelting
+                            && is_unlocked(recipe.result_item_name, &research)
+                            && has_materials(entity, recipe, &backpack, &names)
+                    })
+                    .map(|recipe| recipe.result_item_name);
+            }
+
+            let recipe = match machine.recipe.and_then(find_recipe) {
+                Some(recipe) if recipe.is_made_by(is_furnace) => recipe,
+                _ => continue,
+            };
+            let speed = match (burners.get(entity), consumers.get(entity)) {
+                (Some(burner), _) if burner.is_burning() => 1.,
+                (Some(_), _) => 0.,
+                (None, Some(consumer)) => consumer.satisfaction,
+                (None, None) => 1.,
+            };
+
+            match machine.progress {
+                None => {
+                    let has_fluids = recipe.fluid_requirements.iter().all(|requirement| {
+                        boxes.get(entity).is_some_and(|fluid_box| {
+                            fluid_box.fluid == Some(requirement.fluid)
+                                && fluid_box.volume >= requirement.amount
+                        })
+                    });
+
+                    let has_fire = !recipe.needs_fire
+                        || is_near_fire(position.x, position.y, &positions, &burners, &heats);
+
+                    // Starting does not wait for power: a started machine is what makes the
+                    // generators on its grid burn fuel.
+                    if !has_fire || !has_fluids || !has_materials(entity, recipe, &backpack, &names)
+                    {
+                        continue;
+                    }
+
+                    consume_materials(entity, recipe, &entities, &backpack, &names, &mut events);
+                    recipe.fluid_requirements.iter().for_each(|requirement| {
+                        boxes.get_mut(entity).unwrap().drain(requirement.amount);
+                    });
+
+                    machine.progress = Some(0.);
+                }
+                Some(progress) if progress >= recipe.craft_ticks as f32 => {
+                    let outputs = (&backpack, &names)
+                        .join()
+                        .filter(|(pack, name)| pack.owner == entity && !recipe.requires(&name.name))
+                        .count();
+                    let has_fluid_room = recipe.fluid_results.iter().all(|result| {
+                        boxes.get(entity).is_some_and(|fluid_box| {
+                            fluid_box.can_hold(result.fluid)
+                                && fluid_box.volume + result.amount <= fluid_box.capacity
+                        })
+                    });
+
+                    if outputs >= MAX_MACHINE_OUTPUT || !has_fluid_room {
+                        continue;
+                    }
+
+                    recipe.roll_results(&mut rng).iter().for_each(|result| {
+                        crafted(result, lazy.create_entity(&entities), entity);
+                        events.emit(Event::Crafted {
+                            item: result.to_string(),
+                            by: entity,
+                        });
+                    });
+                    recipe.fluid_results.iter().for_each(|result| {
+                        boxes
+                            .get_mut(entity)
+                            .unwrap()
+                            .fill(result.fluid, result.amount);
+                    });
+
+                    machine.progress = None;
+                }
+                Some(progress) => machine.progress = Some(progress + speed),
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use specs::{Entities, Entity, LazyUpdate, Read, ReadExpect, System, WriteExpect};
+
+use crate::clock::{Clock, TICKS_PER_MINUTE};
+use crate::components::milestones::Milestones;
+use crate::components::research::Research;
+use crate::events::{Event, Events};
+use crate::spawner::crafted;
+use crate::Log;
+
+pub enum Condition {
+    Craft {
+        item: &'static str,
+        count: u32,
+    },
+    Harvest {
+        item: &'static str,
+        count: u32,
+    },
+    Build {
+        structure: &'static str,
+        count: u32,
+    },
+    Research {
+        technology: &'static str,
+    },
+    /// Make `per_minute` of `item` within one minute, by hand, by machines or by harvesting.
+    ProductionRate {
+        item: &'static str,
+        per_minute: u32,
+    },
+    FluidNetwork {
+        members: u32,
+    },
+}
+
+impl Condition {
+    pub fn target(&self) -> u32 {
+        match self {
+            Condition::Craft { count, .. }
+            | Condition::Harvest { count, .. }
+            | Condition::Build { count, .. } => *count,
+            Condition::Research { .. } => 1,
+            Condition::ProductionRate { per_minute, .. } => *per_minute,
+            Condition::FluidNetwork { members } => *members,
+        }
+    }
+}
+
+pub enum Reward {
+    Items(&'static str, u32),
+    Technology(&'static str),
+}
+
+/// Something to work towards, listed in the objectives panel until it is reached.
+pub struct Goal {
+    pub name: &'static str,
+    pub condition: Condition,
+    pub rewards: &'static [Reward],
+}
+
+pub const GOALS: &[Goal] = &[
+    Goal {
+        name: "First Axe",
+        condition: Condition::Craft {
+            item: "Flint Axe",
+            count: 1,
+        },
+        rewards: &[Reward::Technology("Stone Tools")],
+    },
+    Goal {
+        name: "Lumberjack",
+        condition: Condition::Harvest {
+            item: "Wooden Stick",
+            count: 20,
+        },
+        rewards: &[Reward::Technology("Woodworking")],
+    },
+    Goal {
+        name: "Fire Starter",
+        condition: Condition::Build {
+            structure: "Fire Pit",
+            count: 1,
+        },
+        rewards: &[Reward::Items("Charcoal", 2)],
+    },
+    Goal {
+        name: "Smith",
+        condition: Condition::Craft {
+            item: "Copper Ingot",
+            count: 10,
+        },
+        rewards: &[Reward::Items("Iron Ingot", 4)],
+    },
+    Goal {
+        name: "Plumber",
+        condition: Condition::FluidNetwork { members: 4 },
+        rewards: &[Reward::Items("Pipe", 4)],
+    },
+    Goal {
+        name: "Stick Factory",
+        condition: Condition::ProductionRate {
+            item: "Wooden Stick",
+            per_minute: 100,
+        },
+        rewards: &[Reward::Items("Lab", 1)],
+    },
+    Goal {
+        name: "Scholar",
+        condition: Condition::Research {
+            technology: "Power",
+        },
+        rewards: &[Reward::Items("Lamp", 2)],
+    },
+];
+
+pub struct MilestoneSystem {}
+
+impl<'a> System<'a> for MilestoneSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Clock>,
+        ReadExpect<'a, Events>,
+        WriteExpect<'a, Milestones>,
+        WriteExpect<'a, Research>,
+        WriteExpect<'a, Log>,
+        Entities<'a>,
+        Read<'a, LazyUpdate>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (player, clock, events, mut milestones, mut research, mut log, entities, lazy) = data;
+
+        events
+            .this_tick
+            .iter()
+            .filter_map(|event| event.produced())
+            .for_each(|item| milestones.recent.push_back((clock.tick, item.to_string())));
+        while milestones
+            .recent
+            .front()
+            .is_some_and(|(tick, _)| tick + TICKS_PER_MINUTE <= clock.tick)
+        {
+            milestones.recent.pop_front();
+        }
+
+        for goal in GOALS.iter() {
+            if milestones.is_completed(goal.name) {
+                continue;
+            }
+
+            let previous = milestones.progress_of(goal.name);
+            let counted = |matches: &dyn Fn(&Event) -> bool| {
+                previous
+                    + events
+                        .this_tick
+                        .iter()
+                        .filter(|event| matches(event))
+                        .count() as u32
+            };
+
+            let progress = match &goal.condition {
+                Condition::Craft { item, .. } => counted(
+                    &|event| matches!(event, Event::Crafted { item: made, .. } if made == item),
+                ),
+                Condition::Harvest { item, .. } => counted(
+                    &|event| matches!(event, Event::Harvested { item: got, .. } if got == item),
+                ),
+                Condition::Build { structure, .. } => counted(
+                    &|event| matches!(event, Event::Built { structure: built, .. } if built == structure),
+                ),
+                Condition::Research { technology } => research.is_completed(technology) as u32,
+                Condition::ProductionRate { item, .. } => milestones
+                    .recent
+                    .iter()
+                    .filter(|(_, made)| made == item)
+                    .count() as u32,
+                Condition::FluidNetwork { .. } => events
+                    .this_tick
+                    .iter()
+                    .filter_map(|event| match event {
+                        Event::FluidNetworkGrown { members } => Some(*members as u32),
+                        _ => None,
+                    })
+                    .fold(previous, u32::max),
+            };
+            milestones.progress.insert(goal.name, progress);
+
+            if progress < goal.condition.target() {
+                continue;
+            }
+
+            milestones.completed.push(goal.name);
+            log.log(format!("milestone reached: {}", goal.name));
+
+            goal.rewards.iter().for_each(|reward| match reward {
+                Reward::Items(item, amount) => {
+                    (0..*amount)
+                        .for_each(|_| crafted(item, lazy.create_entity(&entities), *player));
+                    log.log(format!("you receive {} {}", amount, item));
+                }
+                Reward::Technology(technology) => {
+                    if !research.is_completed(technology) {
+                        research.completed.push(technology);
+                        log.log(format!("you learn {}", technology));
+                    }
+                    if research.current == Some(*technology) {
+                        research.current = None;
+                        research.progress = 0.;
+                    }
+                }
+            });
+        }
+    }
+}
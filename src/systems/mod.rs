@@ -1,2 +1,29 @@
+pub mod belt;
+pub mod bottlenecks;
+pub mod build;
+pub mod burner;
+pub mod construction;
 pub mod craft;
+pub mod deconstruct;
+pub mod encumbrance;
+pub mod fire_pit;
+pub mod fluid;
+pub mod ghost;
+pub mod growth;
+pub mod harvest;
+pub mod history;
+pub mod inserter;
+pub mod lighting;
+pub mod machine;
+pub mod milestones;
 pub mod pickup;
+pub mod planting;
+pub mod power;
+pub mod rails;
+pub mod regrowth;
+pub mod research;
+pub mod signals;
+pub mod statistics;
+pub mod transfer;
+pub mod tunnel;
+pub mod workers;
@@ -1,6 +1,6 @@
 use specs::{Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
 
-use crate::components::items::{InBackpack, PickupQueue};
+use crate::components::items::{fits_into, Capacity, InBackpack, PickupQueue, Weight};
 use crate::{Log, Name, Position};
 
 pub struct PickupSystem {}
@@ -13,12 +13,40 @@ impl<'a> System<'a> for PickupSystem {
         WriteStorage<'a, Position>,
         ReadStorage<'a, Name>,
         WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, Capacity>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (player, mut log, mut wants_pickup, mut positions, names, mut backpack) = data;
+        let (
+            player,
+            mut log,
+            mut wants_pickup,
+            mut positions,
+            names,
+            mut backpack,
+            weights,
+            capacities,
+        ) = data;
 
         for pickup in wants_pickup.join() {
+            if !fits_into(
+                pickup.collected_by,
+                pickup.item,
+                &backpack,
+                &capacities,
+                &weights,
+            ) {
+                if pickup.collected_by == *player {
+                    log.log(format!(
+                        "the {} does not fit in your backpack",
+                        names.get(pickup.item).unwrap()
+                    ))
+                }
+
+                continue;
+            }
+
             positions.remove(pickup.item);
 
             backpack
@@ -0,0 +1,102 @@
+use specs::{
+    Builder, Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System,
+    WriteExpect, WriteStorage,
+};
+
+use crate::components::farming::{plant_by_seed, Crop, PlantQueue};
+use crate::components::items::ResourceNode;
+use crate::components::structures::Structure;
+use crate::map::{xy_to_idx, TileType};
+use crate::{to_cp437, Log, Name, Position, Renderable, RGB};
+
+pub struct PlantingSystem {}
+
+impl<'a> System<'a> for PlantingSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Log>,
+        ReadExpect<'a, Vec<TileType>>,
+        Entities<'a>,
+        WriteStorage<'a, PlantQueue>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Crop>,
+        ReadStorage<'a, ResourceNode>,
+        ReadStorage<'a, Structure>,
+        Read<'a, LazyUpdate>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player,
+            mut log,
+            map,
+            entities,
+            mut wants_plant,
+            positions,
+            names,
+            crops,
+            nodes,
+            structures,
+            lazy,
+        ) = data;
+
+        for (planter, planting, position) in (&entities, &wants_plant, &positions).join() {
+            let (seed_name, plant) = match names.get(planting.seed) {
+                None => continue,
+                Some(name) => match plant_by_seed(&name.name) {
+                    None => continue,
+                    Some(plant) => (name.to_string(), plant),
+                },
+            };
+
+            if map[xy_to_idx(position.x, position.y)] != TileType::Floor {
+                if planter == *player {
+                    log.log("nothing grows here");
+                }
+                continue;
+            }
+
+            let is_taken = (&positions, &crops)
+                .join()
+                .any(|(other, _)| other.x == position.x && other.y == position.y)
+                || (&positions, &nodes)
+                    .join()
+                    .any(|(other, _)| other.x == position.x && other.y == position.y)
+                || (&positions, &structures)
+                    .join()
+                    .any(|(other, _)| other.x == position.x && other.y == position.y);
+            if is_taken {
+                if planter == *player {
+                    log.log("there is no room to plant here");
+                }
+                continue;
+            }
+
+            let stage = &plant.stages[0];
+            lazy.create_entity(&entities)
+                .with(Position {
+                    x: position.x,
+                    y: position.y,
+                })
+                .with(Renderable::new(
+                    to_cp437(stage.glyph),
+                    RGB::from_u8(stage.color.0, stage.color.1, stage.color.2),
+                ))
+                .with(Name::new(&format!("{} {}", plant.name, stage.name)))
+                .with(Crop {
+                    plant: plant.name,
+                    stage: 0,
+                    progress: 0.,
+                })
+                .build();
+            entities.delete(planting.seed).expect("should plant seed");
+
+            if planter == *player {
+                log.log(format!("you plant the {}", seed_name.to_lowercase()));
+            }
+        }
+
+        wants_plant.clear();
+    }
+}
@@ -0,0 +1,130 @@
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::clock::Clock;
+
+use crate::components::power::{
+    Burner, Generator, PowerConsumer, PowerGrid, PowerGrids, PowerPole, Solar,
+};
+use crate::components::structures::Machine;
+use crate::Position;
+
+fn distance(a: (i32, i32), b: (i32, i32)) -> i32 {
+    (a.0 - b.0).abs().max((a.1 - b.1).abs())
+}
+
+pub struct PowerSystem {}
+
+impl<'a> System<'a> for PowerSystem {
+    type SystemData = (
+        WriteExpect<'a, PowerGrids>,
+        Entities<'a>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, PowerPole>,
+        ReadStorage<'a, Generator>,
+        ReadStorage<'a, Burner>,
+        WriteStorage<'a, PowerConsumer>,
+        ReadStorage<'a, Machine>,
+        ReadStorage<'a, Solar>,
+        ReadExpect<'a, Clock>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut grids,
+            entities,
+            positions,
+            poles,
+            generators,
+            burners,
+            mut consumers,
+            machines,
+            solars,
+            clock,
+        ) = data;
+
+        let pole_list: Vec<(Entity, (i32, i32), i32)> = (&entities, &poles, &positions)
+            .join()
+            .map(|(entity, pole, position)| (entity, (position.x, position.y), pole.radius))
+            .collect();
+
+        // Poles that reach each other form one grid.
+        let mut grid_of: Vec<Option<usize>> = vec![None; pole_list.len()];
+        let mut grid_poles: Vec<Vec<usize>> = vec![];
+        for start in 0..pole_list.len() {
+            if grid_of[start].is_some() {
+                continue;
+            }
+
+            let grid = grid_poles.len();
+            let mut members = vec![];
+            let mut frontier = vec![start];
+            grid_of[start] = Some(grid);
+
+            while let Some(current) = frontier.pop() {
+                members.push(current);
+                let (_, at, radius) = pole_list[current];
+
+                for (other, (_, other_at, other_radius)) in pole_list.iter().enumerate() {
+                    let reaches = distance(at, *other_at) <= radius.max(*other_radius);
+                    if grid_of[other].is_none() && reaches {
+                        grid_of[other] = Some(grid);
+                        frontier.push(other);
+                    }
+                }
+            }
+
+            grid_poles.push(members);
+        }
+
+        let grid_at = |x: i32, y: i32| -> Option<usize> {
+            pole_list
+                .iter()
+                .enumerate()
+                .find(|(_, (_, at, radius))| distance(*at, (x, y)) <= *radius)
+                .and_then(|(index, _)| grid_of[index])
+        };
+
+        grids.grids = grid_poles
+            .iter()
+            .map(|members| PowerGrid {
+                poles: members.iter().map(|index| pole_list[*index].0).collect(),
+                generators: vec![],
+                production: 0.,
+                consumption: 0.,
+            })
+            .collect();
+
+        for (entity, generator, position) in (&entities, &generators, &positions).join() {
+            let output = match (burners.get(entity), solars.contains(entity)) {
+                (Some(burner), _) if burner.is_burning() => generator.output,
+                (None, true) => generator.output * clock.daylight(),
+                _ => 0.,
+            };
+
+            if let Some(grid) = grid_at(position.x, position.y) {
+                grids.grids[grid].generators.push(entity);
+                grids.grids[grid].production += output;
+            }
+        }
+
+        let mut connections = vec![];
+        for (entity, consumer, position) in (&entities, &consumers, &positions).join() {
+            let grid = grid_at(position.x, position.y);
+            let is_working = machines
+                .get(entity)
+                .is_none_or(|machine| machine.progress.is_some());
+
+            if let (Some(grid), true) = (grid, is_working) {
+                grids.grids[grid].consumption += consumer.draw;
+            }
+
+            connections.push((entity, grid));
+        }
+
+        for (entity, grid) in connections.into_iter() {
+            let consumer = consumers.get_mut(entity).unwrap();
+
+            consumer.satisfaction = grid.map_or(0., |grid| grids.grids[grid].satisfaction());
+        }
+    }
+}
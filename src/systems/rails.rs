@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bracket_lib::color::{GREEN, RED};
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::clock::Clock;
+use crate::components::items::{backpack_load, Capacity, Weight};
+use crate::components::rails::{Cart, Rail, RailSignal, Station, Until, MAX_IDLE_WAIT};
+use crate::{to_cp437, FontCharType, InBackpack, Log, Position, Renderable, RGB};
+
+type Tile = (i32, i32);
+
+const NEIGHBORS: [Tile; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Double-line box drawing glyph for a rail, indexed by a north, east, south, west bit mask.
+fn rail_glyph(connections: usize) -> FontCharType {
+    let glyph = match connections {
+        0b0010 | 0b1000 | 0b1010 => '═',
+        0b0011 => '╚',
+        0b0110 => '╔',
+        0b1100 => '╗',
+        0b1001 => '╝',
+        0b0111 => '╠',
+        0b1110 => '╦',
+        0b1101 => '╣',
+        0b1011 => '╩',
+        0b1111 => '╬',
+        _ => '║',
+    };
+
+    to_cp437(glyph)
+}
+
+/// Glyph of a cart moving by `dx`, `dy`.
+fn cart_glyph((dx, dy): Tile) -> FontCharType {
+    let glyph = match (dx, dy) {
+        (1, _) => '►',
+        (-1, _) => '◄',
+        (_, -1) => '▲',
+        _ => '▼',
+    };
+
+    to_cp437(glyph)
+}
+
+/// The first name from A to Z, then A2 to Z2 and so on, that no station has yet.
+fn free_station_name(taken: &HashSet<String>) -> String {
+    (1..)
+        .flat_map(|round| {
+            ('A'..='Z').map(move |letter| match round {
+                1 => letter.to_string(),
+                round => format!("{}{}", letter, round),
+            })
+        })
+        .find(|name| !taken.contains(name))
+        .unwrap()
+}
+
+/// The shortest way along `rails` from `start` to any of `goals`, without `start` itself.
+pub fn find_route(start: Tile, goals: &[Tile], rails: &HashMap<Tile, Entity>) -> Option<Vec<Tile>> {
+    let mut came_from: HashMap<Tile, Tile> = HashMap::new();
+    let mut frontier = VecDeque::from([start]);
+
+    while let Some((x, y)) = frontier.pop_front() {
+        if goals.contains(&(x, y)) {
+            let mut route = vec![(x, y)];
+            while let Some(previous) = came_from.get(route.last().unwrap()) {
+                route.push(*previous);
+            }
+            route.pop();
+            route.reverse();
+
+            return Some(route);
+        }
+
+        for (dx, dy) in NEIGHBORS.iter() {
+            let next = (x + dx, y + dy);
+            if rails.contains_key(&next) && next != start && !came_from.contains_key(&next) {
+                came_from.insert(next, (x, y));
+                frontier.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Names new stations, splits the rails into blocks at their signals and moves carts along their
+/// schedules, one tile at a time.
+pub struct RailSystem {}
+
+impl<'a> System<'a> for RailSystem {
+    type SystemData = (
+        ReadExpect<'a, Clock>,
+        WriteExpect<'a, Log>,
+        Entities<'a>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Rail>,
+        WriteStorage<'a, Station>,
+        WriteStorage<'a, RailSignal>,
+        WriteStorage<'a, Cart>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, Capacity>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            clock,
+            mut log,
+            entities,
+            mut positions,
+            rails,
+            mut stations,
+            mut signals,
+            mut carts,
+            backpack,
+            weights,
+            capacities,
+            mut renderables,
+        ) = data;
+
+        let tiles: HashMap<Tile, Entity> = (&entities, &positions, &rails)
+            .join()
+            .map(|(entity, position, _)| ((position.x, position.y), entity))
+            .collect();
+        if tiles.is_empty() {
+            return;
+        }
+
+        for (entity, position, _, _, _) in
+            (&entities, &positions, &rails, !&stations, !&signals).join()
+        {
+            let connections = NEIGHBORS
+                .iter()
+                .enumerate()
+                .filter(|(_, (dx, dy))| tiles.contains_key(&(position.x + dx, position.y + dy)))
+                .fold(0, |mask, (bit, _)| mask | (1 << bit));
+
+            if let Some(render) = renderables.get_mut(entity) {
+                render.glyph = rail_glyph(connections);
+            }
+        }
+
+        let mut taken: HashSet<String> = stations
+            .join()
+            .filter_map(|station| station.name.clone())
+            .collect();
+        for (station, _) in (&mut stations, &positions).join() {
+            if station.name.is_none() {
+                let name = free_station_name(&taken);
+                log.log(format!("the new station is called {}", name));
+                taken.insert(name.clone());
+                station.name = Some(name);
+            }
+        }
+
+        // Signals are the borders between blocks and belong to none of them.
+        let signal_tiles: HashSet<Tile> = (&positions, &signals)
+            .join()
+            .map(|(position, _)| (position.x, position.y))
+            .collect();
+        let mut block_of: HashMap<Tile, usize> = HashMap::new();
+        let mut blocks = 0;
+        for start in tiles.keys().filter(|tile| !signal_tiles.contains(*tile)) {
+            if block_of.contains_key(start) {
+                continue;
+            }
+
+            let mut frontier = vec![*start];
+            block_of.insert(*start, blocks);
+            while let Some((x, y)) = frontier.pop() {
+                for (dx, dy) in NEIGHBORS.iter() {
+                    let next = (x + dx, y + dy);
+                    let is_same_block = tiles.contains_key(&next)
+                        && !signal_tiles.contains(&next)
+                        && !block_of.contains_key(&next);
+                    if is_same_block {
+                        block_of.insert(next, blocks);
+                        frontier.push(next);
+                    }
+                }
+            }
+            blocks += 1;
+        }
+
+        // Blocks are reserved by direction: the signal each cart in a block will leave it by.
+        // Carts leaving the same way may follow each other into a block, while a cart with no
+        // signal ahead holds its block alone. A cart waiting on a signal holds the block it is
+        // about to enter.
+        let exit_of = |route: &[Tile]| {
+            route
+                .iter()
+                .find(|tile| signal_tiles.contains(*tile))
+                .copied()
+        };
+        let mut cart_tiles: HashSet<Tile> = HashSet::new();
+        let mut reserved: HashMap<usize, Vec<Option<Tile>>> = HashMap::new();
+        for (cart, position) in (&carts, &positions).join() {
+            let tile = (position.x, position.y);
+            cart_tiles.insert(tile);
+
+            let block = match signal_tiles.contains(&tile) {
+                true => cart.route.first().and_then(|next| block_of.get(next)),
+                false => block_of.get(&tile),
+            };
+            if let Some(block) = block {
+                reserved
+                    .entry(*block)
+                    .or_default()
+                    .push(exit_of(&cart.route));
+            }
+        }
+
+        let cargo = |cart: Entity| {
+            (&entities, &backpack)
+                .join()
+                .filter(|(_, pack)| pack.owner == cart)
+                .map(|(item, _)| item)
+                .collect::<Vec<Entity>>()
+        };
+        let station_tiles = |name: &str| {
+            (&positions, &stations)
+                .join()
+                .filter(|(_, station)| station.name.as_deref() == Some(name))
+                .map(|(position, _)| (position.x, position.y))
+                .collect::<Vec<Tile>>()
+        };
+
+        let mut moves: Vec<(Entity, Tile)> = vec![];
+        for (entity, cart, position) in (&entities, &mut carts, &positions).join() {
+            let tile = (position.x, position.y);
+            if !clock.every(cart.interval) || !tiles.contains_key(&tile) {
+                continue;
+            }
+
+            let stop = match cart.current() {
+                None => continue,
+                Some(stop) => stop.clone(),
+            };
+            let goals = station_tiles(&stop.station);
+
+            if goals.contains(&tile) {
+                let held = cargo(entity);
+                if !cart.is_waiting || cart.held.0 != held.len() {
+                    cart.held = (held.len(), clock.tick);
+                }
+
+                let is_idle = clock.tick >= cart.held.1 + MAX_IDLE_WAIT;
+                let is_done = is_idle
+                    || match stop.until {
+                        Until::Empty => held.is_empty(),
+                        Until::Full => {
+                            let load = backpack_load(entity, &backpack, &weights);
+                            capacities.get(entity).is_some_and(|capacity| {
+                                held.iter()
+                                    .filter_map(|item| weights.get(*item))
+                                    .any(|weight| !load.fits(weight, capacity))
+                            })
+                        }
+                    };
+
+                cart.is_waiting = !is_done;
+                if is_done {
+                    cart.stop = (cart.stop + 1) % cart.schedule.len();
+                    cart.route.clear();
+                }
+                continue;
+            }
+            cart.is_waiting = false;
+
+            // Routes go stale when rails are taken down or the cart is put somewhere else.
+            let is_stale = cart.route.first().is_none_or(|(x, y)| {
+                (x - tile.0).abs() + (y - tile.1).abs() != 1 || !tiles.contains_key(&(*x, *y))
+            });
+            if is_stale {
+                match find_route(tile, &goals, &tiles) {
+                    None => {
+                        if !cart.is_lost {
+                            log.log(format!("a cart can not find a way to {}", stop.station));
+                        }
+                        cart.is_lost = true;
+                        cart.route.clear();
+                        continue;
+                    }
+                    Some(route) => {
+                        cart.is_lost = false;
+                        cart.route = route;
+                    }
+                }
+            }
+
+            let next = cart.route[0];
+            if cart_tiles.contains(&next) {
+                continue;
+            }
+            if signal_tiles.contains(&next) {
+                if let Some(ahead) = cart.route.get(1).and_then(|tile| block_of.get(tile)) {
+                    let exit = exit_of(&cart.route[1..]);
+                    let exits = reserved.entry(*ahead).or_default();
+                    let is_clear = exits.is_empty()
+                        || exit.is_some() && exits.iter().all(|other| *other == exit);
+                    if !is_clear {
+                        continue;
+                    }
+                    exits.push(exit);
+                }
+            }
+
+            cart_tiles.remove(&tile);
+            cart_tiles.insert(next);
+            cart.route.remove(0);
+            moves.push((entity, next));
+
+            if let Some(render) = renderables.get_mut(entity) {
+                render.glyph = cart_glyph((next.0 - tile.0, next.1 - tile.1));
+            }
+        }
+
+        for (entity, (x, y)) in moves.into_iter() {
+            positions
+                .insert(entity, Position { x, y })
+                .expect("unable to move cart");
+        }
+
+        for (entity, signal, position) in (&entities, &mut signals, &positions).join() {
+            signal.is_clear = NEIGHBORS
+                .iter()
+                .filter_map(|(dx, dy)| block_of.get(&(position.x + dx, position.y + dy)))
+                .all(|block| !reserved.contains_key(block));
+
+            if let Some(render) = renderables.get_mut(entity) {
+                render.fg = match signal.is_clear {
+                    true => RGB::named(GREEN),
+                    false => RGB::named(RED),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use specs::{Builder, World, WorldExt};
+
+    use super::*;
+
+    fn rails(tiles: &[Tile]) -> HashMap<Tile, Entity> {
+        let mut world = World::new();
+        tiles
+            .iter()
+            .map(|tile| (*tile, world.create_entity().build()))
+            .collect()
+    }
+
+    #[test]
+    fn route_follows_the_rails_without_the_start() {
+        let rails = rails(&[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]);
+
+        assert_eq!(
+            find_route((0, 0), &[(2, 2)], &rails),
+            Some(vec![(1, 0), (2, 0), (2, 1), (2, 2)])
+        );
+        assert_eq!(find_route((1, 0), &[(1, 0)], &rails), Some(vec![]));
+    }
+
+    #[test]
+    fn route_takes_the_shortest_way_to_the_nearest_goal() {
+        // A loop with a short and a long side between (0, 0) and (2, 0).
+        let rails = rails(&[
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (2, 1),
+            (4, 0),
+        ]);
+
+        assert_eq!(
+            find_route((0, 0), &[(2, 0)], &rails),
+            Some(vec![(1, 0), (2, 0)])
+        );
+        assert_eq!(
+            find_route((0, 2), &[(2, 0), (1, 2)], &rails),
+            Some(vec![(1, 2)])
+        );
+    }
+
+    #[test]
+    fn no_route_to_unconnected_rails() {
+        let rails = rails(&[(0, 0), (1, 0), (3, 0)]);
+
+        assert_eq!(find_route((0, 0), &[(3, 0)], &rails), None);
+    }
+}
@@ -0,0 +1,41 @@
+use specs::{Entities, Join, ReadExpect, System, WriteStorage};
+
+use crate::clock::Clock;
+use crate::components::items::{Regrowing, ResourceNode};
+
+pub struct RegrowthSystem {}
+
+impl<'a> System<'a> for RegrowthSystem {
+    type SystemData = (
+        ReadExpect<'a, Clock>,
+        Entities<'a>,
+        WriteStorage<'a, ResourceNode>,
+        WriteStorage<'a, Regrowing>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (clock, entities, mut nodes, mut regrowing) = data;
+
+        let mut regrown = vec![];
+        for (entity, node, growth) in (&entities, &mut nodes, &mut regrowing).join() {
+            let interval = match node.regrow_interval {
+                None => continue,
+                Some(interval) => interval,
+            };
+
+            if clock.tick < growth.since + interval {
+                continue;
+            }
+
+            node.remaining = (node.remaining + 1).min(node.max_yield);
+            growth.since = clock.tick;
+            if node.remaining == node.max_yield {
+                regrown.push(entity);
+            }
+        }
+
+        regrown.into_iter().for_each(|entity| {
+            regrowing.remove(entity);
+        });
+    }
+}
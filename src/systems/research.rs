@@ -0,0 +1,325 @@
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::research::{Lab, Research, ResearchQueue};
+use crate::events::Events;
+use crate::systems::craft::{consume_requirements, has_requirements, Requirement};
+use crate::{InBackpack, Log, Name, Position};
+
+/// A node of the tech tree. Researching it costs `cost` from the backpack or from a lab, takes
+/// `research_ticks`, and unlocks the recipes named in `unlocks`.
+pub struct Technology {
+    pub name: &'static str,
+    pub prerequisites: &'static [&'static str],
+    pub cost: &'static [Requirement],
+    pub research_ticks: u64,
+    pub unlocks: &'static [&'static str],
+}
+
+impl Technology {
+    /// Whether every prerequisite of this technology has been researched.
+    pub fn is_available(&self, research: &Research) -> bool {
+        self.prerequisites
+            .iter()
+            .all(|prerequisite| research.is_completed(prerequisite))
+    }
+}
+
+pub const TECHNOLOGIES: &[Technology] = &[
+    Technology {
+        name: "Woodworking",
+        prerequisites: &[],
+        cost: &[Requirement {
+            item_name: "Wooden Stick",
+            amount: 5,
+        }],
+        research_ticks: 600,
+        unlocks: &["Chest", "Torch"],
+    },
+    Technology {
+        name: "Stone Tools",
+        prerequisites: &[],
+        cost: &[Requirement {
+            item_name: "Flint",
+            amount: 5,
+        }],
+        research_ticks: 600,
+        unlocks: &["Flint Pickaxe", "Fire-Hardened Spear"],
+    },
+    Technology {
+        name: "Logistics",
+        prerequisites: &["Woodworking"],
+        cost: &[
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 10,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 5,
+            },
+        ],
+        research_ticks: 1200,
+        unlocks: &["Belt", "Inserter"],
+    },
+    Technology {
+        name: "Smelting",
+        prerequisites: &["Stone Tools"],
+        cost: &[Requirement {
+            item_name: "Copper Ore",
+            amount: 5,
+        }],
+        research_ticks: 1200,
+        unlocks: &["Furnace", "Copper Ingot", "Iron Ingot"],
+    },
+    Technology {
+        name: "Metal Tools",
+        prerequisites: &["Smelting"],
+        cost: &[Requirement {
+            item_name: "Copper Ingot",
+            amount: 4,
+        }],
+        research_ticks: 1800,
+        unlocks: &["Copper Axe", "Copper Pickaxe", "Iron Axe", "Iron Pickaxe"],
+    },
+    Technology {
+        name: "Belt Routing",
+        prerequisites: &["Logistics"],
+        cost: &[
+            Requirement {
+                item_name: "Belt",
+                amount: 4,
+            },
+            Requirement {
+                item_name: "Flint",
+                amount: 10,
+            },
+        ],
+        research_ticks: 1800,
+        unlocks: &["Splitter", "Merger", "Filter Splitter", "Underground Belt"],
+    },
+    Technology {
+        name: "Railways",
+        prerequisites: &["Belt Routing", "Metal Tools"],
+        cost: &[
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 20,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 10,
+            },
+        ],
+        research_ticks: 3600,
+        unlocks: &["Rail", "Rail Station", "Rail Signal", "Cart"],
+    },
+    Technology {
+        name: "Fluid Handling",
+        prerequisites: &["Logistics"],
+        cost: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 10,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 10,
+            },
+        ],
+        research_ticks: 1800,
+        unlocks: &["Pipe", "Pump", "Tank", "Pipe Tunnel"],
+    },
+    Technology {
+        name: "Automation",
+        prerequisites: &["Logistics"],
+        cost: &[
+            Requirement {
+                item_name: "Flint",
+                amount: 10,
+            },
+            Requirement {
+                item_name: "Wooden Stick",
+                amount: 10,
+            },
+        ],
+        research_ticks: 1800,
+        unlocks: &["Assembler", "Mortar", "Charcoal"],
+    },
+    Technology {
+        name: "Workers",
+        prerequisites: &["Automation", "Metal Tools"],
+        cost: &[
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 10,
+            },
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 10,
+            },
+        ],
+        research_ticks: 3000,
+        unlocks: &["Worker"],
+    },
+    Technology {
+        name: "Power",
+        prerequisites: &["Automation"],
+        cost: &[Requirement {
+            item_name: "Charcoal",
+            amount: 5,
+        }],
+        research_ticks: 2400,
+        unlocks: &["Burner Generator", "Power Pole"],
+    },
+    Technology {
+        name: "Circuit Logic",
+        prerequisites: &["Automation", "Metal Tools"],
+        cost: &[
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 10,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 5,
+            },
+        ],
+        research_ticks: 2400,
+        unlocks: &["Wire", "Arithmetic Combinator", "Decider Combinator"],
+    },
+    Technology {
+        name: "Electric Lighting",
+        prerequisites: &["Power", "Metal Tools"],
+        cost: &[
+            Requirement {
+                item_name: "Copper Ingot",
+                amount: 5,
+            },
+            Requirement {
+                item_name: "Iron Ingot",
+                amount: 2,
+            },
+        ],
+        research_ticks: 3000,
+        unlocks: &["Lamp", "Solar Panel"],
+    },
+];
+
+pub fn find_technology(name: &str) -> Option<&'static Technology> {
+    TECHNOLOGIES
+        .iter()
+        .find(|technology| technology.name == name)
+}
+
+/// Whether the recipe for `item_name` can be crafted, which it can unless a technology that has
+/// not been researched yet unlocks it.
+pub fn is_unlocked(item_name: &str, research: &Research) -> bool {
+    TECHNOLOGIES
+        .iter()
+        .filter(|technology| technology.unlocks.contains(&item_name))
+        .all(|technology| research.is_completed(technology.name))
+}
+
+pub struct ResearchSystem {}
+
+impl<'a> System<'a> for ResearchSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Log>,
+        WriteExpect<'a, Research>,
+        Entities<'a>,
+        WriteStorage<'a, ResearchQueue>,
+        ReadStorage<'a, Lab>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+        WriteExpect<'a, Events>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player,
+            mut log,
+            mut research,
+            entities,
+            mut wants_research,
+            labs,
+            positions,
+            backpack,
+            names,
+            mut events,
+        ) = data;
+
+        for queued in wants_research.join() {
+            let technology = match find_technology(queued.technology) {
+                None => continue,
+                Some(technology) => technology,
+            };
+
+            if research.is_completed(technology.name) {
+                log.log(format!("you already know {}", technology.name));
+                continue;
+            }
+            if let Some(current) = research.current {
+                log.log(format!("you are still researching {}", current));
+                continue;
+            }
+            if !technology.is_available(&research) {
+                log.log(format!(
+                    "{} needs {} first",
+                    technology.name,
+                    technology.prerequisites.join(", ")
+                ));
+                continue;
+            }
+
+            // The backpack pays first, any placed lab holding everything otherwise.
+            let payer = std::iter::once(*player)
+                .chain((&entities, &labs, &positions).join().map(|(lab, _, _)| lab))
+                .find(|owner| has_requirements(*owner, technology.cost, &backpack, &names));
+
+            match payer {
+                None => log.log(format!(
+                    "you lack the materials to research {}",
+                    technology.name
+                )),
+                Some(payer) => {
+                    consume_requirements(
+                        payer,
+                        technology.cost,
+                        &entities,
+                        &backpack,
+                        &names,
+                        &mut events,
+                    );
+                    research.current = Some(technology.name);
+                    research.progress = 0.;
+                    log.log(format!("you start researching {}", technology.name));
+                }
+            }
+        }
+
+        wants_research.clear();
+
+        let technology = match research.current.and_then(find_technology) {
+            None => return,
+            Some(technology) => technology,
+        };
+
+        // Every placed lab adds as much speed as researching alone.
+        let speed = 1. + (&labs, &positions).join().count() as f32;
+        research.progress += speed;
+
+        if research.progress >= technology.research_ticks as f32 {
+            research.completed.push(technology.name);
+            research.current = None;
+            research.progress = 0.;
+
+            log.log(format!(
+                "you finish researching {}, which unlocks {}",
+                technology.name,
+                technology.unlocks.join(", ")
+            ));
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+
+use specs::{Entities, Entity, Join, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::fluids::{FluidBox, Tank};
+use crate::components::signals::{
+    ArithmeticCombinator, Controlled, DeciderCombinator, Disabled, SignalNetwork, SignalNetworks,
+    Signals, Wire,
+};
+use crate::components::structures::{Container, Facing};
+use crate::{to_cp437, FontCharType, InBackpack, Name, Position, Renderable};
+
+const NEIGHBORS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Single-line box drawing glyph for a wire, indexed by a north, east, south, west bit mask.
+fn wire_glyph(connections: usize) -> FontCharType {
+    let glyph = match connections {
+        0b0001 | 0b0100 | 0b0101 => '│',
+        0b0010 | 0b1000 | 0b1010 => '─',
+        0b0011 => '└',
+        0b0110 => '┌',
+        0b1100 => '┐',
+        0b1001 => '┘',
+        0b0111 => '├',
+        0b1110 => '┬',
+        0b1101 => '┤',
+        0b1011 => '┴',
+        0b1111 => '┼',
+        _ => '·',
+    };
+
+    to_cp437(glyph)
+}
+
+/// Finds the signal networks, sums what is broadcast on them, runs the combinators and switches
+/// controlled entities on and off.
+pub struct SignalSystem {}
+
+impl<'a> System<'a> for SignalSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, SignalNetworks>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Wire>,
+        ReadStorage<'a, Container>,
+        ReadStorage<'a, Tank>,
+        ReadStorage<'a, FluidBox>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Facing>,
+        ReadStorage<'a, Controlled>,
+        WriteStorage<'a, Disabled>,
+        WriteStorage<'a, ArithmeticCombinator>,
+        WriteStorage<'a, DeciderCombinator>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut networks,
+            positions,
+            wires,
+            containers,
+            tanks,
+            boxes,
+            backpack,
+            names,
+            facings,
+            controlled,
+            mut disabled,
+            mut arithmetic,
+            mut deciders,
+            mut renderables,
+        ) = data;
+
+        let tiles: HashMap<(i32, i32), Entity> = (&entities, &positions, &wires)
+            .join()
+            .map(|(entity, position, _)| ((position.x, position.y), entity))
+            .collect();
+
+        for (entity, position, _) in (&entities, &positions, &wires).join() {
+            let connections = NEIGHBORS
+                .iter()
+                .enumerate()
+                .filter(|(_, (dx, dy))| tiles.contains_key(&(position.x + dx, position.y + dy)))
+                .fold(0, |mask, (bit, _)| mask | (1 << bit));
+
+            if let Some(render) = renderables.get_mut(entity) {
+                render.glyph = wire_glyph(connections);
+            }
+        }
+
+        networks.networks.clear();
+        let mut network_at: HashMap<(i32, i32), usize> = HashMap::new();
+        for start in tiles.keys() {
+            if network_at.contains_key(start) {
+                continue;
+            }
+
+            let index = networks.networks.len();
+            let mut members = vec![];
+            let mut frontier = vec![*start];
+            network_at.insert(*start, index);
+            while let Some((x, y)) = frontier.pop() {
+                members.push(tiles[&(x, y)]);
+
+                for (dx, dy) in NEIGHBORS.iter() {
+                    let next = (x + dx, y + dy);
+                    if tiles.contains_key(&next) && !network_at.contains_key(&next) {
+                        network_at.insert(next, index);
+                        frontier.push(next);
+                    }
+                }
+            }
+
+            networks.networks.push(SignalNetwork {
+                members,
+                signals: Signals::new(),
+            });
+        }
+
+        // Everything that broadcasts or listens joins the networks of the wires next to it.
+        // Combinators only join through the wires behind and in front of them.
+        for (entity, position, _) in (&entities, &positions, !&wires).join() {
+            let (x, y) = (position.x, position.y);
+            let touching: Vec<(i32, i32)> = match facings.get(entity) {
+                Some(facing) if arithmetic.contains(entity) || deciders.contains(entity) => {
+                    vec![facing.back(x, y), facing.front(x, y)]
+                }
+                _ if containers.contains(entity)
+                    || tanks.contains(entity)
+                    || controlled.contains(entity) =>
+                {
+                    NEIGHBORS.iter().map(|(dx, dy)| (x + dx, y + dy)).collect()
+                }
+                _ => continue,
+            };
+
+            let joined: HashSet<usize> = touching
+                .iter()
+                .filter_map(|tile| network_at.get(tile).copied())
+                .collect();
+            joined
+                .into_iter()
+                .for_each(|index| networks.networks[index].members.push(entity));
+        }
+
+        let mut contents: HashMap<Entity, Signals> = HashMap::new();
+        for (pack, name) in (&backpack, &names).join() {
+            if containers.contains(pack.owner) {
+                *contents
+                    .entry(pack.owner)
+                    .or_default()
+                    .entry(name.name.clone())
+                    .or_default() += 1;
+            }
+        }
+
+        let output_at = |entity: Entity| -> Option<(i32, i32)> {
+            let position = positions.get(entity)?;
+            Some(facings.get(entity)?.front(position.x, position.y))
+        };
+        for (index, network) in networks.networks.iter_mut().enumerate() {
+            let mut signals = Signals::new();
+
+            for member in network.members.iter() {
+                if let Some(held) = contents.get(member) {
+                    held.iter().for_each(|(name, count)| {
+                        *signals.entry(name.clone()).or_default() += count;
+                    });
+                }
+                if let (Some(_), Some(fluid_box)) = (tanks.get(*member), boxes.get(*member)) {
+                    if let Some(fluid) = fluid_box.fluid {
+                        *signals.entry(fluid.name().to_string()).or_default() +=
+                            fluid_box.volume as i32;
+                    }
+                }
+
+                let outputs_here = output_at(*member)
+                    .and_then(|tile| network_at.get(&tile))
+                    .is_some_and(|at| *at == index);
+                if !outputs_here {
+                    continue;
+                }
+                if let Some(combinator) = arithmetic.get(*member) {
+                    *signals.entry(combinator.output.clone()).or_default() += combinator.result;
+                }
+                if let Some(combinator) = deciders.get(*member) {
+                    *signals.entry(combinator.output.clone()).or_default() += combinator.result;
+                }
+            }
+
+            signals.retain(|_, value| *value != 0);
+            network.signals = signals;
+        }
+
+        let empty = Signals::new();
+        let input_of = |entity: Entity| -> &Signals {
+            positions
+                .get(entity)
+                .zip(facings.get(entity))
+                .and_then(|(position, facing)| network_at.get(&facing.back(position.x, position.y)))
+                .map_or(&empty, |index| &networks.networks[*index].signals)
+        };
+        for (entity, combinator) in (&entities, &mut arithmetic).join() {
+            let left = input_of(entity)
+                .get(&combinator.signal)
+                .copied()
+                .unwrap_or(0);
+            combinator.result = combinator.operation.apply(left, combinator.value);
+        }
+        for (entity, combinator) in (&entities, &mut deciders).join() {
+            combinator.result = combinator.condition.holds(input_of(entity)) as i32;
+        }
+
+        let released: Vec<Entity> = (&entities, &disabled, !&controlled)
+            .join()
+            .map(|(entity, _, _)| entity)
+            .collect();
+        released.into_iter().for_each(|entity| {
+            disabled.remove(entity);
+        });
+        for (entity, control) in (&entities, &controlled).join() {
+            if control.condition.holds(&networks.signals_of(entity)) {
+                disabled.remove(entity);
+            } else {
+                disabled
+                    .insert(entity, Disabled {})
+                    .expect("unable to disable entity");
+            }
+        }
+    }
+}
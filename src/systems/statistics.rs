@@ -0,0 +1,55 @@
+use specs::{Entity, ReadExpect, ReadStorage, System, WriteExpect};
+
+use crate::clock::Clock;
+use crate::components::statistics::{Flow, Record, Statistics, WINDOWS};
+use crate::events::{Event, Events};
+use crate::Name;
+
+pub struct StatisticsSystem {}
+
+impl<'a> System<'a> for StatisticsSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Clock>,
+        ReadExpect<'a, Events>,
+        WriteExpect<'a, Statistics>,
+        ReadStorage<'a, Name>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (player, clock, events, mut statistics, names) = data;
+
+        // Machines are told apart by their entity id, the player goes by name alone.
+        let source = |entity: Entity| {
+            let name = names
+                .get(entity)
+                .map_or("Unknown".to_string(), |name| name.name.clone());
+
+            match entity == *player {
+                true => name,
+                false => format!("{} #{}", name, entity.id()),
+            }
+        };
+
+        events.this_tick.iter().for_each(|event| {
+            let (item, flow, amount, by) = match event {
+                Event::Crafted { item, by } | Event::Harvested { item, by } => {
+                    (item, Flow::Produced, 1, by)
+                }
+                Event::Consumed { item, amount, by } => (item, Flow::Consumed, *amount, by),
+                _ => return,
+            };
+
+            statistics.record(Record {
+                tick: clock.tick,
+                source: source(*by),
+                item: item.clone(),
+                flow,
+                amount,
+            });
+        });
+
+        let longest = WINDOWS.iter().map(|window| window.ticks).max().unwrap_or(0);
+        statistics.forget_before(clock.tick.saturating_sub(longest));
+    }
+}
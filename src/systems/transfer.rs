@@ -0,0 +1,55 @@
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::items::{fits_into, Capacity, InBackpack, TransferQueue, Weight};
+use crate::{Log, Name};
+
+pub struct TransferSystem {}
+
+impl<'a> System<'a> for TransferSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Log>,
+        Entities<'a>,
+        WriteStorage<'a, TransferQueue>,
+        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, Capacity>,
+        ReadStorage<'a, Name>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (player, mut log, entities, mut transfers, mut backpack, weights, capacities, names) =
+            data;
+
+        let mut full = None;
+
+        for (item, transfer) in (&entities, &transfers).join() {
+            if !backpack.contains(item) {
+                continue;
+            }
+
+            // Workers moving things around are left to notice a full container themselves.
+            let is_by_player = transfer.to == *player
+                || backpack.get(item).is_some_and(|pack| pack.owner == *player);
+
+            if !fits_into(transfer.to, item, &backpack, &capacities, &weights) {
+                if is_by_player {
+                    full = Some(transfer.to);
+                }
+                continue;
+            }
+
+            backpack
+                .insert(item, InBackpack { owner: transfer.to })
+                .expect("unable to transfer item");
+        }
+
+        match full {
+            None => {}
+            Some(receiver) if receiver == *player => log.log("your backpack is full"),
+            Some(receiver) => log.log(format!("the {} is full", names.get(receiver).unwrap())),
+        }
+
+        transfers.clear();
+    }
+}
@@ -0,0 +1,105 @@
+use specs::{Entities, Entity, Join, ReadStorage, System, WriteStorage};
+
+use crate::components::structures::{Direction, Facing, Tunnel};
+use crate::{Name, Position};
+
+struct End<'a> {
+    entity: Entity,
+    tile: (i32, i32),
+    direction: Direction,
+    name: &'a str,
+    reach: i32,
+}
+
+/// Pairs up tunnel ends as they are placed, and splits pairs whose ends were taken down, turned
+/// apart or moved out of reach.
+pub struct TunnelSystem {}
+
+impl<'a> System<'a> for TunnelSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Tunnel>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Facing>,
+        ReadStorage<'a, Name>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut tunnels, positions, facings, names) = data;
+
+        let mut ends: Vec<End> = (&entities, &tunnels, &positions, &facings, &names)
+            .join()
+            .map(|(entity, tunnel, position, facing, name)| End {
+                entity,
+                tile: (position.x, position.y),
+                direction: facing.direction,
+                name: name.name.as_str(),
+                reach: tunnel.reach,
+            })
+            .collect();
+        let end_of = |entity: Entity| ends.iter().find(|end| end.entity == entity);
+
+        for (entity, tunnel) in (&entities, &mut tunnels).join() {
+            let pair = tunnel
+                .partner
+                .and_then(|partner| Some((end_of(entity)?, end_of(partner)?)));
+            let is_intact = pair.is_some_and(|(end, partner)| {
+                let (entrance, exit) = match tunnel.is_entrance {
+                    true => (end, partner),
+                    false => (partner, end),
+                };
+                let is_in_reach = Tunnel::search_behind(
+                    exit.tile.0,
+                    exit.tile.1,
+                    exit.direction,
+                    exit.reach,
+                    |tile| (tile == entrance.tile).then_some(entrance.entity),
+                )
+                .is_some();
+
+                end.direction == partner.direction && end.name == partner.name && is_in_reach
+            });
+            if !is_intact {
+                tunnel.partner = None;
+                tunnel.is_entrance = false;
+            }
+        }
+
+        // Ends further back along their direction pair first, so a line of ends pairs up the same
+        // way whichever was placed first.
+        ends.sort_by_key(|end| {
+            let (delta_x, delta_y) = end.direction.delta();
+            (end.tile.0 * delta_x + end.tile.1 * delta_y, end.entity.id())
+        });
+
+        for end in ends.iter() {
+            if tunnels.get(end.entity).unwrap().partner.is_some() {
+                continue;
+            }
+
+            let (x, y) = end.tile;
+            let behind = Tunnel::search_behind(x, y, end.direction, end.reach, |tile| {
+                ends.iter()
+                    .find(|other| {
+                        other.tile == tile
+                            && other.direction == end.direction
+                            && other.name == end.name
+                            && tunnels.get(other.entity).unwrap().partner.is_none()
+                    })
+                    .map(|other| other.entity)
+            });
+            let entrance = match behind {
+                None => continue,
+                Some((entrance, _)) => entrance,
+            };
+
+            let entrance_end = tunnels.get_mut(entrance).unwrap();
+            entrance_end.partner = Some(end.entity);
+            entrance_end.is_entrance = true;
+
+            let exit_end = tunnels.get_mut(end.entity).unwrap();
+            exit_end.partner = Some(entrance);
+            exit_end.is_entrance = false;
+        }
+    }
+}
@@ -0,0 +1,802 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bracket_lib::color::{GREY, LIGHTGREEN, RED};
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::clock::Clock;
+use crate::components::blueprints::Ghost;
+use crate::components::items::{
+    backpack_load, Axe, BlocksMovement, Capacity, CraftQueue, HarvestQueue, Item, Pickaxe,
+    PickupQueue, ResourceNode, Tier, Tool, TransferQueue, Weight,
+};
+use crate::components::power::{Burner, Heat};
+use crate::components::rails::Cart;
+use crate::components::research::Research;
+use crate::components::stockpiles::{Stockpiles, TILE_STACK};
+use crate::components::structures::{Chest, Structure};
+use crate::components::workers::{Job, Worker};
+use crate::map::TileType;
+use crate::player::{blocked_tiles, is_walkable};
+use crate::systems::construction::wanted_materials;
+use crate::systems::craft::{find_recipe, has_materials, is_near_fire, materials};
+use crate::systems::research::is_unlocked;
+use crate::{InBackpack, Name, Position, Renderable, RGB};
+
+type Tile = (i32, i32);
+
+/// A construction site, where it stands and what it wants brought.
+type Site = (Entity, Tile, Vec<(String, usize)>);
+
+/// What a worker does once it stands next to where the work is done.
+enum Work {
+    Harvest(Entity),
+    Take(Vec<Entity>),
+    Deposit(Entity, Vec<Entity>),
+    Craft(&'static str),
+    PickUp(Vec<Entity>),
+    Drop(Vec<Entity>, Tile),
+}
+
+fn is_next_to((x, y): Tile, (other_x, other_y): Tile) -> bool {
+    (x - other_x).abs() <= 1 && (y - other_y).abs() <= 1
+}
+
+/// The shortest walk from `start` to a tile where `is_goal` holds, without `start` itself.
+fn find_path(
+    start: Tile,
+    is_goal: impl Fn(Tile) -> bool,
+    is_walkable: impl Fn(Tile) -> bool,
+) -> Option<Vec<Tile>> {
+    let mut came_from: HashMap<Tile, Tile> = HashMap::new();
+    let mut frontier = VecDeque::from([start]);
+
+    while let Some((x, y)) = frontier.pop_front() {
+        if is_goal((x, y)) {
+            let mut path = vec![(x, y)];
+            while let Some(previous) = came_from.get(path.last().unwrap()) {
+                path.push(*previous);
+            }
+            path.pop();
+            path.reverse();
+
+            return Some(path);
+        }
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let next = (x + dx, y + dy);
+                if next != start && !came_from.contains_key(&next) && is_walkable(next) {
+                    came_from.insert(next, (x, y));
+                    frontier.push_back(next);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Has every worker pick the most important job it can get on with, walk over to where it is
+/// done and do it through the same queues the player uses. Workers with nothing else to do carry
+/// materials to construction sites, or else loose items to the stockpile of the highest priority
+/// that takes them.
+pub struct WorkerSystem {}
+
+impl<'a> System<'a> for WorkerSystem {
+    type SystemData = (
+        ReadExpect<'a, Clock>,
+        ReadExpect<'a, Vec<TileType>>,
+        ReadExpect<'a, Research>,
+        ReadExpect<'a, Stockpiles>,
+        Entities<'a>,
+        WriteStorage<'a, Worker>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Structure>,
+        (
+            ReadStorage<'a, Cart>,
+            ReadStorage<'a, Ghost>,
+            ReadStorage<'a, Chest>,
+        ),
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, Capacity>,
+        ReadStorage<'a, ResourceNode>,
+        ReadStorage<'a, BlocksMovement>,
+        ReadStorage<'a, Axe>,
+        ReadStorage<'a, Pickaxe>,
+        ReadStorage<'a, Tier>,
+        ReadStorage<'a, Burner>,
+        ReadStorage<'a, Heat>,
+        WriteStorage<'a, HarvestQueue>,
+        WriteStorage<'a, TransferQueue>,
+        WriteStorage<'a, CraftQueue>,
+        WriteStorage<'a, PickupQueue>,
+        WriteStorage<'a, Renderable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            clock,
+            map,
+            research,
+            stockpiles,
+            entities,
+            mut workers,
+            mut positions,
+            mut backpack,
+            items,
+            structures,
+            (carts, ghosts, chests),
+            names,
+            weights,
+            capacities,
+            nodes,
+            blockers,
+            axes,
+            pickaxes,
+            tiers,
+            burners,
+            heats,
+            mut wants_harvest,
+            mut transfers,
+            mut wants_craft,
+            mut wants_pickup,
+            mut renderables,
+        ) = data;
+
+        let due: Vec<Entity> = (&entities, &workers, &positions)
+            .join()
+            .filter(|(_, worker, _)| clock.every(worker.interval))
+            .map(|(entity, _, _)| entity)
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        // Workers walk wherever the player can.
+        let blocked = blocked_tiles(&positions, &blockers);
+        let is_walkable = |tile: Tile| is_walkable(&map, &blocked, tile);
+
+        let tile_of = |entity: Entity| {
+            positions
+                .get(entity)
+                .map(|position| (position.x, position.y))
+        };
+        let name_of = |entity: Entity| {
+            names
+                .get(entity)
+                .map_or(String::new(), |name| name.to_string())
+        };
+        let held_by = |owner: Entity| -> Vec<Entity> {
+            (&entities, &backpack)
+                .join()
+                .filter(|(_, pack)| pack.owner == owner)
+                .map(|(item, _)| item)
+                .collect()
+        };
+        let fits = |owner: Entity, item: Entity| match (capacities.get(owner), weights.get(item)) {
+            (Some(capacity), Some(weight)) => {
+                backpack_load(owner, &backpack, &weights).fits(weight, capacity)
+            }
+            _ => true,
+        };
+        let can_harvest = |worker: Entity, node: &ResourceNode| {
+            let tool = match node.tool {
+                None => return true,
+                Some(tool) => tool,
+            };
+
+            (&entities, &backpack, &tiers)
+                .join()
+                .filter(|(item, pack, _)| {
+                    pack.owner == worker
+                        && match tool {
+                            Tool::Axe => axes.contains(*item),
+                            Tool::Pickaxe => pickaxes.contains(*item),
+                        }
+                })
+                .any(|(_, _, tier)| tier.level >= node.tier)
+        };
+        let deposit = |container: Entity, items: Vec<Entity>| match items.first() {
+            Some(item) if !fits(container, *item) => {
+                Err(format!("the {} is full", name_of(container)))
+            }
+            _ => Ok((container, Work::Deposit(container, items))),
+        };
+
+        // Where the job is done and what to do there, or why it can not be worked on now.
+        let plan = |worker: Entity,
+                    job: &Job,
+                    hauled: &[Entity]|
+         -> Result<(Entity, Work), String> {
+            let tile = tile_of(worker).unwrap_or_default();
+            let placed = |container: &Option<Entity>| container.filter(|c| positions.contains(*c));
+            let cargo: Vec<Entity> = held_by(worker)
+                .into_iter()
+                .filter(|item| {
+                    !axes.contains(*item) && !pickaxes.contains(*item) && !hauled.contains(item)
+                })
+                .collect();
+
+            match job {
+                Job::Gather { item, to } => {
+                    let held = cargo.iter().find(|held| name_of(**held) == *item);
+                    let drop_off = |reason: String| match placed(to) {
+                        Some(to) if !cargo.is_empty() => deposit(to, cargo.clone()),
+                        _ => Err(reason),
+                    };
+                    if held.is_some_and(|held| !fits(worker, *held)) {
+                        return drop_off("backpack full".to_string());
+                    }
+
+                    let distance = |(x, y): Tile| (x - tile.0).abs().max((y - tile.1).abs());
+                    let node = (&entities, &nodes, &positions)
+                        .join()
+                        .filter(|(_, node, _)| {
+                            node.yields == item && !node.is_depleted() && can_harvest(worker, node)
+                        })
+                        .min_by_key(|(_, _, position)| distance((position.x, position.y)));
+
+                    match node {
+                        Some((node, _, _)) => Ok((node, Work::Harvest(node))),
+                        None => drop_off(format!("no {} to gather", item)),
+                    }
+                }
+                Job::Haul { from, to } => {
+                    let (from, to) = match (placed(from), placed(to)) {
+                        (Some(from), Some(to)) => (from, to),
+                        _ => return Err("pick where to haul from and to".to_string()),
+                    };
+                    if !cargo.is_empty() {
+                        return deposit(to, cargo);
+                    }
+
+                    let contents = held_by(from);
+                    match contents.first() {
+                        None => Err(format!("the {} is empty", name_of(from))),
+                        Some(item) if !fits(worker, *item) => Err("backpack full".to_string()),
+                        Some(_) => Ok((from, Work::Take(contents))),
+                    }
+                }
+                Job::Craft { recipe, at } => {
+                    let at = placed(at).ok_or("pick where to craft")?;
+                    let recipe = match find_recipe(recipe) {
+                        Some(recipe) if is_unlocked(recipe.result_item_name, &research) => recipe,
+                        _ => return Err(format!("can not craft the {}", recipe)),
+                    };
+                    if recipe.smelting || recipe.needs_machine() {
+                        return Err(format!("can not craft the {}", recipe.result_item_name));
+                    }
+
+                    let made: Vec<Entity> = cargo
+                        .iter()
+                        .filter(|item| {
+                            let name = name_of(**item);
+                            name == recipe.result_item_name
+                                || recipe.byproducts.iter().any(|by| by.item_name == name)
+                        })
+                        .copied()
+                        .collect();
+                    if !made.is_empty() {
+                        return deposit(at, made);
+                    }
+                    if has_materials(worker, recipe, &backpack, &names) {
+                        return Ok((at, Work::Craft(recipe.result_item_name)));
+                    }
+
+                    let mut wanted = vec![];
+                    for (item_name, amount) in materials(recipe.requirements) {
+                        let held = cargo
+                            .iter()
+                            .filter(|item| name_of(**item) == item_name)
+                            .count();
+                        let missing = amount.saturating_sub(held);
+                        let stored: Vec<Entity> = held_by(at)
+                            .into_iter()
+                            .filter(|item| name_of(*item) == item_name)
+                            .take(missing)
+                            .collect();
+
+                        if stored.len() < missing {
+                            return Err(format!("the {} needs {}", name_of(at), item_name));
+                        }
+                        wanted.extend(stored);
+                    }
+
+                    Ok((at, Work::Take(wanted)))
+                }
+            }
+        };
+
+        // The walk to a tile next to one of `targets`, keeping to the path already being walked
+        // when it still leads there.
+        let walk = |tile: Tile, path: &[Tile], targets: &[Tile]| -> Option<Vec<Tile>> {
+            let is_goal = |step: Tile| targets.iter().any(|target| is_next_to(step, *target));
+            if is_goal(tile) {
+                return Some(vec![]);
+            }
+
+            let is_on_path = path
+                .first()
+                .is_some_and(|next| is_next_to(tile, *next) && *next != tile && is_walkable(*next))
+                && path.last().is_some_and(|last| is_goal(*last));
+            match is_on_path {
+                true => Some(path.to_vec()),
+                false => find_path(tile, is_goal, is_walkable),
+            }
+        };
+
+        // Loose items lie about on their own, not on belts, rails or other structures.
+        let built: HashSet<Tile> = (&positions, &structures, !&workers, !&carts)
+            .join()
+            .map(|(position, _, _, _)| (position.x, position.y))
+            .collect();
+        let loose: Vec<(Entity, Tile, String)> = (&entities, &items, &positions, &names)
+            .join()
+            .filter(|(_, item, position, _)| {
+                item.can_be_picked && !built.contains(&(position.x, position.y))
+            })
+            .map(|(entity, _, position, name)| (entity, (position.x, position.y), name.to_string()))
+            .collect();
+
+        // What lies on each stockpile tile and how many of it. Tiles of mixed items take no more.
+        let mut stock: HashMap<Tile, (String, usize)> = HashMap::new();
+        for (_, tile, name) in loose.iter() {
+            if stockpiles.zone_at(*tile).is_none() {
+                continue;
+            }
+
+            let (kind, count) = stock.entry(*tile).or_insert((name.clone(), 0));
+            match kind == name {
+                true => *count += 1,
+                false => *count = TILE_STACK,
+            }
+        }
+        let room_for = |stock: &HashMap<Tile, (String, usize)>, tile: Tile, item: &str| match stock
+            .get(&tile)
+        {
+            None => TILE_STACK,
+            Some((kind, count)) if kind == item => TILE_STACK.saturating_sub(*count),
+            Some(_) => 0,
+        };
+        // The priority of the zones that take `item` and have room for it, and their open tiles.
+        let destinations =
+            |stock: &HashMap<Tile, (String, usize)>, item: &str| -> Option<(u8, Vec<Tile>)> {
+                let mut best: Option<(u8, Vec<Tile>)> = None;
+                for zone in stockpiles.zones.iter().filter(|zone| zone.accepts(item)) {
+                    let open: Vec<Tile> = zone
+                        .tiles
+                        .iter()
+                        .filter(|tile| {
+                            is_walkable(**tile)
+                                && !built.contains(*tile)
+                                && room_for(stock, **tile, item) > 0
+                        })
+                        .copied()
+                        .collect();
+                    if open.is_empty() {
+                        continue;
+                    }
+
+                    match &mut best {
+                        Some((priority, tiles)) if *priority == zone.priority => tiles.extend(open),
+                        Some((priority, _)) if *priority > zone.priority => {}
+                        _ => best = Some((zone.priority, open)),
+                    }
+                }
+
+                best
+            };
+        let stored_priority = |tile: Tile, item: &str| {
+            stockpiles
+                .zone_at(tile)
+                .filter(|zone| zone.accepts(item))
+                .map_or(0, |zone| zone.priority)
+        };
+
+        // Materials are fetched from chests and from loose items, stockpiled or not.
+        let chest_tiles: Vec<(Entity, Tile)> = (&entities, &chests, &positions)
+            .join()
+            .map(|(chest, _, position)| (chest, (position.x, position.y)))
+            .collect();
+        let at_hand: HashSet<String> = loose
+            .iter()
+            .map(|(_, _, kind)| kind.clone())
+            .chain(
+                chest_tiles
+                    .iter()
+                    .flat_map(|(chest, _)| held_by(*chest))
+                    .map(&name_of),
+            )
+            .collect();
+
+        // What each construction site wants brought, and how much of it is not on its way yet.
+        let sites: Vec<Site> = (&entities, &ghosts, &positions)
+            .join()
+            .map(|(site, ghost, position)| {
+                let held: Vec<String> = held_by(site).into_iter().map(&name_of).collect();
+                let has_structure = at_hand.contains(&ghost.structure);
+                let wanted = wanted_materials(&ghost.structure, &held, has_structure);
+                (site, (position.x, position.y), wanted)
+            })
+            .collect();
+        let mut needed: HashMap<String, usize> = HashMap::new();
+        for (_, _, wanted) in sites.iter() {
+            for (kind, amount) in wanted.iter() {
+                *needed.entry(kind.clone()).or_insert(0) += amount;
+            }
+        }
+        for (worker, _) in (&workers, &positions).join() {
+            for item in worker
+                .delivering
+                .iter()
+                .filter(|item| backpack.contains(**item))
+            {
+                if let Some(amount) = needed.get_mut(&name_of(*item)) {
+                    *amount = amount.saturating_sub(1);
+                }
+            }
+        }
+
+        let mut moves: Vec<(Entity, Tile)> = vec![];
+        let mut drops: Vec<(Entity, Tile)> = vec![];
+        let mut claimed: HashSet<Entity> = HashSet::new();
+        for entity in due {
+            let tile = match tile_of(entity) {
+                None => continue,
+                Some(tile) => tile,
+            };
+            let worker = workers.get(entity).unwrap();
+            let mut hauling: Vec<Entity> = worker
+                .hauling
+                .iter()
+                .filter(|item| {
+                    backpack
+                        .get(**item)
+                        .is_some_and(|pack| pack.owner == entity)
+                })
+                .copied()
+                .collect();
+            let mut delivering: Vec<Entity> = worker
+                .delivering
+                .iter()
+                .filter(|item| {
+                    backpack
+                        .get(**item)
+                        .is_some_and(|pack| pack.owner == entity)
+                })
+                .copied()
+                .collect();
+
+            let mut reasons = vec![];
+            let mut chosen = None;
+            let mut is_supplying = false;
+
+            // Materials already fetched are brought to a site that wants them before anything
+            // else is done, or stockpiled once no site does.
+            if !delivering.is_empty() {
+                let kinds: Vec<String> = delivering.iter().map(|item| name_of(*item)).collect();
+                let wanting: Vec<&Site> = sites
+                    .iter()
+                    .filter(|(_, _, wanted)| wanted.iter().any(|(kind, _)| kinds.contains(kind)))
+                    .collect();
+                let site_tiles: Vec<Tile> = wanting.iter().map(|(_, site, _)| *site).collect();
+
+                match walk(tile, &worker.path, &site_tiles) {
+                    _ if wanting.is_empty() => hauling.append(&mut delivering),
+                    None => reasons.push("can not reach the construction site".to_string()),
+                    Some(path) => {
+                        let end = path.last().copied().unwrap_or(tile);
+                        let (site, _, wanted) = wanting
+                            .iter()
+                            .find(|(_, site, _)| is_next_to(end, *site))
+                            .unwrap();
+                        let brought: Vec<Entity> = wanted
+                            .iter()
+                            .flat_map(|(kind, amount)| {
+                                delivering
+                                    .iter()
+                                    .filter(move |item| name_of(**item) == *kind)
+                                    .take(*amount)
+                            })
+                            .copied()
+                            .collect();
+
+                        let target = format!("the {}", name_of(*site));
+                        chosen = Some((target, Work::Deposit(*site, brought), path));
+                    }
+                }
+            }
+
+            // Loose items already picked up are stored before anything else is done.
+            if let Some(first) = hauling.first().filter(|_| chosen.is_none()) {
+                let kind = name_of(*first);
+                match destinations(&stock, &kind) {
+                    None => reasons.push(format!("no room in the stockpiles for {}", kind)),
+                    Some((_, open)) => match walk(tile, &worker.path, &open) {
+                        None => reasons.push("can not reach a stockpile".to_string()),
+                        Some(path) => {
+                            let end = path.last().copied().unwrap_or(tile);
+                            let target = *open.iter().find(|open| is_next_to(end, **open)).unwrap();
+                            let zone = stockpiles.zone_at(target).unwrap();
+                            let stored: Vec<Entity> = hauling
+                                .iter()
+                                .filter(|item| name_of(**item) == kind)
+                                .take(room_for(&stock, target, &kind))
+                                .copied()
+                                .collect();
+
+                            chosen = Some((zone.name.clone(), Work::Drop(stored, target), path));
+                        }
+                    },
+                }
+            }
+
+            // The first job by priority that can be done and reached is the one worked on.
+            for index in worker.by_priority() {
+                if chosen.is_some() {
+                    break;
+                }
+
+                let carried = [hauling.clone(), delivering.clone()].concat();
+                let (target, work) = match plan(entity, &worker.jobs[index].job, &carried) {
+                    Err(reason) => {
+                        reasons.push(reason);
+                        continue;
+                    }
+                    Ok(planned) => planned,
+                };
+                match walk(tile, &worker.path, &[tile_of(target).unwrap()]) {
+                    None => reasons.push(format!("can not reach the {}", name_of(target))),
+                    Some(path) => chosen = Some((format!("the {}", name_of(target)), work, path)),
+                }
+            }
+
+            // Otherwise it fetches what construction sites still need from the nearest chest or
+            // loose items.
+            let is_empty_handed = hauling.is_empty() && delivering.is_empty();
+            if chosen.is_none() && is_empty_handed && needed.values().any(|amount| *amount > 0) {
+                let is_needed = |item: Entity, kind: &String| {
+                    !claimed.contains(&item) && needed.get(kind).is_some_and(|amount| *amount > 0)
+                };
+                let lying: Vec<&(Entity, Tile, String)> = loose
+                    .iter()
+                    .filter(|(item, _, kind)| is_needed(*item, kind) && fits(entity, *item))
+                    .collect();
+                let stored: Vec<(Entity, Tile, Vec<Entity>)> = chest_tiles
+                    .iter()
+                    .map(|(chest, spot)| {
+                        let items: Vec<Entity> = held_by(*chest)
+                            .into_iter()
+                            .filter(|item| is_needed(*item, &name_of(*item)))
+                            .collect();
+                        (*chest, *spot, items)
+                    })
+                    .filter(|(_, _, items)| !items.is_empty())
+                    .collect();
+                let spots: Vec<Tile> = lying
+                    .iter()
+                    .map(|(_, spot, _)| *spot)
+                    .chain(stored.iter().map(|(_, spot, _)| *spot))
+                    .collect();
+
+                // Takes no more of each kind than is needed.
+                let enough = |items: Vec<Entity>| {
+                    let mut counts: HashMap<String, usize> = HashMap::new();
+                    items
+                        .into_iter()
+                        .filter(|item| {
+                            let kind = name_of(*item);
+                            let count = counts.entry(kind.clone()).or_insert(0);
+                            *count += 1;
+                            *count <= needed[&kind]
+                        })
+                        .collect::<Vec<Entity>>()
+                };
+
+                if !spots.is_empty() {
+                    match walk(tile, &worker.path, &spots) {
+                        None => reasons.push("can not reach the materials".to_string()),
+                        Some(path) => {
+                            let end = path.last().copied().unwrap_or(tile);
+                            let near = |spot: &Tile| is_next_to(end, *spot);
+                            let work = match lying.iter().find(|(_, spot, _)| near(spot)) {
+                                Some((_, spot, kind)) => {
+                                    let items = lying
+                                        .iter()
+                                        .filter(|(_, other, name)| other == spot && name == kind)
+                                        .map(|(item, _, _)| *item)
+                                        .collect();
+                                    (format!("the {}", kind), Work::PickUp(enough(items)))
+                                }
+                                None => {
+                                    let (chest, _, items) =
+                                        stored.iter().find(|(_, spot, _)| near(spot)).unwrap();
+                                    let target = format!("the {}", name_of(*chest));
+                                    (target, Work::Take(enough(items.clone())))
+                                }
+                            };
+
+                            is_supplying = true;
+                            chosen = Some((work.0, work.1, path));
+                        }
+                    }
+                }
+            }
+
+            // Otherwise it picks up the nearest loose items a stockpile of a higher priority than
+            // the one they lie in has room for.
+            if chosen.is_none() && is_empty_handed {
+                let mut best: HashMap<&str, u8> = HashMap::new();
+                for (_, _, kind) in loose.iter() {
+                    if !best.contains_key(kind.as_str()) {
+                        let priority =
+                            destinations(&stock, kind).map_or(0, |(priority, _)| priority);
+                        best.insert(kind, priority);
+                    }
+                }
+
+                let wanted: Vec<&(Entity, Tile, String)> = loose
+                    .iter()
+                    .filter(|(item, spot, kind)| {
+                        !claimed.contains(item)
+                            && best[kind.as_str()] > stored_priority(*spot, kind)
+                            && fits(entity, *item)
+                    })
+                    .collect();
+                let spots: Vec<Tile> = wanted.iter().map(|(_, spot, _)| *spot).collect();
+
+                if !spots.is_empty() {
+                    match walk(tile, &worker.path, &spots) {
+                        None => reasons.push("can not reach the loose items".to_string()),
+                        Some(path) => {
+                            let end = path.last().copied().unwrap_or(tile);
+                            let (_, spot, kind) = wanted
+                                .iter()
+                                .find(|(_, spot, _)| is_next_to(end, *spot))
+                                .unwrap();
+                            let picked: Vec<Entity> = wanted
+                                .iter()
+                                .filter(|(_, other, name)| other == spot && name == kind)
+                                .map(|(item, _, _)| *item)
+                                .collect();
+
+                            chosen = Some((format!("the {}", kind), Work::PickUp(picked), path));
+                        }
+                    }
+                }
+            }
+
+            let (status, is_stuck, path) = match chosen {
+                None if reasons.is_empty() => ("idle".to_string(), false, vec![]),
+                None => (reasons.remove(0), true, vec![]),
+                Some((target, _, path)) if !path.is_empty() => {
+                    moves.push((entity, path[0]));
+                    let status = format!("walking to {}", target);
+                    (status, false, path[1..].to_vec())
+                }
+                Some((target, work, _)) => {
+                    let status = match work {
+                        Work::Harvest(node) => {
+                            wants_harvest
+                                .insert(entity, HarvestQueue { node })
+                                .expect("unable to harvest");
+                            format!("gathering {}", nodes.get(node).unwrap().yields)
+                        }
+                        Work::Take(items) => {
+                            if is_supplying {
+                                for item in items.iter() {
+                                    needed
+                                        .entry(name_of(*item))
+                                        .and_modify(|amount| *amount -= 1);
+                                }
+                                claimed.extend(items.iter());
+                                delivering.extend(items.iter());
+                            }
+                            items.into_iter().for_each(|item| {
+                                transfers
+                                    .insert(item, TransferQueue { to: entity })
+                                    .expect("unable to take item");
+                            });
+                            format!("taking from {}", target)
+                        }
+                        Work::Deposit(container, items) => {
+                            items.into_iter().for_each(|item| {
+                                transfers
+                                    .insert(item, TransferQueue { to: container })
+                                    .expect("unable to store item");
+                            });
+                            format!("putting things in {}", target)
+                        }
+                        Work::Craft(item_name) => {
+                            let is_by_fire = find_recipe(item_name).is_some_and(|recipe| {
+                                !recipe.needs_fire
+                                    || is_near_fire(tile.0, tile.1, &positions, &burners, &heats)
+                            });
+                            if !is_by_fire {
+                                let status = format!("needs a lit fire to make a {}", item_name);
+                                let worker = workers.get_mut(entity).unwrap();
+                                (worker.status, worker.is_stuck) = (status, true);
+                                continue;
+                            }
+
+                            wants_craft
+                                .insert(
+                                    entity,
+                                    CraftQueue {
+                                        item_name: item_name.to_string(),
+                                    },
+                                )
+                                .expect("unable to craft");
+                            format!("crafting a {}", item_name)
+                        }
+                        Work::PickUp(items) => {
+                            items.iter().for_each(|item| {
+                                wants_pickup
+                                    .insert(
+                                        *item,
+                                        PickupQueue {
+                                            collected_by: entity,
+                                            item: *item,
+                                        },
+                                    )
+                                    .expect("unable to pick up item");
+                            });
+                            claimed.extend(items.iter());
+                            match is_supplying {
+                                true => {
+                                    for item in items.iter() {
+                                        needed
+                                            .entry(name_of(*item))
+                                            .and_modify(|amount| *amount -= 1);
+                                    }
+                                    delivering.extend(items);
+                                }
+                                false => hauling.extend(items),
+                            }
+                            format!("picking up {}", target)
+                        }
+                        Work::Drop(items, spot) => {
+                            let (_, count) = stock.entry(spot).or_insert((name_of(items[0]), 0));
+                            *count += items.len();
+                            hauling.retain(|item| !items.contains(item));
+                            drops.extend(items.into_iter().map(|item| (item, spot)));
+                            format!("storing things in {}", target)
+                        }
+                    };
+                    (status, false, vec![])
+                }
+            };
+
+            let worker = workers.get_mut(entity).unwrap();
+            worker.status = status;
+            worker.is_stuck = is_stuck;
+            worker.path = path;
+            worker.hauling = hauling;
+            worker.delivering = delivering;
+        }
+
+        for (entity, (x, y)) in moves.into_iter() {
+            positions
+                .insert(entity, Position { x, y })
+                .expect("unable to move worker");
+        }
+
+        for (item, (x, y)) in drops.into_iter() {
+            backpack.remove(item);
+            positions
+                .insert(item, Position { x, y })
+                .expect("unable to drop item");
+        }
+
+        for (worker, render) in (&workers, &mut renderables).join() {
+            render.fg = match (worker.jobs.is_empty(), worker.is_stuck) {
+                (true, _) => RGB::named(GREY),
+                (false, true) => RGB::named(RED),
+                (false, false) => RGB::named(LIGHTGREEN),
+            };
+        }
+    }
+}